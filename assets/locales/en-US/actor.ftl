@@ -0,0 +1,34 @@
+actor-not-found = Sorry, we could not find your account.
+actor-inactive = Your account is inactive. Please contact your administrator for more information.
+actor-invalid-credentials = Invalid email or password.
+actor-email-taken = This email address is already registered.
+actor-email-empty = Please set your email address.
+actor-email-invalid = Please enter a valid email address.
+actor-email-max = Your email address must be at most ❛{ $max }❜ characters long.
+actor-password-empty = Please set your password.
+actor-password-min-max = Your password must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+actor-password-min = Your password must be at least ❛{ $min }❜ characters long.
+actor-password-max = Your password must be at most ❛{ $max }❜ characters long.
+actor-first-name-empty = Please set your first name.
+actor-first-name-min-max = Your first name must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+actor-first-name-min = Your first name must be at least ❛{ $min }❜ characters long.
+actor-first-name-max = Your first name must be at most ❛{ $max }❜ characters long.
+actor-last-name-empty = Please set your last name.
+actor-last-name-min-max = Your last name must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+actor-last-name-min = Your last name must be at least ❛{ $min }❜ characters long.
+actor-last-name-max = Your last name must be at most ❛{ $max }❜ characters long.
+actor-password-lowercase = Your password must contain at least one lowercase letter.
+actor-password-uppercase = Your password must contain at least one uppercase letter.
+actor-password-digit = Your password must contain at least one number.
+actor-password-symbol = Your password must contain at least one symbol.
+actor-password-breached = This password has appeared in a data breach. Please choose a different one.
+
+actor-email-verified = Your email address is already verified.
+actor-verification-sent = We sent a verification link to your email address.
+actor-verification-success = Your email address has been verified successfully.
+actor-verification-invalid = This verification link is invalid or has expired.
+actor-reset-password-sent = If an account exists for that email address, we sent a link to reset your password.
+actor-reset-password-success = Your password has been reset successfully. Please sign in again.
+actor-reset-password-invalid = This password reset link is invalid or has expired.
+actor-sign-in-locked = Too many failed sign in attempts. Your account is locked, please try again in ❛{ $minutes }❜ minutes.
+actor-sign-in-throttled = Too many failed sign in attempts from your network. Please try again in ❛{ $minutes }❜ minutes.
@@ -0,0 +1,14 @@
+api-key-scope-read = This api key is not allowed to run queries.
+api-key-scope-write = This api key is not allowed to run mutations.
+api-key-not-found = Sorry, we could not find that api key.
+api-key-revoke-success = The api key has been revoked successfully.
+api-key-forbidden = Api keys cannot be managed with an api key.
+api-key-owner-required = Please choose the actor that will own this api key.
+api-key-name-empty = Please set the api key name.
+api-key-name-min-max = The api key name must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+api-key-name-min = The api key name must be at least ❛{ $min }❜ characters long.
+api-key-name-max = The api key name must be at most ❛{ $max }❜ characters long.
+api-key-scopes-empty = Please choose at least one scope.
+api-key-scopes-invalid = Invalid scope, please choose between ❛controller❜, ❛read❜ or ❛write❜.
+api-key-scopes-forbidden = Only the controller can issue api keys with the ❛controller❜ scope.
+api-key-expires-at-invalid = The expiry date must be in the future.
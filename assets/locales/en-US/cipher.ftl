@@ -0,0 +1,2 @@
+cipher-reencrypt-failed = Unable to re-encrypt stored secrets, make sure the previous master key is still set.
+cipher-reencrypted = Re-encrypted settings ❛{ $modules }❜, ❛{ $mfa_secrets }❜ mfa secret(s) and re-indexed ❛{ $actors }❜ actor(s) under the current master key.
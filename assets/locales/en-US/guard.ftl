@@ -0,0 +1,3 @@
+guard-status-forbidden = Sorry, your account is ❛{ $status }❜ and is not allowed to perform this action.
+
+guard-mfa-required = Sorry, your role requires two-factor authentication. Please enable it to continue.
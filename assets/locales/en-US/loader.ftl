@@ -0,0 +1,2 @@
+loader-retrieve-failed = Unable to retrieve the data loaders
+loader-load-failed = Unable to load the related records, please try again.
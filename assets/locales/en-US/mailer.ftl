@@ -19,4 +19,19 @@ mailer-service-empty = Please set your smtp service.
 mailer-service-invalid = Invalid mailer service please choose between ❛MAILGUN❜ or ❛SES❜.
 mailer-service-min-max = Your smtp service must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
 mailer-service-min = Your smtp service must be at least ❛{ $min }❜ characters long.
-mailer-service-max = Your smtp service must be at most ❛{ $max }❜ characters long.
\ No newline at end of file
+mailer-service-max = Your smtp service must be at most ❛{ $max }❜ characters long.
+mailer-greeting = Hi { $name },
+mailer-ignore = If you did not request this, you can safely ignore this email.
+mailer-expiry = This link expires in ❛{ $minutes }❜ minutes.
+mailer-verification-subject = Verify your email address
+mailer-verification-title = Email Verification
+mailer-verification-message = Please confirm that this is your email address by clicking the button below.
+mailer-verification-action = Verify Email
+mailer-reset-password-subject = Reset your password
+mailer-reset-password-title = Password Reset
+mailer-reset-password-message = We received a request to reset your password. Click the button below to choose a new one.
+mailer-reset-password-action = Reset Password
+mailer-locked-subject = Your account has been temporarily locked
+mailer-locked-title = Account Locked
+mailer-locked-message = We locked your account for ❛{ $minutes }❜ minutes after too many failed sign in attempts. The last attempt came from ❛{ $ip }❜.
+mailer-locked-advice = If this was not you, we recommend resetting your password once the lock expires.
@@ -0,0 +1,2 @@
+message-room-forbidden = Sorry, you do not have access to this chat room.
+message-room-retrieve-failed = Unable to retrieve the chat room, please try again.
@@ -0,0 +1,8 @@
+mfa-enroll-failed = Unable to set up two-factor authentication. Please try again.
+mfa-already-enabled = Two-factor authentication is already enabled.
+mfa-not-enrolled = Please start two-factor authentication setup first.
+mfa-not-enabled = Two-factor authentication is not enabled.
+mfa-invalid-code = Invalid authentication code.
+mfa-disable-success = Two-factor authentication has been disabled.
+mfa-token-expired = Your sign in attempt has expired. Please sign in again.
+mfa-token-invalid = Invalid sign in attempt. Please sign in again.
@@ -0,0 +1,35 @@
+oauth-retrieve-failed = Unable to retrieve oauth settings
+oauth-update-failed = Failed to update oauth configuration...
+oauth-discovery-failed = We could not reach your identity provider, please check the issuer.
+oauth-state-invalid = Your sign in request is invalid or has expired, please try again.
+oauth-callback-failed = We could not sign you in with your identity provider, please try again.
+oauth-email-unverified = Your identity provider did not confirm your email address.
+oauth-link-required = An account already uses this email, please sign in and link your identity provider from your account.
+oauth-link-taken = This identity provider account is already linked to another account.
+oauth-link-success = Your identity provider has been linked to your account.
+oauth-provider-empty = Please set your provider name.
+oauth-provider-min-max = Your provider name must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+oauth-provider-min = Your provider name must be at least ❛{ $min }❜ characters long.
+oauth-provider-max = Your provider name must be at most ❛{ $max }❜ characters long.
+oauth-issuer-empty = Please set your issuer.
+oauth-issuer-min-max = Your issuer must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+oauth-issuer-min = Your issuer must be at least ❛{ $min }❜ characters long.
+oauth-issuer-max = Your issuer must be at most ❛{ $max }❜ characters long.
+oauth-issuer-invalid = Your issuer must be an absolute url.
+oauth-client-id-empty = Please set your client id.
+oauth-client-id-min-max = Your client id must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+oauth-client-id-min = Your client id must be at least ❛{ $min }❜ characters long.
+oauth-client-id-max = Your client id must be at most ❛{ $max }❜ characters long.
+oauth-client-secret-empty = Please set your client secret.
+oauth-client-secret-min-max = Your client secret must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+oauth-client-secret-min = Your client secret must be at least ❛{ $min }❜ characters long.
+oauth-client-secret-max = Your client secret must be at most ❛{ $max }❜ characters long.
+oauth-redirect-uri-empty = Please set your redirect uri.
+oauth-redirect-uri-min-max = Your redirect uri must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+oauth-redirect-uri-min = Your redirect uri must be at least ❛{ $min }❜ characters long.
+oauth-redirect-uri-max = Your redirect uri must be at most ❛{ $max }❜ characters long.
+oauth-redirect-uri-invalid = Your redirect uri must be an absolute url.
+oauth-scopes-empty = Please set your scopes.
+oauth-scopes-min-max = Your scopes must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+oauth-scopes-min = Your scopes must be at least ❛{ $min }❜ characters long.
+oauth-scopes-max = Your scopes must be at most ❛{ $max }❜ characters long.
@@ -31,4 +31,6 @@ paseto-refresh-token-key-time-max = Your refresh token key time must be at most
 paseto-refresh-token-key-signing-empty = Please set your refresh token key signing.
 paseto-refresh-token-key-signing-min-max = Your refresh token key signing must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
 paseto-refresh-token-key-signing-min = Your refresh token key signing must be at least ❛{ $min }❜ characters long.
-paseto-refresh-token-key-signing-max = Your refresh token key signing must be at most ❛{ $max }❜ characters long.
\ No newline at end of file
+paseto-refresh-token-key-signing-max = Your refresh token key signing must be at most ❛{ $max }❜ characters long.
+paseto-token-purpose-empty = Please set your token purpose.
+paseto-token-purpose-invalid = Your token purpose must be either ❛local❜ or ❛public❜.
@@ -0,0 +1,2 @@
+password-retrieve-failed = Unable to retrieve password policy
+password-hash-failed = Unable to secure your password. Please try again.
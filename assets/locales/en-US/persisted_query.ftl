@@ -0,0 +1,5 @@
+persisted-query-invalid = Invalid ❛persistedQuery❜ extension, expected a version and a sha256 hash.
+persisted-query-version-unsupported = Only version ❛1❜ of persisted queries is supported.
+persisted-query-hash-mismatch = The provided sha256 hash does not match the query.
+persisted-query-not-allowed = Sorry, this query is not registered and cannot be executed.
+persisted-query-get-mutation = Mutations must be sent over POST.
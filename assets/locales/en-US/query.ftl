@@ -0,0 +1,4 @@
+query-limit-depth = Your query is nested ❛{ $actual }❜ levels deep, the maximum is ❛{ $max }❜.
+query-limit-complexity = Your query has a complexity of ❛{ $actual }❜, the maximum is ❛{ $max }❜.
+query-limit-aliases = Your query uses ❛{ $actual }❜ aliases, the maximum is ❛{ $max }❜.
+query-limit-root-fields = Your query selects ❛{ $actual }❜ root fields, the maximum is ❛{ $max }❜.
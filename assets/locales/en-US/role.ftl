@@ -0,0 +1,13 @@
+role-not-found = Sorry, we could not find that role.
+role-description-max = The role description must be at most ❛{ $max }❜ characters long.
+permission-retrieve-failed = Unable to retrieve permission settings
+permission-reload-failed = Unable to reload role permissions, please try again.
+permission-not-found = Sorry, we could not find that permission.
+permission-delete-success = The permission has been deleted successfully.
+permission-name-taken = A permission with that name already exists.
+permission-name-empty = Please set the permission name.
+permission-name-min-max = The permission name must be between ❛{ $min }❜ and ❛{ $max }❜ characters long.
+permission-name-min = The permission name must be at least ❛{ $min }❜ characters long.
+permission-name-max = The permission name must be at most ❛{ $max }❜ characters long.
+permission-name-invalid = The permission name must look like ❛resource:action❜, e.g. ❛company:update❜.
+permission-description-max = The permission description must be at most ❛{ $max }❜ characters long.
@@ -0,0 +1,14 @@
+session-expired = We're sorry, but your authentication token has expired. Please sign in again to continue.
+session-unauthenticated = Please sign in to continue.
+session-sign-out-success = You have been signed out successfully.
+session-refresh-expired = Your refresh token has expired. Please sign in again to continue.
+session-refresh-invalid = Invalid refresh token. Please sign in again to continue.
+session-refresh-reused = This refresh token has already been used. All of your sessions have been signed out for your protection.
+session-retrieve-failed = Unable to retrieve session settings
+session-not-found = Sorry, we could not find that session.
+session-revoke-success = The session has been signed out successfully.
+session-revoke-others-success = ❛{ $count }❜ other session(s) have been signed out successfully.
+session-impersonation-not-found = Sorry, we could not find the account to impersonate.
+session-impersonation-denied = You are not allowed to impersonate this account.
+session-impersonation-forbidden = This action is not allowed while impersonating another account.
+session-impersonation-audit-failed = Unable to record the impersonated request, please try again.
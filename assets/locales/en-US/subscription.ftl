@@ -0,0 +1,3 @@
+subscription-retrieve-failed = Unable to retrieve subscription settings
+subscription-token-expired = Your authentication token has expired, please sign in again before subscribing.
+subscription-token-invalid = Your authentication token is invalid, unable to open a subscription.
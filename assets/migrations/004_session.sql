@@ -0,0 +1,9 @@
+-------------------------------
+----- ALTER SESSION TABLE -----
+-------------------------------
+ALTER TABLE session
+    ADD COLUMN refresh_token_hash CHARACTER VARYING(64) COLLATE __gl_numeric DEFAULT NULL::CHARACTER VARYING,
+    ADD COLUMN refreshed_at TIMESTAMP WITH TIME ZONE DEFAULT NULL;
+
+---- CREATE SESSION INDEXES ----
+CREATE INDEX idx_session_expires_at ON session USING btree (expires_at);
\ No newline at end of file
@@ -0,0 +1,5 @@
+-------------------------------
+---- ALTER ACTOR INDEXES ------
+-------------------------------
+CREATE INDEX idx_actor_account_verification_hash ON actor USING btree ((account_verification->'token'->>'hash'));
+CREATE INDEX idx_actor_account_reset_password_hash ON actor USING btree ((account_reset_password->>'hash'));
@@ -0,0 +1,12 @@
+-------------------------------
+--- CREATE SIGN IN ATTEMPT ----
+-------------------------------
+-- Failed sign in attempts per ip, including emails that belong to no actor
+CREATE TABLE sign_in_attempt (
+    ip CHARACTER VARYING(100) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    attempts BIGINT NOT NULL DEFAULT 0,
+    last_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+---- CREATE SIGN IN ATTEMPT INDEXES ----
+CREATE INDEX idx_sign_in_attempt_last_attempt_at ON sign_in_attempt USING btree (last_attempt_at);
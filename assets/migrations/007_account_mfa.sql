@@ -0,0 +1,5 @@
+-------------------------------
+------ ALTER ACTOR TABLE ------
+-------------------------------
+ALTER TABLE actor
+    ADD COLUMN account_mfa JSONB DEFAULT NULL;
@@ -0,0 +1,30 @@
+-------------------------------
+---- CREATE API KEY TABLE -----
+-------------------------------
+CREATE TABLE api_key (
+    id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    cursor BIGSERIAL UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    actor_id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL,
+    name CHARACTER VARYING(100) COLLATE __gl_numeric NOT NULL,
+    key_prefix CHARACTER VARYING(16) COLLATE __gl_numeric NOT NULL,
+    key_hash CHARACTER VARYING(64) COLLATE __gl_numeric NOT NULL UNIQUE,
+    scopes TEXT[] NOT NULL DEFAULT '{}',
+    expires_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    last_used_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE DEFAULT NULL
+);
+
+---- CREATE API KEY INDEXES ----
+CREATE INDEX idx_api_key_actor_id ON api_key USING btree (actor_id);
+CREATE INDEX idx_api_key_created_at ON api_key USING btree (created_at);
+
+---- CREATE API KEY TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON api_key FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+CREATE TRIGGER set_updated_at_insert BEFORE INSERT ON api_key FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+CREATE TRIGGER set_updated_at_update BEFORE UPDATE ON api_key FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+
+---- CREATE API KEY CONSTRAINTS ----
+ALTER TABLE ONLY api_key
+    ADD CONSTRAINT fk_api_key_actor FOREIGN KEY (actor_id) REFERENCES actor(id) ON DELETE CASCADE;
@@ -0,0 +1,28 @@
+-----------------------------------
+---- CREATE ACTOR OAUTH TABLE -----
+-----------------------------------
+CREATE TABLE actor_oauth (
+    id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    cursor BIGSERIAL UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    actor_id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL,
+    provider CHARACTER VARYING(50) COLLATE __gl_numeric NOT NULL,
+    issuer CHARACTER VARYING(255) COLLATE __gl_numeric NOT NULL,
+    subject CHARACTER VARYING(255) COLLATE __gl_numeric NOT NULL,
+    email CHARACTER VARYING(255) COLLATE __gl_numeric DEFAULT NULL,
+    last_sign_in_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    UNIQUE (issuer, subject)
+);
+
+---- CREATE ACTOR OAUTH INDEXES ----
+CREATE INDEX idx_actor_oauth_actor_id ON actor_oauth USING btree (actor_id);
+
+---- CREATE ACTOR OAUTH TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON actor_oauth FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+CREATE TRIGGER set_updated_at_insert BEFORE INSERT ON actor_oauth FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+CREATE TRIGGER set_updated_at_update BEFORE UPDATE ON actor_oauth FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+
+---- CREATE ACTOR OAUTH CONSTRAINTS ----
+ALTER TABLE ONLY actor_oauth
+    ADD CONSTRAINT fk_actor_oauth_actor FOREIGN KEY (actor_id) REFERENCES actor(id) ON DELETE CASCADE;
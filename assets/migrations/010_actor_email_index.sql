@@ -0,0 +1,6 @@
+-------------------------------
+------ ALTER ACTOR TABLE ------
+-------------------------------
+-- Blind index of the email so lookups & uniqueness keep working once the email is encrypted
+ALTER TABLE actor
+    ADD COLUMN email_index CHARACTER VARYING(64) COLLATE __gl_numeric DEFAULT NULL UNIQUE;
\ No newline at end of file
@@ -0,0 +1,80 @@
+-------------------------------
+------- CREATE ROLE TABLE -----
+-------------------------------
+-- Role names match the role stored on actors, e.g. ❛ADMIN❜, no other role can be assigned
+CREATE TABLE role (
+    id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    cursor BIGSERIAL UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    name CHARACTER VARYING(50) COLLATE __gl_numeric NOT NULL UNIQUE,
+    description CHARACTER VARYING(300) COLLATE __gl_numeric DEFAULT NULL
+);
+
+---- CREATE ROLE INDEXES ----
+CREATE INDEX idx_role_created_at ON role USING btree (created_at);
+
+---- CREATE ROLE TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON role FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+CREATE TRIGGER set_updated_at_insert BEFORE INSERT ON role FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+CREATE TRIGGER set_updated_at_update BEFORE UPDATE ON role FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+
+---- CREATE ROLE CONSTRAINTS ----
+ALTER TABLE ONLY role
+    ADD CONSTRAINT chk_role_name CHECK (name IN ('CONTROLLER', 'ADMIN', 'GUEST'));
+
+-------------------------------
+---- CREATE PERMISSION TABLE --
+-------------------------------
+-- Permission names are ❛resource:action❜, e.g. ❛company:update❜
+CREATE TABLE permission (
+    id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    cursor BIGSERIAL UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    name CHARACTER VARYING(100) COLLATE __gl_numeric NOT NULL UNIQUE,
+    description CHARACTER VARYING(300) COLLATE __gl_numeric DEFAULT NULL
+);
+
+---- CREATE PERMISSION INDEXES ----
+CREATE INDEX idx_permission_created_at ON permission USING btree (created_at);
+
+---- CREATE PERMISSION TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON permission FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+CREATE TRIGGER set_updated_at_insert BEFORE INSERT ON permission FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+CREATE TRIGGER set_updated_at_update BEFORE UPDATE ON permission FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+
+-------------------------------
+-- CREATE ROLE PERMISSION TABLE --
+-------------------------------
+CREATE TABLE role_permission (
+    role_id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL,
+    permission_id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    PRIMARY KEY (role_id, permission_id)
+);
+
+---- CREATE ROLE PERMISSION INDEXES ----
+CREATE INDEX idx_role_permission_permission_id ON role_permission USING btree (permission_id);
+
+---- CREATE ROLE PERMISSION TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON role_permission FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+
+---- CREATE ROLE PERMISSION CONSTRAINTS ----
+ALTER TABLE ONLY role_permission
+    ADD CONSTRAINT fk_role_permission_role FOREIGN KEY (role_id) REFERENCES role(id) ON DELETE CASCADE;
+
+ALTER TABLE ONLY role_permission
+    ADD CONSTRAINT fk_role_permission_permission FOREIGN KEY (permission_id) REFERENCES permission(id) ON DELETE CASCADE;
+
+-------------------------------
+----- SEED BUILT-IN ROLES -----
+-------------------------------
+INSERT INTO role (id, name, description) VALUES
+    ('controller', 'CONTROLLER', 'Holds every permission'),
+    ('admin', 'ADMIN', NULL),
+    ('guest', 'GUEST', NULL);
+
+-- Permissions checked by the resolvers, granted to roles through ❛grant❜
+INSERT INTO permission (id, name, description) VALUES
+    ('role-read', 'role:read', 'List roles & permissions');
@@ -0,0 +1,72 @@
+-------------------------------
+--- CREATE TENANT FUNCTIONS ---
+-------------------------------
+-- Company of the current scope, set with ❛app.company_id❜.
+CREATE FUNCTION __gl_tenant_company_id() RETURNS CHARACTER VARYING
+    LANGUAGE sql STABLE
+AS $$
+    SELECT NULLIF(current_setting('app.company_id', true), '');
+$$;
+
+-- Actor of the current scope, set with ❛app.actor_id❜. Actors always see their own row.
+CREATE FUNCTION __gl_tenant_actor_id() RETURNS CHARACTER VARYING
+    LANGUAGE sql STABLE
+AS $$
+    SELECT NULLIF(current_setting('app.actor_id', true), '');
+$$;
+
+-- Unscoped (controller or system) work must opt in with ❛app.tenant_bypass = on❜.
+CREATE FUNCTION __gl_tenant_bypass() RETURNS BOOLEAN
+    LANGUAGE sql STABLE
+AS $$
+    SELECT COALESCE(current_setting('app.tenant_bypass', true), '') = 'on';
+$$;
+
+-- Rows are denied unless they belong to the company of the scope or the tenant is bypassed
+CREATE FUNCTION __gl_tenant_allows(company_id CHARACTER VARYING) RETURNS BOOLEAN
+    LANGUAGE sql STABLE
+AS $$
+    SELECT __gl_tenant_bypass() OR COALESCE(company_id = __gl_tenant_company_id(), false);
+$$;
+
+-------------------------------
+----- ENABLE TENANT POLICIES ---
+-------------------------------
+-- Policies are forced so they also apply to the table owner the server connects as.
+-- Superusers & roles with BYPASSRLS skip them, the server must not connect as one.
+ALTER TABLE company ENABLE ROW LEVEL SECURITY;
+ALTER TABLE company FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON company
+    USING (__gl_tenant_allows(id))
+    WITH CHECK (__gl_tenant_allows(id));
+
+ALTER TABLE address ENABLE ROW LEVEL SECURITY;
+ALTER TABLE address FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON address
+    USING (__gl_tenant_allows(company_id))
+    WITH CHECK (__gl_tenant_allows(company_id));
+
+ALTER TABLE actor ENABLE ROW LEVEL SECURITY;
+ALTER TABLE actor FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON actor
+    USING (__gl_tenant_allows(company_id) OR id = __gl_tenant_actor_id())
+    WITH CHECK (__gl_tenant_allows(company_id) OR (id = __gl_tenant_actor_id() AND company_id IS NULL));
+
+ALTER TABLE chat_room ENABLE ROW LEVEL SECURITY;
+ALTER TABLE chat_room FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON chat_room
+    USING (__gl_tenant_allows(company_id))
+    WITH CHECK (__gl_tenant_allows(company_id));
+
+ALTER TABLE ticket ENABLE ROW LEVEL SECURITY;
+ALTER TABLE ticket FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON ticket
+    USING (__gl_tenant_allows(company_id))
+    WITH CHECK (__gl_tenant_allows(company_id));
+
+-- Keys follow the visibility of their actor
+ALTER TABLE api_key ENABLE ROW LEVEL SECURITY;
+ALTER TABLE api_key FORCE ROW LEVEL SECURITY;
+CREATE POLICY tenant_isolation ON api_key
+    USING (EXISTS (SELECT 1 FROM actor WHERE actor.id = api_key.actor_id))
+    WITH CHECK (EXISTS (SELECT 1 FROM actor WHERE actor.id = api_key.actor_id));
@@ -0,0 +1,27 @@
+-------------------------------
+-- CREATE IMPERSONATION AUDIT --
+-------------------------------
+-- One row when an impersonation starts & one per request made with its token
+CREATE TABLE impersonation_audit (
+    id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    cursor BIGSERIAL UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    impersonator_id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL,
+    actor_id CHARACTER VARYING(32) COLLATE __gl_numeric NOT NULL,
+    session_id CHARACTER VARYING(32) COLLATE __gl_numeric DEFAULT NULL,
+    operation_type CHARACTER VARYING(50) COLLATE __gl_numeric NOT NULL,
+    operation_name CHARACTER VARYING(200) COLLATE __gl_numeric DEFAULT NULL,
+    query TEXT COLLATE __gl_numeric DEFAULT NULL,
+    ip CHARACTER VARYING(100) COLLATE __gl_numeric DEFAULT NULL
+);
+
+---- CREATE IMPERSONATION AUDIT INDEXES ----
+CREATE INDEX idx_impersonation_audit_impersonator_id ON impersonation_audit USING btree (impersonator_id);
+CREATE INDEX idx_impersonation_audit_actor_id ON impersonation_audit USING btree (actor_id);
+CREATE INDEX idx_impersonation_audit_created_at ON impersonation_audit USING btree (created_at);
+
+---- CREATE IMPERSONATION AUDIT TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON impersonation_audit FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+CREATE TRIGGER set_updated_at_insert BEFORE INSERT ON impersonation_audit FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+CREATE TRIGGER set_updated_at_update BEFORE UPDATE ON impersonation_audit FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
@@ -0,0 +1,34 @@
+----- CREATE NOTIFY FUNCTIONS -----
+-- Payloads only carry identifiers, subscribers load the rows they are allowed to see.
+-- Notifications are delivered on commit, so rolled back changes never reach subscribers.
+CREATE FUNCTION __gl_notify_settings_changed() RETURNS trigger
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+    PERFORM pg_notify('settings_changed', json_build_object(
+        'module', NEW.module,
+        'updatedAt', NEW.updated_at
+    )::TEXT);
+
+    RETURN NEW;
+END;
+$$;
+
+CREATE FUNCTION __gl_notify_message_created() RETURNS trigger
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+    IF NEW.chat_room_id IS NOT NULL THEN
+        PERFORM pg_notify('message_created', json_build_object(
+            'id', NEW.id,
+            'chatRoomId', NEW.chat_room_id
+        )::TEXT);
+    END IF;
+
+    RETURN NEW;
+END;
+$$;
+
+----- CREATE NOTIFY TRIGGERS -----
+CREATE TRIGGER notify_settings_changed AFTER INSERT OR UPDATE ON settings FOR EACH ROW EXECUTE FUNCTION __gl_notify_settings_changed();
+CREATE TRIGGER notify_message_created AFTER INSERT ON message FOR EACH ROW EXECUTE FUNCTION __gl_notify_message_created();
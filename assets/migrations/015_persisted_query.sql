@@ -0,0 +1,19 @@
+---------------------------------------
+---- CREATE PERSISTED QUERY TABLE -----
+---------------------------------------
+-- Id is the lowercase hex sha256 of the query, only used when ❛PERSISTED_QUERY_STORE=postgres❜
+CREATE TABLE persisted_query (
+    id CHARACTER VARYING(64) COLLATE __gl_numeric NOT NULL PRIMARY KEY,
+    cursor BIGSERIAL UNIQUE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT NULL,
+    query TEXT COLLATE __gl_numeric NOT NULL
+);
+
+---- CREATE PERSISTED QUERY INDEXES ----
+CREATE INDEX idx_persisted_query_created_at ON persisted_query USING btree (created_at);
+
+---- CREATE PERSISTED QUERY TRIGGERS ----
+CREATE TRIGGER set_created_at_insert BEFORE INSERT ON persisted_query FOR EACH ROW EXECUTE FUNCTION __gl_created_at_now();
+CREATE TRIGGER set_updated_at_insert BEFORE INSERT ON persisted_query FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
+CREATE TRIGGER set_updated_at_update BEFORE UPDATE ON persisted_query FOR EACH ROW EXECUTE FUNCTION __gl_updated_at_now();
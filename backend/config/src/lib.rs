@@ -25,6 +25,9 @@ pub const BASE_PATH: &str = "";
 pub const SERVER_ADDRESS: &str = "0.0.0.0";
 pub const SERVER_PORT: u16 = 9020; // Set your port here
 
+/// Proxies allowed to set the client ip through the Forwarded & X-Forwarded-For headers
+pub const TRUSTED_PROXIES: [&str; 0] = []; // e.g. ["127.0.0.1"] behind a local reverse proxy
+
 /// Set assets variables
 pub const FROM_FAVICON: &str = "/favicon.ico";
 pub const FROM_STATIC: &str = "/static";
@@ -33,9 +36,20 @@ pub const PATH_FAVICON: &str = "assets/static/media/favicon.ico";
 pub const PATH_STATIC: &str = "assets/static";
 pub const PATH_ERROR_404: &str = "assets/templates/errors/404.html";
 
+/// Account related variables
+pub const ACCOUNT_VERIFICATION_TTL: i64 = 1440; // Minutes
+pub const ACCOUNT_VERIFICATION_PATH: &str = "/verify-email";
+pub const ACCOUNT_RESET_PASSWORD_TTL: i64 = 60; // Minutes
+pub const ACCOUNT_RESET_PASSWORD_PATH: &str = "/reset-password";
+
+/// API key related variables
+pub const API_KEY_PREFIX: &str = "ak_";
+pub const API_KEY_SCOPES: [&str; 3] = ["controller", "read", "write"];
+pub const API_KEY_LAST_USED_INTERVAL: i32 = 60; // Seconds between last used updates
+
 /// Set attempt variables
 pub const ATTEMPT_RETRY_MAX: usize = 5;
-pub const ATTEMPT_RETRY_DURATION: usize = 30;
+pub const ATTEMPT_RETRY_DURATION: usize = 30; // Minutes
 
 /// Set catchers variables
 pub const CATCHER_CACHE_DIRECTIVES: u32 = 86400u32;
@@ -52,10 +66,18 @@ pub const CRON_DURATION: &str = "0 0 */1 * * * *"; // Every Hour
 // pub const CRON_DURATION: &str = "0 */1 * * * * *"; // every minute
 // pub const CRON_DURATION: &str = "0 */2 * * * * *"; // every 2 minutes
 
+/// Filter related variables
+pub const FILTER_MAX_DEPTH: usize = 4;
+pub const FILTER_MAX_ORDERS: usize = 3;
+pub const FILTER_MAX_VALUES: usize = 100;
+
 /// Set handlebars variables
 pub const HANDLEBARS_ASSET_PATH: &str = "./assets/templates";
 pub const HANDLEBARS_EXTENSION: &str = ".hbs";
 
+/// Impersonation related variables
+pub const IMPERSONATION_TOKEN_TTL: i64 = 15; // Minutes
+
 /// Locales related variables
 pub const LOCALES_PATH: &str = "./assets/locales/";
 pub const LOCALES_US: &str = "en-US";
@@ -66,7 +88,25 @@ pub const MAILER_FROM_NO_REPLY: &str = "My Server <no-reply@my-server.com>";
 pub const MAILER_FROM_SUCCESS: &str = "My Server <success@my-server.com>";
 pub const MAILER_TO_CONTROLLER: &str = "markhenry.liwag@gmail.com";
 
+/// Multi-factor authentication related variables
+pub const MFA_TOKEN_TTL: i64 = 5; // Minutes
+pub const MFA_RECOVERY_CODES: usize = 10;
+pub const MFA_REQUIRED_ROLES: [&str; 1] = ["ADMIN"];
+
+/// OAuth related variables
+pub const OAUTH_STATE_TTL: i64 = 10; // Minutes
+pub const OAUTH_BINDING_COOKIE: &str = "oauth_binding";
+pub const OAUTH_DEFAULT_SCOPES: &str = "openid email profile";
+pub const OAUTH_HTTP_TIMEOUT: u64 = 10; // Seconds
+pub const OAUTH_LEEWAY: u64 = 60; // Seconds of clock skew accepted on id tokens
+
+/// Pagination related variables
+pub const PAGINATION_DEFAULT_LIMIT: usize = 20;
+pub const PAGINATION_MAX_LIMIT: usize = 100;
+
 /// Paseto defaults
+pub const PASETO_KEYS_MAX_AGE: u64 = 300; // Seconds verifiers may cache the public keys
+pub const PASETO_LEEWAY: i64 = 60; // Seconds of clock skew accepted on iat & nbf
 pub const PASETO_ACCESS_TOKEN_KEY_UNIT: &str = "120";
 pub const PASETO_ACCESS_TOKEN_KEY_TIME: &str = "Days";
 pub const PASETO_ACCESS_TOKEN_KEY_SIGNING: &str = ""; // Use generator here
@@ -74,8 +114,47 @@ pub const PASETO_REFRESH_TOKEN_KEY_UNIT: &str = "180";
 pub const PASETO_REFRESH_TOKEN_KEY_TIME: &str = "Days";
 pub const PASETO_REFRESH_TOKEN_KEY_SIGNING: &str = ""; // Use generator here
 
+/// Password related variables
+pub const PASSWORD_MEMORY_COST: u32 = 19_456; // KiB
+pub const PASSWORD_TIME_COST: u32 = 2;
+pub const PASSWORD_PARALLELISM: u32 = 1;
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+pub const PASSWORD_MAX_LENGTH: usize = 100;
+pub const PASSWORD_REQUIRE_LOWERCASE: bool = true;
+pub const PASSWORD_REQUIRE_UPPERCASE: bool = true;
+pub const PASSWORD_REQUIRE_DIGIT: bool = true;
+pub const PASSWORD_REQUIRE_SYMBOL: bool = false;
+pub const PASSWORD_BREACHED_PATH: &str = "./assets/passwords/breached.txt";
+
+/// Session cache related variables
+pub const SESSION_CACHE_TTL: u64 = 30; // Seconds
+pub const SESSION_CACHE_CAPACITY: usize = 10_000;
+
+/// Persisted query related variables
+pub const PERSISTED_QUERY_CACHE_CAPACITY: usize = 1_000;
+pub const PERSISTED_QUERY_MANIFEST_PATH: &str = "./assets/persisted_queries.json"; // Allow-list read in strict mode
+pub const PERSISTED_QUERY_MAX_LENGTH: usize = 20_000; // Bytes, larger queries run but are never registered
+pub const PERSISTED_QUERY_STORE_CAPACITY: i64 = 10_000; // Stored rows kept by the cleanup, newest first
+pub const PERSISTED_QUERY_STORE_TTL: i64 = 30; // Days
+
+/// Permission cache related variables
+pub const PERMISSION_CACHE_TTL: u64 = 60; // Seconds
+
+/// GraphQL query limits, the depth leaves room for the playground introspection query
+pub const QUERY_MAX_DEPTH: usize = 15;
+pub const QUERY_MAX_COMPLEXITY: usize = 500;
+pub const QUERY_MAX_ALIASES: usize = 30;
+pub const QUERY_MAX_ROOT_FIELDS: usize = 15;
+pub const QUERY_LIST_COST: usize = 10; // Rows a list field is assumed to return when costing its selection
+
 /// Sentry related variables
 pub const SENTRY_URL: &str = "";
 
+/// TOTP related variables
+pub const TOTP_DIGITS: u32 = 6;
+pub const TOTP_PERIOD: u64 = 30; // Seconds
+pub const TOTP_SKEW: u64 = 1; // Steps accepted before & after the current one
+pub const TOTP_SECRET_LENGTH: usize = 20; // Bytes
+
 /// User Agent Parser related variables
 pub const USER_AGENT_REGEXES: &str = "./assets/regexes.yaml";
\ No newline at end of file
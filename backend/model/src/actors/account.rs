@@ -0,0 +1,47 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::hashes::sha256;
+
+/// Single-use token stored as a sha256 hash, the raw value is only ever sent by email
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountToken {
+    pub hash: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl AccountToken {
+    /// Generate a new token valid for `ttl` minutes, returns the raw token and its stored form
+    pub fn generate(ttl: i64) -> (String, Self) {
+        let token = nanoid::nanoid!(64);
+        let now = Utc::now();
+
+        let account_token = Self {
+            hash: sha256(&token),
+            created_at: now,
+            expires_at: now + Duration::minutes(ttl),
+        };
+
+        (token, account_token)
+    }
+}
+
+/// Value of `actor.account_verification`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountVerification {
+    pub verified_at: Option<DateTime<Utc>>,
+    pub token: Option<AccountToken>,
+}
+
+impl AccountVerification {
+    /// Verification of an email address already confirmed elsewhere
+    pub fn verified() -> Self {
+        Self {
+            verified_at: Some(Utc::now()),
+            token: None,
+        }
+    }
+}
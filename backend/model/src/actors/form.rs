@@ -0,0 +1,129 @@
+use async_graphql::{Context, MaybeUndefined, InputObject, Result};
+use serde::{Serialize, Deserialize};
+
+use library::{Core, Errors, Validator, Response};
+use macros::{AsForm, SetIsEmpty};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignUp {
+    pub email: String,
+    pub password: String,
+    pub first_name: String,
+    pub last_name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
+#[derive(AsForm, SetIsEmpty)]
+#[form(to = crate::actors::SignUp, error = "SignUpError")]
+#[serde(rename_all = "camelCase")]
+pub struct SignUpForm {
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub email: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[error(String)]
+    pub password: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub first_name: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub last_name: MaybeUndefined<String>,
+}
+
+impl SignUpForm {
+    pub fn validate(&mut self, ctx: &Context<'_>) -> Result<&mut Self> {
+        let locale = Core::locales(ctx)?;
+        let policy = Core::passwords(ctx)?;
+        let data = self.sanitize();
+
+        let error = SignUpError {
+            email: Validator::new(locale, "actor-email")
+                .set_max(300)
+                .set_as_required(true)
+                .set_string_value(&data.email)
+                .validate_email(),
+            password: Validator::new(locale, "actor-password")
+                .set_as_required(true)
+                .set_string_value(&data.password)
+                .validate_password(policy),
+            first_name: Validator::new(locale, "actor-first-name")
+                .set_min(1)
+                .set_max(300)
+                .set_as_required(true)
+                .set_string_value(&data.first_name)
+                .validate_string(),
+            last_name: Validator::new(locale, "actor-last-name")
+                .set_min(1)
+                .set_max(300)
+                .set_as_required(true)
+                .set_string_value(&data.last_name)
+                .validate_string()
+        };
+
+        let response = Response::BadRequest;
+
+        match error.is_empty() {
+            true => Ok(data),
+            false => Err(Errors::to(response, error))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignIn {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
+#[derive(AsForm, SetIsEmpty)]
+#[form(to = crate::actors::SignIn, error = "SignInError")]
+#[serde(rename_all = "camelCase")]
+pub struct SignInForm {
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub email: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[error(String)]
+    pub password: MaybeUndefined<String>,
+}
+
+impl SignInForm {
+    pub fn validate(&mut self, ctx: &Context<'_>) -> Result<&mut Self> {
+        let locale = Core::locales(ctx)?;
+        let policy = Core::passwords(ctx)?;
+        let data = self.sanitize();
+
+        let error = SignInError {
+            email: Validator::new(locale, "actor-email")
+                .set_max(300)
+                .set_as_required(true)
+                .set_string_value(&data.email)
+                .validate_email(),
+            // Bounds the work handed to argon2, the length is in bytes & a character takes at most four
+            password: Validator::new(locale, "actor-password")
+                .set_max(policy.max_length * 4)
+                .set_as_required(true)
+                .set_string_value(&data.password)
+                .validate_string()
+        };
+
+        let response = Response::BadRequest;
+
+        match error.is_empty() {
+            true => Ok(data),
+            false => Err(Errors::to(response, error))
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::{Cipher, Totp};
+use library::hashes::sha256;
+use library::totp::{generate_recovery_codes, normalize_recovery_code};
+
+/// Value of `actor.account_mfa`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMfa {
+    /// Base32 totp secret encrypted with the master key
+    pub secret: String,
+    pub enabled_at: Option<DateTime<Utc>>,
+    /// sha256 hashes of unused recovery codes
+    #[serde(default)]
+    pub recovery_codes: Vec<String>,
+    pub last_used_step: Option<u64>,
+}
+
+/// Returned once when enrolling, the secret is never readable afterwards
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct MfaEnrollment {
+    pub secret: String,
+    pub uri: String,
+}
+
+impl AccountMfa {
+    /// Create a pending enrollment for the totp secret
+    pub fn new(totp: &Totp) -> Result<Self> {
+        let secret = Cipher::from(totp.to_base32())
+            .set_as_decrypted()
+            .encrypt()?
+            .b64encode()?;
+
+        Ok(Self {
+            secret,
+            ..Default::default()
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled_at.is_some()
+    }
+
+    /// Seal the secret again under the current master key
+    pub fn reencrypt(&mut self) -> Result<&mut Self> {
+        self.secret = Cipher::from(&self.secret)
+            .decrypt()?
+            .encrypt()?
+            .b64encode()?;
+
+        Ok(self)
+    }
+
+    pub fn totp(&self) -> Result<Totp> {
+        let secret = Cipher::from(&self.secret)
+            .decrypt()?
+            .to_string()?;
+
+        Totp::from_base32(&secret)
+    }
+
+    /// Verify totp code, rejecting codes from an already used time step
+    pub fn verify_code(&mut self, code: &str) -> bool {
+        let Ok(totp) = self.totp() else {
+            return false;
+        };
+
+        match totp.verify(code) {
+            Some(step) if self.last_used_step.is_none_or(|last| step > last) => {
+                self.last_used_step = Some(step);
+                true
+            },
+            _ => false
+        }
+    }
+
+    /// Consume recovery code if it matches an unused one
+    pub fn use_recovery_code(&mut self, code: &str) -> bool {
+        let hash = sha256(normalize_recovery_code(code));
+        let count = self.recovery_codes.len();
+
+        self.recovery_codes.retain(|recovery_code| *recovery_code != hash);
+        self.recovery_codes.len() < count
+    }
+
+    /// Verify either a totp code or a recovery code
+    pub fn verify(&mut self, code: &str) -> bool {
+        self.verify_code(code) || self.use_recovery_code(code)
+    }
+
+    /// Enable mfa and replace recovery codes, returns the raw codes
+    pub fn enable(&mut self) -> Vec<String> {
+        self.enabled_at = Some(Utc::now());
+        self.regenerate_recovery_codes()
+    }
+
+    pub fn regenerate_recovery_codes(&mut self) -> Vec<String> {
+        let codes = generate_recovery_codes(config::MFA_RECOVERY_CODES);
+
+        self.recovery_codes = codes.iter()
+            .map(|code| sha256(normalize_recovery_code(code)))
+            .collect();
+
+        codes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovery_codes_match_with_or_without_dash() {
+        let mut mfa = AccountMfa::default();
+        let codes = mfa.enable();
+
+        assert!(mfa.use_recovery_code(&codes[0]));
+        assert!(mfa.use_recovery_code(&codes[1].replace('-', "").to_uppercase()));
+        assert!(mfa.use_recovery_code(&codes[2].replace('-', " ")));
+        assert_eq!(mfa.recovery_codes.len(), config::MFA_RECOVERY_CODES - 3);
+    }
+
+    #[test]
+    fn recovery_codes_are_single_use() {
+        let mut mfa = AccountMfa::default();
+        let codes = mfa.enable();
+
+        assert!(mfa.use_recovery_code(&codes[0]));
+        assert!(!mfa.use_recovery_code(&codes[0]));
+        assert!(!mfa.use_recovery_code("aaaaa-aaaaa"));
+    }
+}
@@ -0,0 +1,151 @@
+pub mod account;
+pub mod form;
+pub mod mfa;
+pub mod public;
+pub mod queries;
+pub mod sign_in;
+
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use sqlx::types::Json;
+
+use library::{Claims, Password};
+use macros::SetBlindIndex;
+use library::prelude::CustomRole;
+
+use crate::{Company, Loaders, Role, Status};
+
+pub use account::{AccountToken, AccountVerification};
+pub use form::{SignIn, SignInForm, SignInError, SignUp, SignUpForm, SignUpError};
+pub use mfa::{AccountMfa, MfaEnrollment};
+pub use public::PublicActor;
+pub use sign_in::{AccountSignIn, SignInAttempts, SignInPayload};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject, SetBlindIndex)]
+#[graphql(complex)]
+#[serde(rename_all = "camelCase")]
+pub struct Actor {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub company_id: Option<String>,
+    pub image_id: Option<String>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub account_verification: Option<Json<AccountVerification>>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub account_reset_password: Option<Json<AccountToken>>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub account_sign_in: Option<Json<AccountSignIn>>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub account_mfa: Option<Json<AccountMfa>>,
+    #[blind_index(email_index)]
+    pub email: Option<String>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub email_index: Option<String>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub password: Option<Json<Password>>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub slug: Option<String>,
+    #[sqlx(try_from = "Option<String>")]
+    pub role: Role,
+    #[sqlx(try_from = "Option<String>")]
+    pub status: Status,
+}
+
+#[ComplexObject]
+impl Actor {
+    /// Company of the actor, companies outside the tenant of the request resolve to none
+    async fn company(&self, ctx: &Context<'_>) -> Result<Option<Company>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.companies, &self.company_id).await
+    }
+
+    /// Actor impersonating this one in the current request
+    async fn impersonator_aid(&self, ctx: &Context<'_>) -> Option<String> {
+        ctx.data_opt::<Claims<Role, Status>>()
+            .filter(|claims| claims.aid.as_deref() == Some(self.id.as_str()))
+            .and_then(|claims| claims.iid.clone())
+    }
+
+    /// Whether the actor has verified their email address
+    async fn is_email_verified(&self) -> bool {
+        self.is_verified()
+    }
+
+    /// Whether the actor signs in with a second factor
+    async fn is_mfa_enabled(&self) -> bool {
+        self.get_account_mfa().is_some_and(|mfa| mfa.is_enabled())
+    }
+}
+
+impl Actor {
+    pub fn new() -> Self {
+        Self {
+            id: nanoid::nanoid!(),
+            role: Role::Guest,
+            status: Status::Active,
+            ..Default::default()
+        }
+    }
+
+    pub fn set_password(&mut self, password: Password) -> &mut Self {
+        self.password = Some(Json(password));
+        self
+    }
+
+    pub fn set_account_verification(&mut self, verification: AccountVerification) -> &mut Self {
+        self.account_verification = Some(Json(verification));
+        self
+    }
+
+    pub fn set_account_reset_password(&mut self, token: AccountToken) -> &mut Self {
+        self.account_reset_password = Some(Json(token));
+        self
+    }
+
+    /// Retrieve sign in attempts, defaulting to none recorded
+    pub fn get_account_sign_in(&self) -> AccountSignIn {
+        self.account_sign_in
+            .as_ref()
+            .map(|sign_in| sign_in.0.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn set_account_mfa(&mut self, mfa: Option<AccountMfa>) -> &mut Self {
+        self.account_mfa = mfa.map(Json);
+        self
+    }
+
+    pub fn get_account_mfa(&self) -> Option<AccountMfa> {
+        self.account_mfa
+            .as_ref()
+            .map(|mfa| mfa.0.clone())
+    }
+
+    /// Whether the actor's role must enroll a second factor
+    pub fn is_mfa_required(&self) -> bool {
+        config::MFA_REQUIRED_ROLES.contains(&self.role.to_string().as_str())
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.get_account_sign_in().is_locked()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status == Status::Active
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.account_verification
+            .as_ref()
+            .is_some_and(|verification| verification.verified_at.is_some())
+    }
+}
@@ -0,0 +1,35 @@
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use serde::{Serialize, Deserialize};
+
+use crate::{Actor, File, Loaders};
+
+/// Fields of an actor anyone sharing a chat room with them may see
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[graphql(complex)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicActor {
+    pub id: String,
+    pub image_id: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub slug: Option<String>,
+}
+
+#[ComplexObject]
+impl PublicActor {
+    async fn image(&self, ctx: &Context<'_>) -> Result<Option<File>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.files, &self.image_id).await
+    }
+}
+
+impl From<Actor> for PublicActor {
+    fn from(actor: Actor) -> Self {
+        Self {
+            id: actor.id,
+            image_id: actor.image_id,
+            first_name: actor.first_name,
+            last_name: actor.last_name,
+            slug: actor.slug,
+        }
+    }
+}
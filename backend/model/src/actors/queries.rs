@@ -0,0 +1,433 @@
+use anyhow::Result;
+use sqlx::postgres::PgExecutor;
+use sqlx::types::Json;
+
+use library::{DBManager, Password};
+use library::ciphers::blind_index::blind_indexes;
+use library::prelude::{CustomRole, CustomStatus};
+
+use crate::Actor;
+use crate::actors::AccountVerification;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, company_id, image_id,
+    account_verification, account_reset_password, account_sign_in, account_mfa, email, email_index, password,
+    first_name, last_name, slug, role, status
+"#;
+
+impl Actor {
+    pub async fn select_by_ids(manager: &DBManager, ids: &[String]) -> Result<Vec<Self>> {
+        let query = format!("SELECT {COLUMNS} FROM actor WHERE id = ANY($1)");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(ids)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_id<T>(manager: &DBManager, id: T) -> Result<Option<Self>>
+        where T: ToString
+    {
+        let query = format!("SELECT {COLUMNS} FROM actor WHERE id = $1");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(id.to_string())
+            .fetch_optional(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_email<T>(manager: &DBManager, email: T) -> Result<Option<Self>>
+        where T: ToString
+    {
+        // Match the blind index, rows that were never indexed still match the plain email
+        let email = email.to_string().trim().to_lowercase();
+        let query = format!(r#"
+            SELECT {COLUMNS} FROM actor
+            WHERE email_index = ANY($1) OR (email_index IS NULL AND email = $2)
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(blind_indexes(&email)?)
+            .bind(email)
+            .fetch_optional(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn insert(&self, manager: &DBManager) -> Result<Self> {
+        self.insert_with(manager.writer()).await
+    }
+
+    /// Insert within the executor, e.g. a transaction shared with other writes
+    pub async fn insert_with<'e, E>(&self, executor: E) -> Result<Self>
+        where E: PgExecutor<'e>
+    {
+        let mut actor = self.clone();
+        actor.set_blind_indexes()?;
+
+        let query = format!(r#"
+            INSERT INTO actor (id, email, email_index, password, first_name, last_name, role, status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {COLUMNS}
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(&actor.id)
+            .bind(&actor.email)
+            .bind(&actor.email_index)
+            .bind(&actor.password)
+            .bind(&actor.first_name)
+            .bind(&actor.last_name)
+            .bind(actor.role.to_string())
+            .bind(actor.status.to_string())
+            .fetch_one(executor)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn update_password(&self, manager: &DBManager) -> Result<u64> {
+        let result = sqlx::query("UPDATE actor SET password = $2 WHERE id = $1")
+            .bind(&self.id)
+            .bind(&self.password)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update_account_verification(&self, manager: &DBManager) -> Result<u64> {
+        self.update_account_verification_with(manager.writer()).await
+    }
+
+    pub async fn update_account_verification_with<'e, E>(&self, executor: E) -> Result<u64>
+        where E: PgExecutor<'e>
+    {
+        let result = sqlx::query("UPDATE actor SET account_verification = $2 WHERE id = $1")
+            .bind(&self.id)
+            .bind(&self.account_verification)
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update_account_reset_password(&self, manager: &DBManager) -> Result<u64> {
+        let result = sqlx::query("UPDATE actor SET account_reset_password = $2 WHERE id = $1")
+            .bind(&self.id)
+            .bind(&self.account_reset_password)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Increment failed sign in attempts in a single statement, concurrent attempts are all counted.
+    /// Attempts older than the retry window start over, reaching the max locks the account & resets them.
+    /// Returns true when this attempt locked the account.
+    pub async fn record_sign_in_failure(&self, manager: &DBManager) -> Result<bool> {
+        let result = sqlx::query_scalar::<_, bool>(r#"
+            UPDATE actor SET account_sign_in = COALESCE(account_sign_in, '{}'::JSONB) || (
+                SELECT JSONB_BUILD_OBJECT(
+                    'attempts', CASE WHEN is_locking THEN 0 ELSE attempts END,
+                    'lastAttemptAt', NOW(),
+                    'lockedUntil', CASE WHEN is_locking
+                        THEN TO_JSONB(NOW() + MAKE_INTERVAL(mins => $3))
+                        ELSE COALESCE(account_sign_in->'lockedUntil', 'null'::JSONB)
+                    END
+                ) FROM (
+                    SELECT attempts, attempts >= $2
+                        AND COALESCE((account_sign_in->>'lockedUntil')::TIMESTAMPTZ <= NOW(), TRUE) AS is_locking
+                    FROM (
+                        SELECT CASE
+                            WHEN (account_sign_in->>'lastAttemptAt')::TIMESTAMPTZ > NOW() - MAKE_INTERVAL(mins => $3)
+                            THEN COALESCE((account_sign_in->>'attempts')::INTEGER, 0) + 1
+                            ELSE 1
+                        END AS attempts
+                    ) AS attempt
+                ) AS attempt
+            )
+            WHERE id = $1
+            RETURNING (account_sign_in->>'attempts')::INTEGER = 0
+        "#).bind(&self.id)
+            .bind(config::ATTEMPT_RETRY_MAX as i32)
+            .bind(config::ATTEMPT_RETRY_DURATION as i32)
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result.unwrap_or(false))
+    }
+
+    /// Clear failed attempts & the lock after a successful sign in
+    pub async fn record_sign_in_success(&self, manager: &DBManager) -> Result<u64> {
+        let result = sqlx::query(r#"
+            UPDATE actor SET account_sign_in = COALESCE(account_sign_in, '{}'::JSONB) || JSONB_BUILD_OBJECT(
+                'attempts', 0,
+                'lastAttemptAt', NULL,
+                'lockedUntil', NULL,
+                'lastSignInAt', NOW()
+            )
+            WHERE id = $1
+        "#).bind(&self.id)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update_account_mfa(&self, manager: &DBManager) -> Result<u64> {
+        let result = sqlx::query("UPDATE actor SET account_mfa = $2 WHERE id = $1")
+            .bind(&self.id)
+            .bind(&self.account_mfa)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Rewrite every mfa secret under the current master key, returns the number of rewritten actors
+    pub async fn reencrypt_mfa_secrets(manager: &DBManager) -> Result<u64> {
+        let query = format!("SELECT {COLUMNS} FROM actor WHERE account_mfa IS NOT NULL");
+
+        let actors = sqlx::query_as::<_, Self>(&query)
+            .fetch_all(manager.writer())
+            .await?;
+
+        let mut count = 0;
+
+        for mut actor in actors {
+            let Some(mut mfa) = actor.get_account_mfa() else {
+                continue;
+            };
+
+            mfa.reencrypt()?;
+
+            count += actor.set_account_mfa(Some(mfa))
+                .update_account_mfa(manager)
+                .await?;
+        }
+
+        Ok(count)
+    }
+
+    /// Recompute blind indexes under the current master key, returns the number of re-indexed actors
+    pub async fn reindex(manager: &DBManager) -> Result<u64> {
+        let query = format!("SELECT {COLUMNS} FROM actor WHERE email IS NOT NULL");
+
+        let actors = sqlx::query_as::<_, Self>(&query)
+            .fetch_all(manager.writer())
+            .await?;
+
+        let mut count = 0;
+
+        for mut actor in actors {
+            let previous = actor.email_index.clone();
+
+            if actor.set_blind_indexes()?.email_index == previous {
+                continue;
+            }
+
+            let result = sqlx::query("UPDATE actor SET email_index = $2 WHERE id = $1")
+                .bind(&actor.id)
+                .bind(&actor.email_index)
+                .execute(manager.writer())
+                .await?;
+
+            count += result.rows_affected();
+        }
+
+        Ok(count)
+    }
+
+    /// Increment failed sign in attempts made by the ip, whether or not the email belongs to an actor.
+    /// Attempts older than the retry window start over.
+    pub async fn record_ip_sign_in_failure<T>(manager: &DBManager, ip: T) -> Result<i64>
+        where T: ToString
+    {
+        let result = sqlx::query_scalar::<_, i64>(r#"
+            INSERT INTO sign_in_attempt (ip, attempts, last_attempt_at) VALUES ($1, 1, NOW())
+            ON CONFLICT (ip) DO UPDATE SET
+                attempts = CASE
+                    WHEN sign_in_attempt.last_attempt_at > NOW() - MAKE_INTERVAL(mins => $2)
+                    THEN sign_in_attempt.attempts + 1
+                    ELSE 1
+                END,
+                last_attempt_at = NOW()
+            RETURNING attempts
+        "#).bind(ip.to_string())
+            .bind(config::ATTEMPT_RETRY_DURATION as i32)
+            .fetch_one(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Delete ip attempts past the retry window, ran by the scheduler
+    pub async fn cleanup_ip_sign_in_attempts(manager: &DBManager) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM sign_in_attempt WHERE last_attempt_at <= NOW() - MAKE_INTERVAL(mins => $1)")
+            .bind(config::ATTEMPT_RETRY_DURATION as i32)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Count failed sign in attempts made by the ip within the retry window
+    pub async fn count_ip_sign_in_attempts<T>(manager: &DBManager, ip: T) -> Result<i64>
+        where T: ToString
+    {
+        let result = sqlx::query_scalar::<_, i64>(r#"
+            SELECT attempts FROM sign_in_attempt
+            WHERE ip = $1 AND last_attempt_at > NOW() - MAKE_INTERVAL(mins => $2)
+        "#).bind(ip.to_string())
+            .bind(config::ATTEMPT_RETRY_DURATION as i32)
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result.unwrap_or(0))
+    }
+
+    /// Mark email as verified and consume the verification token in a single statement.
+    /// Returns the actor id, or none if the token is unknown, expired or already used.
+    pub async fn consume_verification_token(manager: &DBManager, hash: &str) -> Result<Option<String>> {
+        let verification = AccountVerification {
+            verified_at: Some(chrono::Utc::now()),
+            token: None,
+        };
+
+        let result = sqlx::query_scalar::<_, String>(r#"
+            UPDATE actor SET account_verification = $2
+            WHERE account_verification->'token'->>'hash' = $1
+            AND (account_verification->'token'->>'expiresAt')::TIMESTAMPTZ > NOW()
+            RETURNING id
+        "#).bind(hash)
+            .bind(Json(verification))
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Replace the password and consume the reset token in a single statement.
+    /// Returns the actor id, or none if the token is unknown, expired or already used.
+    pub async fn consume_reset_password_token(manager: &DBManager, hash: &str, password: &Password) -> Result<Option<String>> {
+        let result = sqlx::query_scalar::<_, String>(r#"
+            UPDATE actor SET password = $2, account_reset_password = NULL
+            WHERE account_reset_password->>'hash' = $1
+            AND (account_reset_password->>'expiresAt')::TIMESTAMPTZ > NOW()
+            RETURNING id
+        "#).bind(hash)
+            .bind(Json(password))
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use library::Tenant;
+
+    use super::*;
+
+    /// Database tests run against `DATABASE_WRITE_URL` when it is set.
+    /// Row level security only applies when the url connects as a role without `BYPASSRLS`.
+    async fn manager() -> Option<DBManager> {
+        std::env::var("DATABASE_WRITE_URL").ok()?;
+        Some(DBManager::init().await.expect("Database failed to initialize..."))
+    }
+
+    async fn insert_actor(manager: &DBManager, company_id: Option<&str>) -> String {
+        let id = nanoid::nanoid!();
+
+        sqlx::query("INSERT INTO actor (id, company_id, role, status) VALUES ($1, $2, 'GUEST', 'ACTIVE')")
+            .bind(&id)
+            .bind(company_id)
+            .execute(manager.writer())
+            .await
+            .unwrap();
+
+        id
+    }
+
+    #[actix_rt::test]
+    async fn select_by_id_only_finds_actors_of_the_tenant() {
+        let Some(anonymous) = manager().await else {
+            return;
+        };
+
+        let system = anonymous.system();
+        let companies = vec![nanoid::nanoid!(), nanoid::nanoid!()];
+
+        sqlx::query("INSERT INTO company (id) SELECT UNNEST($1::VARCHAR[])")
+            .bind(&companies)
+            .execute(system.writer())
+            .await
+            .unwrap();
+
+        let member = insert_actor(&system, Some(&companies[0])).await;
+        let outsider = insert_actor(&system, Some(&companies[1])).await;
+        let loner = insert_actor(&system, None).await;
+
+        let company = anonymous.scoped(Tenant {
+            company_id: Some(companies[0].clone()),
+            actor_id: Some(member.clone()),
+            is_system: false,
+        });
+
+        let own = anonymous.scoped(Tenant {
+            actor_id: Some(loner.clone()),
+            ..Default::default()
+        });
+
+        let is_found = |manager: &DBManager, id: &str| {
+            let manager = manager.clone();
+            let id = id.to_string();
+            async move { Actor::select_by_id(&manager, id).await.unwrap().is_some() }
+        };
+
+        assert!(is_found(&company, &member).await);
+        assert!(!is_found(&company, &outsider).await);
+        assert!(!is_found(&company, &loner).await);
+
+        // Actors without a company only see themselves
+        assert!(is_found(&own, &loner).await);
+        assert!(!is_found(&own, &member).await);
+
+        // Pooled connections never keep the scope of their previous statement
+        for id in [&member, &outsider, &loner] {
+            assert!(is_found(&system, id).await);
+            assert!(!is_found(&anonymous, id).await);
+        }
+
+        // Transactions are scoped the same way
+        let mut transaction = company.begin().await.unwrap();
+
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM actor WHERE id = ANY($1)")
+            .bind([member.clone(), outsider.clone(), loner.clone()])
+            .fetch_one(&mut *transaction)
+            .await
+            .unwrap();
+
+        transaction.commit().await.unwrap();
+        assert_eq!(count, 1);
+
+        sqlx::query("DELETE FROM company WHERE id = ANY($1)")
+            .bind(&companies)
+            .execute(system.writer())
+            .await
+            .unwrap();
+
+        sqlx::query("DELETE FROM actor WHERE id = ANY($1)")
+            .bind(vec![member, outsider, loner])
+            .execute(system.writer())
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+use async_graphql::{Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use serde_json::json;
+use std::sync::Arc;
+
+use library::{Core, Errors, Token};
+
+use crate::{Actor, Session};
+
+/// Failed sign in attempts within the current window
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInAttempts {
+    pub attempts: usize,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+}
+
+/// Value of `actor.account_sign_in`, only ever written by atomic updates
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSignIn {
+    #[serde(flatten)]
+    pub actor: SignInAttempts,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_sign_in_at: Option<DateTime<Utc>>,
+}
+
+impl AccountSignIn {
+    pub fn is_locked(&self) -> bool {
+        self.locked_until
+            .is_some_and(|locked_until| locked_until > Utc::now())
+    }
+}
+
+/// Result of the first sign in step.
+/// Either tokens are issued right away or an mfa token must be exchanged with `verifyMfa`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct SignInPayload {
+    pub token: Option<Token>,
+    pub mfa_token: Option<String>,
+    pub is_mfa_required: bool,
+    pub is_mfa_enrollment_required: bool,
+}
+
+impl SignInPayload {
+    /// Finish signing the actor in once the first factor succeeded.
+    /// Asks for the second factor when enabled, otherwise creates a session.
+    pub async fn start(ctx: &Context<'_>, actor: &Actor) -> Result<Self> {
+        if actor.get_account_mfa().is_some_and(|mfa| mfa.is_enabled()) {
+            let mfa_token = Core::paseto(ctx)?
+                .clone()
+                .generate_mfa_token(&actor.id)?;
+
+            return Ok(Self {
+                mfa_token: Some(mfa_token),
+                is_mfa_required: true,
+                ..Default::default()
+            });
+        }
+
+        Ok(Self {
+            token: Some(Session::start(ctx, actor).await?),
+            is_mfa_enrollment_required: actor.is_mfa_required(),
+            ..Default::default()
+        })
+    }
+}
+
+impl Actor {
+    /// Scheduler task running `cleanup_ip_sign_in_attempts` in the background
+    pub fn cleanup_task(core: Arc<Core>) {
+        actix_rt::spawn(async move {
+            if let Err(error) = Actor::cleanup_ip_sign_in_attempts(&core.database.system()).await {
+                tracing::error!("Sign in attempt cleanup failed: {error}");
+            }
+        });
+    }
+
+    /// Record a failed sign in attempt from the ip and email the actor when it locks the account.
+    /// Returns the error to respond with, `error` unless the account got locked.
+    pub async fn record_failed_sign_in(&self, ctx: &Context<'_>, ip: &str, error: String) -> Result<async_graphql::Error> {
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+        let minutes = config::ATTEMPT_RETRY_DURATION.to_string();
+
+        // Record failed attempt, the lock is decided by the database so concurrent attempts are all counted
+        if !ip.is_empty() {
+            Actor::record_ip_sign_in_failure(manager, ip)
+                .await
+                .map_err(Errors::bad_request)?;
+        }
+
+        let is_locked = self.record_sign_in_failure(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        if !is_locked {
+            return Ok(Errors::unauthorized(error));
+        }
+
+        // Notify actor, a mailer failure should not hide the lock
+        let from = config::MAILER_FROM_NO_REPLY;
+        let to = self.email.clone().unwrap_or_default();
+        let subject = locale.lookup("mailer-locked-subject");
+
+        let _ = Core::mailer(ctx)?
+            .set_template("emails/account/locked.html")
+            .set_context(json!({
+                "title": locale.lookup("mailer-locked-title"),
+                "greeting": locale.lookup_with_args(
+                    "mailer-greeting",
+                    &[("name", self.first_name.clone().unwrap_or_default().as_str())]
+                ),
+                "message": locale.lookup_with_args(
+                    "mailer-locked-message",
+                    &[("minutes", minutes.as_str()), ("ip", ip)]
+                ),
+                "advice": locale.lookup("mailer-locked-advice"),
+                "web_url": Core::base(ctx)?.get_web_url(),
+            }))
+            .send(from, to, subject);
+
+        Ok(Errors::too_many_requests(locale.lookup_with_args(
+            "actor-sign-in-locked",
+            &[("minutes", minutes.as_str())]
+        )))
+    }
+}
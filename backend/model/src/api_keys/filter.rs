@@ -0,0 +1,70 @@
+use async_graphql::{Enum, InputObject};
+
+use library::filters::{self, Condition, DateTimeFilter, Filterable, SortDirection, Sortable, StringFilter};
+
+#[derive(Debug, Default, Clone, PartialEq, InputObject)]
+pub struct ApiKeyFilter {
+    pub actor_id: Option<StringFilter>,
+    pub name: Option<StringFilter>,
+    pub key_prefix: Option<StringFilter>,
+    pub created_at: Option<DateTimeFilter>,
+    pub expires_at: Option<DateTimeFilter>,
+    pub last_used_at: Option<DateTimeFilter>,
+    pub revoked_at: Option<DateTimeFilter>,
+    pub and: Option<Vec<ApiKeyFilter>>,
+    pub or: Option<Vec<ApiKeyFilter>>,
+    pub not: Option<Box<ApiKeyFilter>>,
+}
+
+impl Filterable for ApiKeyFilter {
+    fn conditions(&self) -> Vec<(&'static str, Option<&dyn Condition>)> {
+        vec![
+            ("actor_id", filters::condition(&self.actor_id)),
+            ("name", filters::condition(&self.name)),
+            ("key_prefix", filters::condition(&self.key_prefix)),
+            ("created_at", filters::condition(&self.created_at)),
+            ("expires_at", filters::condition(&self.expires_at)),
+            ("last_used_at", filters::condition(&self.last_used_at)),
+            ("revoked_at", filters::condition(&self.revoked_at)),
+        ]
+    }
+
+    fn and(&self) -> &[Self] {
+        self.and.as_deref().unwrap_or_default()
+    }
+
+    fn or(&self) -> &[Self] {
+        self.or.as_deref().unwrap_or_default()
+    }
+
+    fn not(&self) -> Option<&Self> {
+        self.not.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum ApiKeyOrderField {
+    Name,
+    CreatedAt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, InputObject)]
+pub struct ApiKeyOrder {
+    pub field: ApiKeyOrderField,
+    #[graphql(default)]
+    pub direction: SortDirection,
+}
+
+impl Sortable for ApiKeyOrder {
+    fn column(&self) -> &'static str {
+        match self.field {
+            ApiKeyOrderField::Name => "name",
+            // Always set by the insert trigger
+            ApiKeyOrderField::CreatedAt => "created_at"
+        }
+    }
+
+    fn direction(&self) -> SortDirection {
+        self.direction
+    }
+}
@@ -0,0 +1,96 @@
+use async_graphql::{Context, MaybeUndefined, InputObject, Result};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::{Core, Errors, Validator, Response};
+use macros::{AsForm, SetIsEmpty};
+
+use crate::Guard;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub actor_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
+#[derive(AsForm, SetIsEmpty)]
+#[form(to = crate::api_keys::CreateApiKey, error = "ApiKeyError")]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyForm {
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub name: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::muvec2vec)]
+    #[error(String)]
+    pub scopes: MaybeUndefined<Vec<String>>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mu2opt)]
+    #[error(String)]
+    pub expires_at: MaybeUndefined<DateTime<Utc>>,
+    /// Owner of the key, only the controller may set it
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mu2opt)]
+    #[error(String)]
+    pub actor_id: MaybeUndefined<String>,
+}
+
+impl ApiKeyForm {
+    pub fn validate(&mut self, ctx: &Context<'_>) -> Result<&mut Self> {
+        let locale = Core::locales(ctx)?;
+        let is_controller = Guard::is_controller(ctx);
+        let data = self.sanitize();
+        let scopes = data.scopes.clone().take().unwrap_or_default();
+
+        // Validate each scope against the allowed list
+        let scopes_error = match scopes.is_empty() {
+            true => Some(locale.lookup("api-key-scopes-empty")),
+            false => scopes.iter().find_map(|scope| Validator::new(locale, "api-key-scopes")
+                .set_option_list_string(&config::API_KEY_SCOPES)
+                .set_as_required(true)
+                .set_string_value(&MaybeUndefined::Value(scope.clone()))
+                .validate_list_string())
+        };
+
+        let scopes_error = scopes_error.or_else(|| {
+            let is_forbidden = !is_controller && scopes.iter()
+                .any(|scope| scope == library::api_keys::SCOPE_CONTROLLER);
+
+            is_forbidden.then(|| locale.lookup("api-key-scopes-forbidden"))
+        });
+
+        let expires_at_error = data.expires_at
+            .take()
+            .filter(|expires_at| *expires_at <= Utc::now())
+            .map(|_| locale.lookup("api-key-expires-at-invalid"));
+
+        let actor_id_error = match data.actor_id.is_null() || data.actor_id.is_undefined() {
+            true if is_controller => Some(locale.lookup("api-key-owner-required")),
+            _ => None
+        };
+
+        let error = ApiKeyError {
+            name: Validator::new(locale, "api-key-name")
+                .set_min(1)
+                .set_max(100)
+                .set_as_required(true)
+                .set_string_value(&data.name)
+                .validate_string(),
+            scopes: scopes_error,
+            expires_at: expires_at_error,
+            actor_id: actor_id_error,
+        };
+
+        let response = Response::BadRequest;
+
+        match error.is_empty() {
+            true => Ok(data),
+            false => Err(Errors::to(response, error))
+        }
+    }
+}
@@ -0,0 +1,222 @@
+pub mod filter;
+pub mod form;
+pub mod queries;
+
+use async_graphql::{Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::{Claims, Core, Errors};
+use library::pagination::Paginated;
+use library::hashes::sha256;
+
+use crate::{Guard, Role, Session, Status};
+
+pub use filter::{ApiKeyFilter, ApiKeyOrder, ApiKeyOrderField};
+pub use form::{CreateApiKey, ApiKeyForm, ApiKeyError};
+
+/// Length of the key kept in plain text so owners can tell keys apart
+const KEY_PREFIX_LENGTH: usize = 10;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKey {
+    pub id: String,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub cursor: i64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub actor_id: String,
+    pub name: String,
+    pub key_prefix: String,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Paginated for ApiKey {
+    fn cursor(&self) -> i64 {
+        self.cursor
+    }
+}
+
+/// Newly created key, the raw key is only returned once
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKey {
+    pub api_key: ApiKey,
+    pub key: String,
+}
+
+impl ApiKey {
+    /// Create key for the actor, returns the raw key alongside the stored record
+    pub fn new<T>(actor_id: T, form: &CreateApiKey) -> (String, Self)
+        where T: ToString
+    {
+        let key = format!("{}{}", config::API_KEY_PREFIX, nanoid::nanoid!(48));
+
+        let api_key = Self {
+            id: nanoid::nanoid!(),
+            actor_id: actor_id.to_string(),
+            name: form.name.clone(),
+            key_prefix: key.chars().take(KEY_PREFIX_LENGTH).collect(),
+            key_hash: sha256(&key),
+            scopes: form.scopes.clone(),
+            expires_at: form.expires_at,
+            ..Default::default()
+        };
+
+        (key, api_key)
+    }
+
+    /// Resolve who manages keys in this request.
+    /// Returns none for the controller (every key) or the id of the signed in actor (own keys).
+    pub fn manager_id(ctx: &Context<'_>) -> Result<Option<String>> {
+        let locale = Core::locales(ctx)?;
+
+        // Keys cannot be used to issue or revoke keys
+        let is_api_key = ctx.data_opt::<Claims<Role, Status>>()
+            .is_some_and(|claims| claims.scopes.is_some());
+
+        if is_api_key {
+            return Err(Errors::forbidden(locale.lookup("api-key-forbidden")));
+        }
+
+        if Guard::is_controller(ctx) {
+            return Ok(None);
+        }
+
+        // Session claims always carry the actor id
+        Ok(Session::claims(ctx)?.aid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use library::{ApiKeyIdentity, DBManager};
+
+    use super::*;
+
+    fn create(scopes: &[&str], expires_at: Option<DateTime<Utc>>) -> CreateApiKey {
+        CreateApiKey {
+            name: String::from("ci"),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            expires_at,
+            actor_id: None,
+        }
+    }
+
+    fn identity(scopes: &[&str], role: &str) -> ApiKeyIdentity {
+        ApiKeyIdentity {
+            id: String::from("key"),
+            actor_id: String::from("actor"),
+            company_id: Some(String::from("company")),
+            scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+            role: Some(role.to_string()),
+            status: Some(String::from("ACTIVE")),
+        }
+    }
+
+    #[test]
+    fn new_stores_only_the_hash_and_prefix_of_the_key() {
+        let (key, api_key) = ApiKey::new("actor", &create(&["read"], None));
+
+        assert!(ApiKeyIdentity::is_api_key(&key));
+        assert_eq!(api_key.key_hash, sha256(&key));
+        assert_ne!(api_key.key_hash, key);
+        assert_eq!(api_key.key_prefix, key.chars().take(KEY_PREFIX_LENGTH).collect::<String>());
+        assert_ne!(ApiKey::new("actor", &create(&["read"], None)).0, key);
+    }
+
+    #[test]
+    fn to_claims_keeps_the_owner_role_and_the_key_scopes() {
+        let claims = identity(&["read"], "ADMIN").to_claims::<Role, Status>();
+
+        assert_eq!(claims.aid.as_deref(), Some("actor"));
+        assert_eq!(claims.cid.as_deref(), Some("company"));
+        assert_eq!(claims.role, Some(Role::Admin));
+        assert_eq!(claims.status, Some(Status::Active));
+        assert!(claims.has_scope("read"));
+        assert!(!claims.has_scope("write"));
+    }
+
+    #[test]
+    fn to_claims_grants_the_controller_role_only_with_the_controller_scope() {
+        let controller = identity(&["controller", "read"], "ADMIN").to_claims::<Role, Status>();
+        let admin = identity(&["read", "write"], "ADMIN").to_claims::<Role, Status>();
+
+        assert_eq!(controller.role, Some(Role::Controller));
+        assert_eq!(admin.role, Some(Role::Admin));
+    }
+
+    /// Database tests run against `DATABASE_WRITE_URL` when it is set, seeding rows is system work
+    async fn manager() -> Option<DBManager> {
+        std::env::var("DATABASE_WRITE_URL").ok()?;
+        Some(DBManager::init().await.expect("Database failed to initialize...").system())
+    }
+
+    async fn insert_actor(manager: &DBManager, status: &str) -> String {
+        let id = nanoid::nanoid!();
+
+        sqlx::query("INSERT INTO actor (id, role, status) VALUES ($1, 'ADMIN', $2)")
+            .bind(&id)
+            .bind(status)
+            .execute(manager.writer())
+            .await
+            .unwrap();
+
+        id
+    }
+
+    async fn insert_key(manager: &DBManager, actor_id: &str, expires_at: Option<DateTime<Utc>>) -> (String, ApiKey) {
+        let (key, api_key) = ApiKey::new(actor_id, &create(&["read"], expires_at));
+        let api_key = api_key.insert(manager).await.unwrap();
+
+        (key, api_key)
+    }
+
+    #[actix_rt::test]
+    async fn authenticate_rejects_revoked_expired_and_inactive_keys() {
+        let Some(manager) = manager().await else {
+            return;
+        };
+
+        let active = insert_actor(&manager, "ACTIVE").await;
+        let inactive = insert_actor(&manager, "INACTIVE").await;
+
+        let (key, api_key) = insert_key(&manager, &active, Some(Utc::now() + Duration::hours(1))).await;
+        let (expired, _) = insert_key(&manager, &active, Some(Utc::now() - Duration::seconds(1))).await;
+        let (revoked, revoked_key) = insert_key(&manager, &active, None).await;
+        let (suspended, _) = insert_key(&manager, &inactive, None).await;
+
+        ApiKey::revoke(&manager, &revoked_key.id, None).await.unwrap();
+
+        let authenticate = |token: String| {
+            let manager = manager.clone();
+            async move { ApiKeyIdentity::authenticate(&manager, &token).await.unwrap() }
+        };
+
+        let identity = authenticate(key.clone()).await.expect("active key should authenticate");
+        assert_eq!(identity.id, api_key.id);
+        assert_eq!(identity.actor_id, active);
+
+        // Only the raw key authenticates, never its stored hash
+        assert!(authenticate(api_key.key_hash.clone()).await.is_none());
+        assert!(authenticate(expired).await.is_none());
+        assert!(authenticate(revoked).await.is_none());
+        assert!(authenticate(suspended).await.is_none());
+
+        sqlx::query("DELETE FROM actor WHERE id = ANY($1)")
+            .bind(vec![active, inactive])
+            .execute(manager.writer())
+            .await
+            .unwrap();
+    }
+}
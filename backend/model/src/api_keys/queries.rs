@@ -0,0 +1,105 @@
+use anyhow::Result;
+use sqlx::{Postgres, QueryBuilder};
+
+use library::DBManager;
+use library::filters::{self, Order};
+use library::pagination::Page;
+
+use crate::ApiKey;
+use crate::api_keys::ApiKeyFilter;
+
+const COLUMNS: &str = r#"
+    id, cursor, created_at, updated_at, actor_id, name, key_prefix, key_hash,
+    scopes, expires_at, last_used_at, revoked_at
+"#;
+
+impl ApiKey {
+    /// Select a page of filtered keys, every key for the controller or the keys of a single actor
+    pub async fn select_page(
+        manager: &DBManager,
+        actor_id: Option<String>,
+        filter: Option<&ApiKeyFilter>,
+        orders: &[Order],
+        page: &Page,
+    ) -> Result<(Vec<Self>, i64)> {
+        let mut builder = QueryBuilder::<Postgres>::new(format!("SELECT {COLUMNS} FROM api_key"));
+
+        builder.push(" WHERE TRUE");
+        Self::push_conditions(&mut builder, &actor_id, filter)?;
+
+        // The cursor row is looked up within the same conditions
+        page.push_keyset(&mut builder, "api_key", orders, |builder| Self::push_conditions(builder, &actor_id, filter))?;
+
+        let result = builder.build_query_as::<Self>()
+            .fetch_all(manager.reader())
+            .await?;
+
+        let count = match page.is_counted {
+            true => Self::count(manager, actor_id, filter).await?,
+            false => 0
+        };
+
+        Ok((result, count))
+    }
+
+    pub async fn count(manager: &DBManager, actor_id: Option<String>, filter: Option<&ApiKeyFilter>) -> Result<i64> {
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM api_key WHERE TRUE");
+
+        Self::push_conditions(&mut builder, &actor_id, filter)?;
+
+        let result = builder.build_query_scalar::<i64>()
+            .fetch_one(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Append the `AND ...` predicates of the visible keys, the query must already have a `WHERE` clause
+    fn push_conditions(builder: &mut QueryBuilder<'_, Postgres>, actor_id: &Option<String>, filter: Option<&ApiKeyFilter>) -> Result<()> {
+        if let Some(actor_id) = actor_id {
+            builder.push(" AND actor_id = ").push_bind(actor_id.clone());
+        }
+
+        if let Some(filter) = filter {
+            filters::push_filter(builder, filter)?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn insert(&self, manager: &DBManager) -> Result<Self> {
+        let query = format!(r#"
+            INSERT INTO api_key (id, actor_id, name, key_prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {COLUMNS}
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(&self.id)
+            .bind(&self.actor_id)
+            .bind(&self.name)
+            .bind(&self.key_prefix)
+            .bind(&self.key_hash)
+            .bind(&self.scopes)
+            .bind(self.expires_at)
+            .fetch_one(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Revoke key, restricted to the owner unless `actor_id` is none
+    pub async fn revoke<I>(manager: &DBManager, id: I, actor_id: Option<String>) -> Result<u64>
+        where I: ToString
+    {
+        let result = sqlx::query(r#"
+            UPDATE api_key SET revoked_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL AND ($2::VARCHAR IS NULL OR actor_id = $2)
+        "#).bind(id.to_string())
+            .bind(actor_id)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,33 @@
+pub mod queries;
+
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::{File, Loaders};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[graphql(complex)]
+#[serde(rename_all = "camelCase")]
+pub struct Category {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub parent_id: Option<String>,
+    pub image_id: Option<String>,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub status: Option<String>,
+}
+
+#[ComplexObject]
+impl Category {
+    async fn image(&self, ctx: &Context<'_>) -> Result<Option<File>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.files, &self.image_id).await
+    }
+
+    async fn parent(&self, ctx: &Context<'_>) -> Result<Option<Category>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.categories, &self.parent_id).await
+    }
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+
+use library::DBManager;
+
+use crate::Category;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, parent_id, image_id, name, slug, status
+"#;
+
+impl Category {
+    pub async fn select_by_ids(manager: &DBManager, ids: &[String]) -> Result<Vec<Self>> {
+        let query = format!("SELECT {COLUMNS} FROM category WHERE id = ANY($1)");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(ids)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+}
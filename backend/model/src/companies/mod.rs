@@ -0,0 +1,40 @@
+pub mod queries;
+
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use crate::{File, Loaders};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[graphql(complex)]
+#[serde(rename_all = "camelCase")]
+pub struct Company {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub banner_id: Option<String>,
+    pub logo_id: Option<String>,
+    pub name: Option<String>,
+    pub slug: Option<String>,
+    pub status: Option<String>,
+    pub business_description: Option<String>,
+    pub street: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zip: Option<String>,
+    pub country: Option<String>,
+    pub website: Option<String>,
+}
+
+#[ComplexObject]
+impl Company {
+    async fn banner(&self, ctx: &Context<'_>) -> Result<Option<File>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.files, &self.banner_id).await
+    }
+
+    async fn logo(&self, ctx: &Context<'_>) -> Result<Option<File>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.files, &self.logo_id).await
+    }
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+use library::DBManager;
+
+use crate::Company;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, banner_id, logo_id, name, slug, status,
+    business_description, street, city, state, zip, country, website
+"#;
+
+impl Company {
+    pub async fn select_by_ids(manager: &DBManager, ids: &[String]) -> Result<Vec<Self>> {
+        let query = format!("SELECT {COLUMNS} FROM company WHERE id = ANY($1)");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(ids)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+}
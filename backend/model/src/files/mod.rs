@@ -0,0 +1,23 @@
+pub mod queries;
+
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct File {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub filename: Option<String>,
+    pub module: Option<String>,
+    pub label: Option<String>,
+    pub description: Option<String>,
+    pub extension: Option<String>,
+    pub mime_type: Option<String>,
+    pub file_size: Option<String>,
+    pub height: Option<String>,
+    pub width: Option<String>,
+}
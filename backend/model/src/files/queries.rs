@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+use library::DBManager;
+
+use crate::File;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, filename, module, label, description,
+    extension, mime_type, file_size, height, width
+"#;
+
+impl File {
+    pub async fn select_by_ids(manager: &DBManager, ids: &[String]) -> Result<Vec<Self>> {
+        let query = format!("SELECT {COLUMNS} FROM file WHERE id = ANY($1)");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(ids)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+}
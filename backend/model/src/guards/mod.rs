@@ -7,10 +7,24 @@ use crate::{Role, Status};
 pub struct Guard;
 
 impl Guard {
+    /// Allow any signed in actor whose account is active
+    pub fn authenticated() -> GuardLib<Role, Status> {
+        GuardLib::<Role, Status>::role_and_status(vec![], vec![Status::Active])
+    }
+
+    pub fn role_and_status(role: Vec<Role>, status: Vec<Status>) -> GuardLib<Role, Status> {
+        GuardLib::<Role, Status>::role_and_status(role, status)
+    }
+
     pub fn controller() -> GuardLib<Role, Status> {
         GuardLib::<Role, Status>::role(vec![Role::Controller])
     }
 
+    /// Allow roles granted the permission, signed in actors also need an active account
+    pub fn permission(permission: &str) -> GuardLib<Role, Status> {
+        GuardLib::<Role, Status>::permission_and_status(permission, vec![Status::Active])
+    }
+
     pub fn is_controller(ctx: &Context) -> bool {
         GuardLib::<Role, Status>::is_controller(ctx)
     }
@@ -1,7 +1,27 @@
+pub mod actors;
+pub mod api_keys;
+pub mod categories;
+pub mod companies;
+pub mod files;
 pub mod guards;
+pub mod loaders;
+pub mod messages;
+pub mod oauth;
+pub mod permissions;
 pub mod roles;
+pub mod sessions;
 pub mod statuses;
 
+pub use actors::Actor;
+pub use api_keys::ApiKey;
+pub use categories::Category;
+pub use companies::Company;
+pub use files::File;
 pub use guards::Guard;
-pub use roles::Role;
+pub use loaders::{GqlLoaders, Loaders};
+pub use messages::Message;
+pub use oauth::ActorOAuth;
+pub use permissions::Permission;
+pub use roles::{Role, RoleDefinition};
+pub use sessions::Session;
 pub use statuses::Status;
\ No newline at end of file
@@ -0,0 +1,208 @@
+use async_graphql::async_trait::async_trait;
+use async_graphql::dataloader::{DataLoader, HashMapCache, Loader};
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest};
+use async_graphql::{Context, Request, Result, ServerResult};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use library::{Core, DBManager, Errors};
+
+use crate::{Actor, Category, Company, File, Message};
+
+pub type ByIdDataLoader<T> = DataLoader<ByIdLoader<T>, HashMapCache>;
+
+/// Rows loaded in batches by primary key
+#[async_trait]
+pub trait Loadable: Clone + Send + Sync + 'static {
+    async fn select_by_ids(manager: &DBManager, ids: &[String]) -> anyhow::Result<Vec<Self>>;
+
+    fn id(&self) -> &str;
+}
+
+/// Loads every id requested during the same tick with a single `id = ANY($1)` query
+pub struct ByIdLoader<T> {
+    manager: DBManager,
+    entity: PhantomData<T>,
+}
+
+impl<T> ByIdLoader<T> {
+    pub fn new(manager: &DBManager) -> Self {
+        Self {
+            manager: manager.clone(),
+            entity: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Loader<String> for ByIdLoader<T>
+    where T: Loadable
+{
+    type Value = T;
+    type Error = Arc<anyhow::Error>;
+
+    async fn load(&self, ids: &[String]) -> Result<HashMap<String, T>, Self::Error> {
+        let rows = T::select_by_ids(&self.manager, ids)
+            .await
+            .map_err(Arc::new)?;
+
+        Ok(rows.into_iter().map(|row| (row.id().to_string(), row)).collect())
+    }
+}
+
+/// Batch loaders of a single request, attached to the request data.
+/// Nested fields resolve with one query per entity, ids repeated within the request are served from the cache.
+pub struct Loaders {
+    pub actors: ByIdDataLoader<Actor>,
+    pub categories: ByIdDataLoader<Category>,
+    pub companies: ByIdDataLoader<Company>,
+    pub files: ByIdDataLoader<File>,
+    pub messages: ByIdDataLoader<Message>,
+}
+
+impl Loaders {
+    pub fn new(manager: &DBManager) -> Self {
+        Self {
+            actors: Self::loader(manager),
+            categories: Self::loader(manager),
+            companies: Self::loader(manager),
+            files: Self::loader(manager),
+            messages: Self::loader(manager),
+        }
+    }
+
+    /// Loaders of a websocket connection, which lives too long to cache rows
+    pub fn uncached(manager: &DBManager) -> Self {
+        let loaders = Self::new(manager);
+
+        loaders.actors.enable_all_cache(false);
+        loaders.categories.enable_all_cache(false);
+        loaders.companies.enable_all_cache(false);
+        loaders.files.enable_all_cache(false);
+        loaders.messages.enable_all_cache(false);
+
+        loaders
+    }
+
+    fn loader<T>(manager: &DBManager) -> ByIdDataLoader<T>
+        where T: Loadable
+    {
+        DataLoader::with_cache(ByIdLoader::new(manager), tokio::spawn, HashMapCache::default())
+    }
+
+    pub fn get<'a>(ctx: &Context<'a>) -> Result<&'a Self> {
+        let locale = Core::locales(ctx)?;
+
+        ctx.data_opt::<Self>()
+            .ok_or_else(|| Errors::internal_server_error(locale.lookup("loader-retrieve-failed")))
+    }
+
+    /// Resolve an optional foreign key, dangling keys resolve to none
+    pub async fn load<T>(ctx: &Context<'_>, loader: &ByIdDataLoader<T>, id: &Option<String>) -> Result<Option<T>>
+        where T: Loadable
+    {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        let locale = Core::locales(ctx)?;
+
+        loader.load_one(id.clone())
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("loader-load-failed")))
+    }
+}
+
+/// Attaches the batch loaders of a request, built from the manager scoped to the request by the token parser.
+/// Must be registered after the token parser, websocket connections bring their own uncached loaders.
+#[derive(Default)]
+pub struct GqlLoaders;
+
+impl ExtensionFactory for GqlLoaders {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GqlLoaders)
+    }
+}
+
+#[async_trait]
+impl Extension for GqlLoaders {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        if ctx.data_opt::<Loaders>().is_some() {
+            return next.run(ctx, request).await;
+        }
+
+        let loaders = request.data
+            .get(&TypeId::of::<DBManager>())
+            .and_then(|manager| manager.downcast_ref::<DBManager>())
+            .map(Loaders::new);
+
+        let request = match loaders {
+            Some(loaders) => request.data(loaders),
+            None => request
+        };
+
+        next.run(ctx, request).await
+    }
+}
+
+#[async_trait]
+impl Loadable for Actor {
+    async fn select_by_ids(manager: &DBManager, ids: &[String]) -> anyhow::Result<Vec<Self>> {
+        Actor::select_by_ids(manager, ids).await
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl Loadable for Category {
+    async fn select_by_ids(manager: &DBManager, ids: &[String]) -> anyhow::Result<Vec<Self>> {
+        Category::select_by_ids(manager, ids).await
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl Loadable for Company {
+    async fn select_by_ids(manager: &DBManager, ids: &[String]) -> anyhow::Result<Vec<Self>> {
+        Company::select_by_ids(manager, ids).await
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl Loadable for File {
+    async fn select_by_ids(manager: &DBManager, ids: &[String]) -> anyhow::Result<Vec<Self>> {
+        File::select_by_ids(manager, ids).await
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[async_trait]
+impl Loadable for Message {
+    async fn select_by_ids(manager: &DBManager, ids: &[String]) -> anyhow::Result<Vec<Self>> {
+        Message::select_by_ids(manager, ids).await
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
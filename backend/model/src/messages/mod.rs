@@ -0,0 +1,99 @@
+pub mod queries;
+
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::{Claims, Core, DBManager, Errors};
+
+use crate::{File, Guard, Loaders, Role, Session, Status};
+use crate::actors::PublicActor;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[graphql(complex)]
+#[serde(rename_all = "camelCase")]
+pub struct Message {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub removed_at: Option<DateTime<Utc>>,
+    pub chat_room_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub file_id: Option<String>,
+    pub content: Option<String>,
+    pub message_type: Option<String>,
+    pub status: Option<String>,
+}
+
+#[ComplexObject]
+impl Message {
+    /// Author of the message, only the fields other room members may see
+    async fn actor(&self, ctx: &Context<'_>) -> Result<Option<PublicActor>> {
+        let actor = Loaders::load(ctx, &Loaders::get(ctx)?.actors, &self.actor_id).await?;
+
+        Ok(actor.map(PublicActor::from))
+    }
+
+    async fn file(&self, ctx: &Context<'_>) -> Result<Option<File>> {
+        Loaders::load(ctx, &Loaders::get(ctx)?.files, &self.file_id).await
+    }
+}
+
+impl Message {
+    /// Check the signed in actor may follow the chat room.
+    /// Controllers follow every room, admins the rooms of their company & everyone else
+    /// the rooms they own or take part in.
+    pub async fn check_room_access(ctx: &Context<'_>, chat_room_id: &str) -> Result<()> {
+        let locale = Core::locales(ctx)?;
+
+        if Guard::is_controller(ctx) {
+            return Ok(());
+        }
+
+        let Some((actor_id, claims)) = ctx.data_opt::<Claims<Role, Status>>()
+            .and_then(|claims| claims.aid.clone().map(|aid| (aid, claims))) else {
+            return Err(Errors::unauthorized(locale.lookup("session-unauthenticated")));
+        };
+
+        // Admins work within their company, everyone else only through their own rooms
+        let manager = match claims.role {
+            Some(Role::Admin) => Core::database(ctx)?.clone(),
+            _ => Core::system_database(ctx)?
+        };
+
+        match Self::is_room_member(&manager, chat_room_id, actor_id).await {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Errors::forbidden(locale.lookup("message-room-forbidden"))),
+            Err(_) => Err(Errors::internal_server_error(locale.lookup("message-room-retrieve-failed")))
+        }
+    }
+
+    /// Check a chat room stream may still deliver messages to its subscriber.
+    /// Access granted at subscribe time ends with the session or the room membership.
+    /// The manager is the one scoped to the subscription's request.
+    pub async fn has_stream_access(manager: &DBManager, role: Role, claims: Option<&Claims<Role, Status>>, chat_room_id: &str) -> anyhow::Result<bool> {
+        if role == Role::Controller {
+            return Ok(true);
+        }
+
+        let Some((actor_id, claims)) = claims.and_then(|claims| claims.aid.as_ref().map(|aid| (aid, claims))) else {
+            return Ok(false);
+        };
+
+        // Impersonation tokens live on the session of the impersonator
+        if let Some(sid) = &claims.sid {
+            let session_actor_id = claims.iid.as_ref().unwrap_or(actor_id);
+
+            if Session::select_active(manager, sid, session_actor_id).await?.is_none() {
+                return Ok(false);
+            }
+        }
+
+        // Admins work within their company, everyone else only through their own rooms
+        match claims.role {
+            Some(Role::Admin) => Self::is_room_member(manager, chat_room_id, actor_id).await,
+            _ => Self::is_room_member(&manager.system(), chat_room_id, actor_id).await
+        }
+    }
+}
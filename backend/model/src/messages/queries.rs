@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use library::DBManager;
+
+use crate::messages::Message;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, removed_at, chat_room_id, actor_id,
+    file_id, content, message_type, status
+"#;
+
+impl Message {
+    pub async fn select_by_id<I>(manager: &DBManager, id: I) -> Result<Self>
+        where I: ToString
+    {
+        let query = format!("SELECT {COLUMNS} FROM message WHERE id = $1 AND removed_at IS NULL");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(id.to_string())
+            .fetch_one(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_ids(manager: &DBManager, ids: &[String]) -> Result<Vec<Self>> {
+        let query = format!("SELECT {COLUMNS} FROM message WHERE id = ANY($1) AND removed_at IS NULL");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(ids)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Owner or active participant of the room, or any actor of the room's company for company tenants.
+    /// Rooms of other companies are never visible to company tenants.
+    pub async fn is_room_member<R, A>(manager: &DBManager, chat_room_id: R, actor_id: A) -> Result<bool>
+        where R: ToString, A: ToString
+    {
+        let result = sqlx::query_scalar::<_, bool>(r#"
+            SELECT EXISTS (
+                SELECT 1 FROM chat_room
+                WHERE id = $1 AND (actor_id = $2 OR ($3::VARCHAR IS NOT NULL AND company_id = $3))
+            ) OR EXISTS (
+                SELECT 1 FROM chat_pipeline
+                WHERE chat_room_id = $1 AND actor_id = $2 AND removed_at IS NULL
+            )
+        "#).bind(chat_room_id.to_string())
+            .bind(actor_id.to_string())
+            .bind(&manager.tenant().company_id)
+            .fetch_one(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+}
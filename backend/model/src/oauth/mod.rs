@@ -0,0 +1,39 @@
+pub mod queries;
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::OAuthIdentity;
+
+/// Account of an external identity provider linked to an actor
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorOAuth {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub actor_id: String,
+    pub provider: String,
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub last_sign_in_at: Option<DateTime<Utc>>,
+}
+
+impl ActorOAuth {
+    pub fn new<T>(actor_id: T, identity: &OAuthIdentity) -> Self
+        where T: ToString
+    {
+        Self {
+            id: nanoid::nanoid!(),
+            actor_id: actor_id.to_string(),
+            provider: identity.provider.clone(),
+            issuer: identity.issuer.clone(),
+            subject: identity.subject.clone(),
+            email: identity.email.clone(),
+            last_sign_in_at: Some(Utc::now()),
+            ..Default::default()
+        }
+    }
+}
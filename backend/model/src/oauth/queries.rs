@@ -0,0 +1,65 @@
+use anyhow::Result;
+use sqlx::postgres::PgExecutor;
+
+use library::DBManager;
+
+use crate::ActorOAuth;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, actor_id, provider, issuer, subject, email, last_sign_in_at
+"#;
+
+impl ActorOAuth {
+    pub async fn select_by_subject<I, S>(manager: &DBManager, issuer: I, subject: S) -> Result<Option<Self>>
+        where I: ToString,
+              S: ToString
+    {
+        let query = format!("SELECT {COLUMNS} FROM actor_oauth WHERE issuer = $1 AND subject = $2");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(issuer.to_string())
+            .bind(subject.to_string())
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn insert(&self, manager: &DBManager) -> Result<Self> {
+        self.insert_with(manager.writer()).await
+    }
+
+    /// Insert within the executor, e.g. the transaction creating the actor
+    pub async fn insert_with<'e, E>(&self, executor: E) -> Result<Self>
+        where E: PgExecutor<'e>
+    {
+        let query = format!(r#"
+            INSERT INTO actor_oauth (id, actor_id, provider, issuer, subject, email, last_sign_in_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {COLUMNS}
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(&self.id)
+            .bind(&self.actor_id)
+            .bind(&self.provider)
+            .bind(&self.issuer)
+            .bind(&self.subject)
+            .bind(&self.email)
+            .bind(self.last_sign_in_at)
+            .fetch_one(executor)
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn update_last_sign_in(&self, manager: &DBManager) -> Result<u64> {
+        let result = sqlx::query("UPDATE actor_oauth SET last_sign_in_at = NOW(), email = $2 WHERE id = $1")
+            .bind(&self.id)
+            .bind(&self.email)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
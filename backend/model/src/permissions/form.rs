@@ -0,0 +1,68 @@
+use async_graphql::{Context, MaybeUndefined, InputObject, Result};
+use serde::{Serialize, Deserialize};
+
+use library::{Core, Errors, Validator, Response};
+use macros::{AsForm, SetIsEmpty};
+
+use crate::Permission;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CreatePermission {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
+#[derive(AsForm, SetIsEmpty)]
+#[form(to = crate::permissions::CreatePermission, error = "PermissionError")]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionForm {
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mustr2str)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub name: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mu2opt)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub description: MaybeUndefined<String>,
+}
+
+impl PermissionForm {
+    pub fn validate(&mut self, ctx: &Context<'_>) -> Result<&mut Self> {
+        let locale = Core::locales(ctx)?;
+        let data = self.sanitize();
+        let name = data.name.clone().take().unwrap_or_default();
+
+        let name_error = Validator::new(locale, "permission-name")
+            .set_min(1)
+            .set_max(100)
+            .set_as_required(true)
+            .set_string_value(&data.name)
+            .validate_string()
+            .or_else(|| (!Permission::is_valid_name(&name)).then(|| locale.lookup("permission-name-invalid")));
+
+        // Description is optional, only its length is checked
+        let description_error = data.description
+            .clone()
+            .take()
+            .filter(|description| !description.is_empty())
+            .and_then(|_| Validator::new(locale, "permission-description")
+                .set_max(300)
+                .set_string_value(&data.description)
+                .validate_string());
+
+        let error = PermissionError {
+            name: name_error,
+            description: description_error,
+        };
+
+        let response = Response::BadRequest;
+
+        match error.is_empty() {
+            true => Ok(data),
+            false => Err(Errors::to(response, error))
+        }
+    }
+}
@@ -0,0 +1,44 @@
+pub mod form;
+pub mod queries;
+
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+pub use form::{CreatePermission, PermissionForm, PermissionError};
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl Permission {
+    pub fn new(form: &CreatePermission) -> Self {
+        Self {
+            id: nanoid::nanoid!(),
+            name: form.name.clone(),
+            description: form.description.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Permission names are `resource:action` in lowercase, `resource:*` or `*` grant every matching permission
+    pub fn is_valid_name(name: &str) -> bool {
+        let is_segment = |segment: &str| !segment.is_empty() && segment
+            .chars()
+            .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit() || char == '_' || char == '-');
+
+        match name.split_once(':') {
+            _ if name == library::permissions::WILDCARD => true,
+            Some((resource, "*")) => is_segment(resource),
+            Some((resource, action)) => is_segment(resource) && is_segment(action),
+            None => false
+        }
+    }
+}
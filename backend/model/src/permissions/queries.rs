@@ -0,0 +1,74 @@
+use anyhow::Result;
+
+use library::DBManager;
+
+use crate::Permission;
+
+const COLUMNS: &str = "id, created_at, updated_at, name, description";
+
+impl Permission {
+    pub async fn select_all(manager: &DBManager) -> Result<Vec<Self>> {
+        let query = format!("SELECT {COLUMNS} FROM permission ORDER BY name ASC");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_id<I>(manager: &DBManager, id: I) -> Result<Option<Self>>
+        where I: ToString
+    {
+        let query = format!("SELECT {COLUMNS} FROM permission WHERE id = $1");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(id.to_string())
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_name<N>(manager: &DBManager, name: N) -> Result<Option<Self>>
+        where N: ToString
+    {
+        let query = format!("SELECT {COLUMNS} FROM permission WHERE name = $1");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(name.to_string())
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn insert(&self, manager: &DBManager) -> Result<Self> {
+        let query = format!(r#"
+            INSERT INTO permission (id, name, description)
+            VALUES ($1, $2, $3)
+            RETURNING {COLUMNS}
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(&self.id)
+            .bind(&self.name)
+            .bind(&self.description)
+            .fetch_one(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Delete permission, its grants are removed along with it
+    pub async fn delete<I>(manager: &DBManager, id: I) -> Result<u64>
+        where I: ToString
+    {
+        let result = sqlx::query("DELETE FROM permission WHERE id = $1")
+            .bind(id.to_string())
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,18 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+/// Stored role with the names of the permissions granted to it.
+/// Rows are seeded for each variant of the `Role` of actors & share its name,
+/// only their description & grants can be changed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDefinition {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
@@ -0,0 +1,50 @@
+use async_graphql::{Context, MaybeUndefined, InputObject, Result};
+use serde::{Serialize, Deserialize};
+
+use library::{Core, Errors, Validator, Response};
+use macros::{AsForm, SetIsEmpty};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UpdateRole {
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
+#[derive(AsForm, SetIsEmpty)]
+#[form(to = crate::roles::UpdateRole, error = "RoleError")]
+#[serde(rename_all = "camelCase")]
+pub struct RoleForm {
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(library::conversions::mu2opt)]
+    #[sanitize(library::sanitize::mustring)]
+    #[error(String)]
+    pub description: MaybeUndefined<String>,
+}
+
+impl RoleForm {
+    pub fn validate(&mut self, ctx: &Context<'_>) -> Result<&mut Self> {
+        let locale = Core::locales(ctx)?;
+        let data = self.sanitize();
+
+        // Description is optional, only its length is checked
+        let description_error = data.description
+            .clone()
+            .take()
+            .filter(|description| !description.is_empty())
+            .and_then(|_| Validator::new(locale, "role-description")
+                .set_max(300)
+                .set_string_value(&data.description)
+                .validate_string());
+
+        let error = RoleError {
+            description: description_error,
+        };
+
+        let response = Response::BadRequest;
+
+        match error.is_empty() {
+            true => Ok(data),
+            false => Err(Errors::to(response, error))
+        }
+    }
+}
@@ -1,3 +1,8 @@
 pub mod actor;
+pub mod definition;
+pub mod form;
+pub mod queries;
 
 pub use actor::Role;
+pub use definition::RoleDefinition;
+pub use form::{UpdateRole, RoleForm, RoleError};
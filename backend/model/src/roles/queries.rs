@@ -0,0 +1,96 @@
+use anyhow::Result;
+
+use library::DBManager;
+
+use crate::roles::RoleDefinition;
+
+const SELECT: &str = r#"
+    SELECT role.id, role.created_at, role.updated_at, role.name, role.description,
+        COALESCE(
+            ARRAY_AGG(permission.name ORDER BY permission.name) FILTER (WHERE permission.name IS NOT NULL),
+            '{}'
+        ) AS permissions
+    FROM role
+    LEFT JOIN role_permission ON role_permission.role_id = role.id
+    LEFT JOIN permission ON permission.id = role_permission.permission_id
+"#;
+
+impl RoleDefinition {
+    pub async fn select_all(manager: &DBManager) -> Result<Vec<Self>> {
+        let query = format!("{SELECT} GROUP BY role.id ORDER BY role.name ASC");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_id<I>(manager: &DBManager, id: I) -> Result<Option<Self>>
+        where I: ToString
+    {
+        let query = format!("{SELECT} WHERE role.id = $1 GROUP BY role.id");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(id.to_string())
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_by_name<N>(manager: &DBManager, name: N) -> Result<Option<Self>>
+        where N: ToString
+    {
+        let query = format!("{SELECT} WHERE role.name = $1 GROUP BY role.id");
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(name.to_string())
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Update the description, the name stays the one of its `Role`
+    pub async fn update(&self, manager: &DBManager) -> Result<Self> {
+        sqlx::query("UPDATE role SET description = $2 WHERE id = $1")
+            .bind(&self.id)
+            .bind(&self.description)
+            .execute(manager.writer())
+            .await?;
+
+        Self::select_by_id(manager, &self.id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Unable to retrieve role"))
+    }
+
+    /// Grant permission to the role, granting it twice is a no-op
+    pub async fn grant<R, P>(manager: &DBManager, role_id: R, permission_id: P) -> Result<u64>
+        where R: ToString,
+              P: ToString
+    {
+        let result = sqlx::query(r#"
+            INSERT INTO role_permission (role_id, permission_id) VALUES ($1, $2)
+            ON CONFLICT (role_id, permission_id) DO NOTHING
+        "#).bind(role_id.to_string())
+            .bind(permission_id.to_string())
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn revoke<R, P>(manager: &DBManager, role_id: R, permission_id: P) -> Result<u64>
+        where R: ToString,
+              P: ToString
+    {
+        let result = sqlx::query("DELETE FROM role_permission WHERE role_id = $1 AND permission_id = $2")
+            .bind(role_id.to_string())
+            .bind(permission_id.to_string())
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
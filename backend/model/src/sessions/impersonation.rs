@@ -0,0 +1,102 @@
+use async_graphql::{Context, Result, SimpleObject};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::{Claims, Core, Errors, ImpersonationAudit, Paseto, UserAgent};
+use library::impersonation::START;
+
+use crate::{Actor, Role, Session, Status};
+
+/// Short lived access token acting as another actor, there is no refresh token
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct Impersonation {
+    pub access: String,
+    pub expires_at: DateTime<Utc>,
+    pub actor_id: String,
+    pub impersonator_id: String,
+}
+
+impl Session {
+    /// Issue a token acting as the actor, bound to the impersonator's session.
+    /// Admins may only impersonate guests of their own company, controllers anyone but other controllers.
+    pub async fn impersonate(ctx: &Context<'_>, actor_id: &str) -> Result<Impersonation> {
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+        let claims = Session::claims(ctx)?;
+
+        if claims.is_impersonated() {
+            return Err(Errors::forbidden(locale.lookup("session-impersonation-forbidden")));
+        }
+
+        let impersonator_id = claims.aid
+            .clone()
+            .unwrap_or_default();
+
+        // Admins only find actors of their company, controllers are unscoped
+        let actor = Actor::select_by_id(manager, actor_id)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("session-impersonation-not-found")))?;
+
+        let is_allowed = match claims.role.unwrap_or_default() {
+            Role::Controller => actor.role != Role::Controller,
+            Role::Admin => actor.role == Role::Guest && actor.company_id.is_some() && actor.company_id == claims.cid,
+            Role::Guest => false
+        };
+
+        if actor.id == impersonator_id || !is_allowed {
+            return Err(Errors::forbidden(locale.lookup("session-impersonation-denied")));
+        }
+
+        if !actor.is_active() {
+            return Err(Errors::forbidden(locale.lookup("actor-inactive")));
+        }
+
+        let impersonated = Claims::<Role, Status> {
+            aid: Some(actor.id.clone()),
+            sid: claims.sid.clone(),
+            cid: actor.company_id.clone(),
+            iid: Some(impersonator_id.clone()),
+            role: Some(actor.role),
+            status: Some(actor.status),
+            ..Default::default()
+        };
+
+        let expires_at = Paseto::get_expiration_date(&Duration::minutes(config::IMPERSONATION_TOKEN_TTL));
+        let access = Core::paseto(ctx)?
+            .generate_impersonation_token(&actor.id, &impersonated, &expires_at)?;
+
+        // Record the start before handing out the token
+        if let Some(mut audit) = ImpersonationAudit::from_claims(&impersonated, START) {
+            audit.set_ip(UserAgent::get(ctx).ok().and_then(|user_agent| user_agent.ip))
+                .insert(manager)
+                .await
+                .map_err(|_| Errors::internal_server_error(locale.lookup("session-impersonation-audit-failed")))?;
+        }
+
+        Ok(Impersonation {
+            access,
+            expires_at,
+            actor_id: actor.id,
+            impersonator_id,
+        })
+    }
+
+    /// Actor impersonating the current one, if any
+    pub fn impersonator_id(ctx: &Context<'_>) -> Option<String> {
+        ctx.data_opt::<Claims<Role, Status>>()
+            .and_then(|claims| claims.iid.clone())
+    }
+
+    /// Refuse sensitive actions such as password & second factor changes while impersonating
+    pub fn deny_impersonation(ctx: &Context<'_>) -> Result<()> {
+        if Self::impersonator_id(ctx).is_none() {
+            return Ok(());
+        }
+
+        let locale = Core::locales(ctx)?;
+
+        Err(Errors::forbidden(locale.lookup("session-impersonation-forbidden")))
+    }
+}
@@ -0,0 +1,245 @@
+pub mod impersonation;
+pub mod queries;
+
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+use library::{Claims, Core, Errors, ExpiredToken, Paseto, Token, UserAgent};
+use library::hashes::sha256;
+
+use crate::{Actor, Role, Status};
+
+pub use impersonation::Impersonation;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(sqlx::FromRow, SimpleObject)]
+#[graphql(complex)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+    pub id: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub actor_id: Option<String>,
+    pub product_name: Option<String>,
+    pub product_major: Option<String>,
+    pub product_minor: Option<String>,
+    pub product_patch: Option<String>,
+    pub os_name: Option<String>,
+    pub os_major: Option<String>,
+    pub os_minor: Option<String>,
+    pub os_patch: Option<String>,
+    pub os_patch_minor: Option<String>,
+    pub device_name: Option<String>,
+    pub device_brand: Option<String>,
+    pub device_model: Option<String>,
+    pub cpu_architecture: Option<String>,
+    pub engine_name: Option<String>,
+    pub engine_major: Option<String>,
+    pub engine_minor: Option<String>,
+    pub engine_patch: Option<String>,
+    pub ip: Option<String>,
+    pub refreshed_at: Option<DateTime<Utc>>,
+    #[graphql(skip)]
+    #[serde(skip)]
+    pub refresh_token_hash: Option<String>,
+}
+
+#[ComplexObject]
+impl Session {
+    /// Whether this is the session used by the current request
+    async fn is_current(&self, ctx: &Context<'_>) -> bool {
+        Self::claims(ctx)
+            .map(|claims| claims.sid.as_deref() == Some(self.id.as_str()))
+            .unwrap_or(false)
+    }
+}
+
+impl Session {
+    pub fn new<T>(actor_id: T, user_agent: &UserAgent, expires_at: DateTime<Utc>) -> Self
+        where T: ToString
+    {
+        let ua = user_agent.clone();
+
+        Self {
+            id: nanoid::nanoid!(),
+            expires_at: Some(expires_at),
+            actor_id: Some(actor_id.to_string()),
+            product_name: ua.product.name,
+            product_major: ua.product.major,
+            product_minor: ua.product.minor,
+            product_patch: ua.product.patch,
+            os_name: ua.os.name,
+            os_major: ua.os.major,
+            os_minor: ua.os.minor,
+            os_patch: ua.os.patch,
+            os_patch_minor: ua.os.patch_minor,
+            device_name: ua.device.name,
+            device_brand: ua.device.brand,
+            device_model: ua.device.model,
+            cpu_architecture: ua.cpu.architecture,
+            engine_name: ua.engine.name,
+            engine_major: ua.engine.major,
+            engine_minor: ua.engine.minor,
+            engine_patch: ua.engine.patch,
+            ip: ua.ip,
+            ..Default::default()
+        }
+    }
+
+    /// Retrieve claims of the currently authenticated actor.
+    /// Fails with unauthorized unless both actor id and session id are present.
+    pub fn claims(ctx: &Context<'_>) -> Result<Claims<Role, Status>> {
+        let locale = Core::locales(ctx)?;
+
+        if ctx.data_opt::<ExpiredToken>().is_some() {
+            return Err(Errors::unauthorized(locale.lookup("session-expired")));
+        }
+
+        match ctx.data_opt::<Claims<Role, Status>>() {
+            Some(claims) if claims.aid.is_some() && claims.sid.is_some() => Ok(claims.clone()),
+            _ => Err(Errors::unauthorized(locale.lookup("session-unauthenticated")))
+        }
+    }
+
+    /// Generate an access & refresh token pair bound to this session
+    pub fn generate_tokens(&self, paseto: &Paseto, actor: &Actor) -> Result<Token> {
+        let claims = Claims::<Role, Status> {
+            aid: Some(actor.id.clone()),
+            sid: Some(self.id.clone()),
+            cid: actor.company_id.clone(),
+            mfa: actor.get_account_mfa().is_some_and(|mfa| mfa.is_enabled()).then_some(true),
+            role: Some(actor.role),
+            status: Some(actor.status),
+            ..Default::default()
+        };
+
+        paseto.generate_tokens(&actor.id, &claims)
+    }
+
+    /// Create a new session for the actor and return its token pair
+    pub async fn start(ctx: &Context<'_>, actor: &Actor) -> Result<Token> {
+        let manager = Core::database(ctx)?;
+        let paseto = Core::paseto(ctx)?.clone();
+        let user_agent = UserAgent::get(ctx)?;
+
+        // Create session, then bind the refresh token to it
+        let mut session = Self::new(&actor.id, &user_agent, paseto.get_refresh_token_expiry());
+        let tokens = session.generate_tokens(&paseto, actor)?;
+        session.refresh_token_hash = Some(sha256(&tokens.refresh));
+
+        session.insert(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok(tokens)
+    }
+
+    /// Exchange a refresh token for a new token pair.
+    /// Presenting a refresh token that was already rotated revokes every session of the actor.
+    /// Requests carry no access token, so the actor is looked up as system work.
+    pub async fn rotate(ctx: &Context<'_>, token: &str) -> Result<Token> {
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+        let paseto = Core::paseto(ctx)?.clone();
+
+        // Validate refresh token
+        let claims: Claims<Role, Status> = match paseto.validate_refresh_token(token) {
+            Ok(claims) => claims,
+            Err(error) => return match error.to_string().to_lowercase().as_str() {
+                "your refresh token has expired" => Err(Errors::unauthorized(locale.lookup("session-refresh-expired"))),
+                _ => Err(Errors::unauthorized(locale.lookup("session-refresh-invalid")))
+            }
+        };
+
+        let (Some(aid), Some(sid)) = (claims.aid, claims.sid) else {
+            return Err(Errors::unauthorized(locale.lookup("session-refresh-invalid")));
+        };
+
+        // Retrieve live session
+        let session = Self::select_active(manager, &sid, &aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::unauthorized(locale.lookup("session-refresh-invalid")))?;
+
+        // Check if refresh token was already rotated
+        let hash = sha256(token);
+
+        if session.refresh_token_hash.as_deref() != Some(hash.as_str()) {
+            Self::revoke_all(ctx, &aid).await?;
+
+            return Err(Errors::unauthorized(locale.lookup("session-refresh-reused")));
+        }
+
+        // Retrieve latest actor information
+        let actor = Actor::select_by_id(manager, &aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::unauthorized(locale.lookup("session-refresh-invalid")))?;
+
+        if !actor.is_active() {
+            return Err(Errors::forbidden(locale.lookup("actor-inactive")));
+        }
+
+        // Issue new tokens and swap refresh token hash
+        let tokens = session.generate_tokens(&paseto, &actor)?;
+        let expires_at = paseto.get_refresh_token_expiry();
+
+        let is_rotated = session.swap_refresh_token(manager, &hash, &sha256(&tokens.refresh), expires_at)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Another request rotated this token first, treat it as reuse
+        if !is_rotated {
+            Self::revoke_all(ctx, &aid).await?;
+
+            return Err(Errors::unauthorized(locale.lookup("session-refresh-reused")));
+        }
+
+        Ok(tokens)
+    }
+
+    /// Revoke a single session of the actor
+    pub async fn revoke(ctx: &Context<'_>, sid: &str, aid: &str) -> Result<bool> {
+        let manager = Core::database(ctx)?;
+
+        let count = Self::delete(manager, sid, aid)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Core::sessions(ctx)?.invalidate(sid);
+
+        Ok(count > 0)
+    }
+
+    /// Revoke every session of the actor
+    pub async fn revoke_all(ctx: &Context<'_>, aid: &str) -> Result<u64> {
+        let manager = Core::database(ctx)?;
+
+        let count = Self::delete_by_actor(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Core::sessions(ctx)?.invalidate_actor(aid);
+
+        Ok(count)
+    }
+
+    /// Revoke every session of the actor except the given one
+    pub async fn revoke_others(ctx: &Context<'_>, sid: &str, aid: &str) -> Result<u64> {
+        let manager = Core::database(ctx)?;
+
+        let ids = Self::delete_others(manager, sid, aid)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        let sessions = Core::sessions(ctx)?;
+
+        for id in ids.iter() {
+            sessions.invalidate(id);
+        }
+
+        Ok(ids.len() as u64)
+    }
+}
@@ -0,0 +1,156 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use library::DBManager;
+
+use crate::Session;
+
+const COLUMNS: &str = r#"
+    id, created_at, updated_at, expires_at, actor_id,
+    product_name, product_major, product_minor, product_patch,
+    os_name, os_major, os_minor, os_patch, os_patch_minor,
+    device_name, device_brand, device_model, cpu_architecture,
+    engine_name, engine_major, engine_minor, engine_patch, ip,
+    refreshed_at, refresh_token_hash
+"#;
+
+impl Session {
+    pub async fn select_active<I, A>(manager: &DBManager, id: I, actor_id: A) -> Result<Option<Self>>
+        where I: ToString,
+              A: ToString
+    {
+        let query = format!(r#"
+            SELECT {COLUMNS} FROM session
+            WHERE id = $1 AND actor_id = $2 AND expires_at > NOW()
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(id.to_string())
+            .bind(actor_id.to_string())
+            .fetch_optional(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn select_active_by_actor<A>(manager: &DBManager, actor_id: A) -> Result<Vec<Self>>
+        where A: ToString
+    {
+        let query = format!(r#"
+            SELECT {COLUMNS} FROM session
+            WHERE actor_id = $1 AND expires_at > NOW()
+            ORDER BY COALESCE(refreshed_at, created_at) DESC
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(actor_id.to_string())
+            .fetch_all(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn insert(&self, manager: &DBManager) -> Result<Self> {
+        let query = format!(r#"
+            INSERT INTO session (
+                id, expires_at, actor_id,
+                product_name, product_major, product_minor, product_patch,
+                os_name, os_major, os_minor, os_patch, os_patch_minor,
+                device_name, device_brand, device_model, cpu_architecture,
+                engine_name, engine_major, engine_minor, engine_patch, ip,
+                refresh_token_hash
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22)
+            RETURNING {COLUMNS}
+        "#);
+
+        let result = sqlx::query_as::<_, Self>(&query)
+            .bind(&self.id)
+            .bind(self.expires_at)
+            .bind(&self.actor_id)
+            .bind(&self.product_name)
+            .bind(&self.product_major)
+            .bind(&self.product_minor)
+            .bind(&self.product_patch)
+            .bind(&self.os_name)
+            .bind(&self.os_major)
+            .bind(&self.os_minor)
+            .bind(&self.os_patch)
+            .bind(&self.os_patch_minor)
+            .bind(&self.device_name)
+            .bind(&self.device_brand)
+            .bind(&self.device_model)
+            .bind(&self.cpu_architecture)
+            .bind(&self.engine_name)
+            .bind(&self.engine_major)
+            .bind(&self.engine_minor)
+            .bind(&self.engine_patch)
+            .bind(&self.ip)
+            .bind(&self.refresh_token_hash)
+            .fetch_one(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    pub async fn delete<I, A>(manager: &DBManager, id: I, actor_id: A) -> Result<u64>
+        where I: ToString,
+              A: ToString
+    {
+        let result = sqlx::query("DELETE FROM session WHERE id = $1 AND actor_id = $2")
+            .bind(id.to_string())
+            .bind(actor_id.to_string())
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_actor<A>(manager: &DBManager, actor_id: A) -> Result<u64>
+        where A: ToString
+    {
+        let result = sqlx::query("DELETE FROM session WHERE actor_id = $1")
+            .bind(actor_id.to_string())
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_others<I, A>(manager: &DBManager, id: I, actor_id: A) -> Result<Vec<String>>
+        where I: ToString,
+              A: ToString
+    {
+        let result = sqlx::query_scalar::<_, String>(
+            "DELETE FROM session WHERE actor_id = $2 AND id <> $1 RETURNING id"
+        ).bind(id.to_string())
+            .bind(actor_id.to_string())
+            .fetch_all(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Replace the current refresh token hash only if it still matches `current`.
+    /// Returns false when another request has already rotated it.
+    pub async fn swap_refresh_token(
+        &self,
+        manager: &DBManager,
+        current: &str,
+        next: &str,
+        expires_at: DateTime<Utc>
+    ) -> Result<bool> {
+        let result = sqlx::query(r#"
+            UPDATE session
+            SET refresh_token_hash = $3, refreshed_at = NOW(), expires_at = $4
+            WHERE id = $1 AND refresh_token_hash = $2
+        "#).bind(&self.id)
+            .bind(current)
+            .bind(next)
+            .bind(expires_at)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,72 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors};
+use model::{Actor, ApiKey, Session};
+use model::api_keys::{ApiKeyForm, CreateApiKey, CreatedApiKey};
+
+#[derive(Default)]
+pub struct ApiKeyMutation;
+
+#[Object]
+impl ApiKeyMutation {
+    #[autometrics::autometrics]
+    async fn create(&self, ctx: &Context<'_>, mut form: ApiKeyForm) -> Result<CreatedApiKey> {
+        // Keys are not issued or revoked while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Resolve owner, the controller issues keys on behalf of an actor
+        let manager_id = ApiKey::manager_id(ctx)?;
+
+        // Validate form and convert it to CreateApiKey struct if it's valid
+        let form = form.validate(ctx)?
+            .to::<CreateApiKey>();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let aid = manager_id
+            .or_else(|| form.actor_id.clone())
+            .unwrap_or_default();
+
+        let actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))?;
+
+        // Create key
+        let (key, api_key) = ApiKey::new(&actor.id, &form);
+
+        let api_key = api_key.insert(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok(CreatedApiKey {
+            api_key,
+            key,
+        })
+    }
+
+    #[autometrics::autometrics]
+    async fn revoke(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        // Keys are not issued or revoked while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Resolve whose keys can be revoked
+        let manager_id = ApiKey::manager_id(ctx)?;
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let count = ApiKey::revoke(manager, &id, manager_id)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        if count == 0 {
+            return Err(Errors::not_found(locale.lookup("api-key-not-found")));
+        }
+
+        Ok(locale.lookup("api-key-revoke-success"))
+    }
+}
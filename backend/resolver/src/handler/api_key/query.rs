@@ -0,0 +1,39 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors};
+use library::filters;
+use library::pagination::{paginate, PageConnection};
+use model::ApiKey;
+use model::api_keys::{ApiKeyFilter, ApiKeyOrder};
+
+#[derive(Default)]
+pub struct ApiKeyQuery;
+
+#[Object]
+impl ApiKeyQuery {
+    // Relay pagination arguments are top level by convention
+    #[allow(clippy::too_many_arguments)]
+    #[autometrics::autometrics]
+    #[graphql(complexity = "library::pagination::complexity(first, last, child_complexity)")]
+    async fn list(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+        filter: Option<ApiKeyFilter>,
+        order_by: Option<Vec<ApiKeyOrder>>,
+    ) -> Result<PageConnection<ApiKey>> {
+        // Resolve whose keys are visible
+        let manager_id = ApiKey::manager_id(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let orders = filters::orders(&order_by.unwrap_or_default())
+            .map_err(Errors::bad_request)?;
+
+        paginate(ctx, after, before, first, last, |page| async move {
+            ApiKey::select_page(manager, manager_id, filter.as_ref(), &orders, &page).await
+        }).await
+    }
+}
@@ -0,0 +1,2 @@
+pub mod mutation;
+pub mod query;
\ No newline at end of file
@@ -0,0 +1,439 @@
+use async_graphql::{Context, MaybeUndefined, Object, Result};
+use serde_json::json;
+
+use library::{Core, Errors, Password, Response, Token, UserAgent, Validator};
+use library::hashes::sha256;
+use model::{Actor, Guard, Role, Session, Status};
+use model::sessions::Impersonation;
+use model::actors::{AccountToken, AccountVerification, SignIn, SignInPayload, SignInForm, SignUp, SignUpError, SignUpForm};
+
+#[derive(Default)]
+pub struct AuthMutation;
+
+#[Object]
+impl AuthMutation {
+    #[autometrics::autometrics]
+    async fn sign_up(&self, ctx: &Context<'_>, mut form: SignUpForm) -> Result<Token> {
+        // Validate form and convert it to SignUp struct if it's valid
+        let form = form.validate(ctx)?
+            .to::<SignUp>();
+
+        // Retrieve locale and system database manager
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+
+        // Check if email is already taken
+        let email = form.email.to_lowercase();
+        let existing = Actor::select_by_email(manager, &email)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        if existing.is_some() {
+            return Err(Errors::to(Response::BadRequest, SignUpError {
+                email: Some(locale.lookup("actor-email-taken")),
+                ..Default::default()
+            }));
+        }
+
+        // Hash password
+        let password = Password::hash(&form.password)
+            .map_err(|_| Errors::internal_server_error(locale.lookup("password-hash-failed")))?;
+
+        // Create actor
+        let mut actor = Actor::new();
+        actor.email = Some(email);
+        actor.first_name = Some(form.first_name);
+        actor.last_name = Some(form.last_name);
+
+        let actor = actor.set_password(password)
+            .insert(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Create session and return tokens
+        Session::start(ctx, &actor).await
+    }
+
+    #[autometrics::autometrics]
+    async fn sign_in(&self, ctx: &Context<'_>, mut form: SignInForm) -> Result<SignInPayload> {
+        // Validate form and convert it to SignIn struct if it's valid
+        let form = form.validate(ctx)?
+            .to::<SignIn>();
+
+        // Retrieve locale, system database manager and client ip
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+        let error = locale.lookup("actor-invalid-credentials");
+        let ip = UserAgent::get(ctx)?.ip.unwrap_or_default();
+        let minutes = config::ATTEMPT_RETRY_DURATION.to_string();
+
+        // Throttle ips with too many failed attempts across every email, unknown ips cannot be told apart
+        if !ip.is_empty() {
+            let ip_attempts = Actor::count_ip_sign_in_attempts(manager, &ip)
+                .await
+                .map_err(Errors::bad_request)?;
+
+            if ip_attempts >= config::ATTEMPT_RETRY_MAX as i64 {
+                return Err(Errors::too_many_requests(locale.lookup_with_args(
+                    "actor-sign-in-throttled",
+                    &[("minutes", minutes.as_str())]
+                )));
+            }
+        }
+
+        // Retrieve actor, unknown emails still count towards the ip throttle
+        let actor = Actor::select_by_email(manager, &form.email)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        let Some(mut actor) = actor else {
+            // Unknown emails take as long as a wrong password
+            Password::verify_dummy(&form.password);
+
+            if !ip.is_empty() {
+                Actor::record_ip_sign_in_failure(manager, &ip)
+                    .await
+                    .map_err(Errors::bad_request)?;
+            }
+
+            return Err(Errors::unauthorized(&error));
+        };
+
+        // Verify password, actors signing in through a provider have none
+        let is_valid = match actor.password.as_ref() {
+            Some(password) => password.verify(&form.password),
+            None => {
+                Password::verify_dummy(&form.password);
+                false
+            }
+        };
+
+        if !is_valid {
+            // Record failed attempt and lock the account once the max is reached
+            return Err(actor.record_failed_sign_in(ctx, &ip, error).await?);
+        }
+
+        // Only reveal the lock to callers who know the password
+        if actor.is_locked() {
+            return Err(Errors::too_many_requests(locale.lookup_with_args(
+                "actor-sign-in-locked",
+                &[("minutes", minutes.as_str())]
+            )));
+        }
+
+        // Check if actor is allowed to sign in
+        if !actor.is_active() {
+            return Err(Errors::forbidden(locale.lookup("actor-inactive")));
+        }
+
+        // Clear failed attempts
+        actor.record_sign_in_success(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Rehash password if it was hashed with outdated parameters
+        let needs_rehash = actor.password
+            .as_ref()
+            .map(|password| password.needs_rehash())
+            .unwrap_or(false);
+
+        if needs_rehash {
+            if let Ok(password) = Password::hash(&form.password) {
+                actor.set_password(password)
+                    .update_password(manager)
+                    .await
+                    .map_err(Errors::bad_request)?;
+            }
+        }
+
+        // Ask for the second factor or create session and return tokens
+        SignInPayload::start(ctx, &actor).await
+    }
+
+    #[autometrics::autometrics]
+    async fn verify_mfa(&self, ctx: &Context<'_>, mfa_token: String, code: String) -> Result<Token> {
+        // Retrieve locale, system database manager and client ip
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+        let ip = UserAgent::get(ctx)?.ip.unwrap_or_default();
+
+        // Validate mfa token
+        let aid = match Core::paseto(ctx)?.clone().validate_mfa_token(mfa_token.trim()) {
+            Ok(aid) => aid,
+            Err(error) => return match error.to_string().to_lowercase().as_str() {
+                "your mfa token has expired" => Err(Errors::unauthorized(locale.lookup("mfa-token-expired"))),
+                _ => Err(Errors::unauthorized(locale.lookup("mfa-token-invalid")))
+            }
+        };
+
+        // Retrieve actor
+        let mut actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::unauthorized(locale.lookup("mfa-token-invalid")))?;
+
+        if actor.is_locked() {
+            let minutes = config::ATTEMPT_RETRY_DURATION.to_string();
+            return Err(Errors::too_many_requests(locale.lookup_with_args(
+                "actor-sign-in-locked",
+                &[("minutes", minutes.as_str())]
+            )));
+        }
+
+        if !actor.is_active() {
+            return Err(Errors::forbidden(locale.lookup("actor-inactive")));
+        }
+
+        let mut mfa = actor.get_account_mfa()
+            .filter(|mfa| mfa.is_enabled())
+            .ok_or_else(|| Errors::unauthorized(locale.lookup("mfa-token-invalid")))?;
+
+        // Verify totp or recovery code, failures count towards the account lock
+        if !mfa.verify(&code) {
+            let error = locale.lookup("mfa-invalid-code");
+            return Err(actor.record_failed_sign_in(ctx, &ip, error).await?);
+        }
+
+        // Persist used step or consumed recovery code
+        actor.set_account_mfa(Some(mfa))
+            .update_account_mfa(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Create session and return tokens
+        Session::start(ctx, &actor).await
+    }
+
+    #[autometrics::autometrics]
+    async fn refresh_token(&self, ctx: &Context<'_>, token: String) -> Result<Token> {
+        // Rotate refresh token and return the new token pair
+        Session::rotate(ctx, token.trim()).await
+    }
+
+    /// Act as another actor to reproduce their issues, every request made with the token is audited
+    #[graphql(guard = "Guard::role_and_status(vec![Role::Controller, Role::Admin], vec![Status::Active])")]
+    #[autometrics::autometrics]
+    async fn impersonate(&self, ctx: &Context<'_>, actor_id: String) -> Result<Impersonation> {
+        Session::impersonate(ctx, actor_id.trim()).await
+    }
+
+    #[autometrics::autometrics]
+    async fn sign_out(&self, ctx: &Context<'_>) -> Result<String> {
+        // Session & password changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+        let sid = claims.sid.unwrap_or_default();
+
+        // Revoke session
+        Session::revoke(ctx, &sid, &aid).await?;
+
+        Ok(Core::locales(ctx)?.lookup("session-sign-out-success"))
+    }
+
+    #[autometrics::autometrics]
+    async fn revoke_session(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        // Session & password changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+        let locale = Core::locales(ctx)?;
+
+        // Revoke session owned by the current actor
+        if !Session::revoke(ctx, &id, &aid).await? {
+            return Err(Errors::not_found(locale.lookup("session-not-found")));
+        }
+
+        Ok(locale.lookup("session-revoke-success"))
+    }
+
+    #[autometrics::autometrics]
+    async fn revoke_other_sessions(&self, ctx: &Context<'_>) -> Result<String> {
+        // Session & password changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+        let sid = claims.sid.unwrap_or_default();
+
+        // Revoke every other session of the current actor
+        let count = Session::revoke_others(ctx, &sid, &aid).await?;
+
+        Ok(Core::locales(ctx)?.lookup_with_args(
+            "session-revoke-others-success",
+            &[("count", count.to_string().as_str())]
+        ))
+    }
+
+    #[autometrics::autometrics]
+    async fn request_email_verification(&self, ctx: &Context<'_>) -> Result<String> {
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        // Retrieve actor
+        let mut actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))?;
+
+        if actor.is_verified() {
+            return Err(Errors::bad_request(locale.lookup("actor-email-verified")));
+        }
+
+        // Generate token, replacing any previously issued one
+        let ttl = config::ACCOUNT_VERIFICATION_TTL;
+        let (token, account_token) = AccountToken::generate(ttl);
+
+        actor.set_account_verification(AccountVerification {
+            verified_at: None,
+            token: Some(account_token),
+        }).update_account_verification(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Send email
+        let web_url = Core::base(ctx)?.get_web_url().to_string();
+        let action_url = format!("{web_url}{}?token={token}", config::ACCOUNT_VERIFICATION_PATH);
+
+        let from = config::MAILER_FROM_NO_REPLY;
+        let to = actor.email.clone().unwrap_or_default();
+        let subject = locale.lookup("mailer-verification-subject");
+
+        Core::mailer(ctx)?
+            .set_template("emails/account/verification.html")
+            .set_context(json!({
+                "title": locale.lookup("mailer-verification-title"),
+                "greeting": locale.lookup_with_args(
+                    "mailer-greeting",
+                    &[("name", actor.first_name.clone().unwrap_or_default().as_str())]
+                ),
+                "message": locale.lookup("mailer-verification-message"),
+                "action": locale.lookup("mailer-verification-action"),
+                "action_url": action_url,
+                "expiry": locale.lookup_with_args("mailer-expiry", &[("minutes", ttl.to_string().as_str())]),
+                "ignore": locale.lookup("mailer-ignore"),
+                "web_url": web_url,
+            }))
+            .send(from, to, subject)?;
+
+        Ok(locale.lookup("actor-verification-sent"))
+    }
+
+    #[autometrics::autometrics]
+    async fn verify_email(&self, ctx: &Context<'_>, token: String) -> Result<String> {
+        // Retrieve locale and system database manager
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+
+        // Consume verification token
+        Actor::consume_verification_token(manager, &sha256(token.trim()))
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::bad_request(locale.lookup("actor-verification-invalid")))?;
+
+        Ok(locale.lookup("actor-verification-success"))
+    }
+
+    #[autometrics::autometrics]
+    async fn request_password_reset(&self, ctx: &Context<'_>, email: String) -> Result<String> {
+        // Session & password changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve locale and system database manager
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+
+        // Respond the same way whether or not the actor exists
+        let success = locale.lookup("actor-reset-password-sent");
+
+        let actor = Actor::select_by_email(manager, email.trim())
+            .await
+            .map_err(Errors::bad_request)?;
+
+        let Some(mut actor) = actor.filter(|actor| actor.is_active()) else {
+            return Ok(success);
+        };
+
+        // Generate token, replacing any previously issued one
+        let ttl = config::ACCOUNT_RESET_PASSWORD_TTL;
+        let (token, account_token) = AccountToken::generate(ttl);
+
+        actor.set_account_reset_password(account_token)
+            .update_account_reset_password(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Send email
+        let web_url = Core::base(ctx)?.get_web_url().to_string();
+        let action_url = format!("{web_url}{}?token={token}", config::ACCOUNT_RESET_PASSWORD_PATH);
+
+        let from = config::MAILER_FROM_NO_REPLY;
+        let to = actor.email.clone().unwrap_or_default();
+        let subject = locale.lookup("mailer-reset-password-subject");
+
+        Core::mailer(ctx)?
+            .set_template("emails/account/reset_password.html")
+            .set_context(json!({
+                "title": locale.lookup("mailer-reset-password-title"),
+                "greeting": locale.lookup_with_args(
+                    "mailer-greeting",
+                    &[("name", actor.first_name.clone().unwrap_or_default().as_str())]
+                ),
+                "message": locale.lookup("mailer-reset-password-message"),
+                "action": locale.lookup("mailer-reset-password-action"),
+                "action_url": action_url,
+                "expiry": locale.lookup_with_args("mailer-expiry", &[("minutes", ttl.to_string().as_str())]),
+                "ignore": locale.lookup("mailer-ignore"),
+                "web_url": web_url,
+            }))
+            .send(from, to, subject)?;
+
+        Ok(success)
+    }
+
+    #[autometrics::autometrics]
+    async fn reset_password(&self, ctx: &Context<'_>, token: String, new_password: String) -> Result<String> {
+        // Session & password changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve locale, password policy and system database manager
+        let locale = Core::locales(ctx)?;
+        let policy = Core::passwords(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+
+        // Validate new password
+        let error = Validator::new(locale, "actor-password")
+            .set_as_required(true)
+            .set_string_value(&MaybeUndefined::Value(new_password.clone()))
+            .validate_password(policy);
+
+        if let Some(error) = error {
+            return Err(Errors::bad_request(error));
+        }
+
+        // Hash password
+        let password = Password::hash(&new_password)
+            .map_err(|_| Errors::internal_server_error(locale.lookup("password-hash-failed")))?;
+
+        // Consume reset token and replace password
+        let aid = Actor::consume_reset_password_token(manager, &sha256(token.trim()), &password)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::bad_request(locale.lookup("actor-reset-password-invalid")))?;
+
+        // Sign out every session of the actor
+        Session::revoke_all(ctx, &aid).await?;
+
+        Ok(locale.lookup("actor-reset-password-success"))
+    }
+}
@@ -0,0 +1,41 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors};
+use model::{Actor, Session};
+
+#[derive(Default)]
+pub struct AuthQuery;
+
+#[Object]
+impl AuthQuery {
+    #[autometrics::autometrics]
+    async fn me(&self, ctx: &Context<'_>) -> Result<Actor> {
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))
+    }
+
+    #[autometrics::autometrics]
+    #[graphql(complexity = "1 + config::QUERY_LIST_COST * child_complexity")]
+    async fn my_sessions(&self, ctx: &Context<'_>) -> Result<Vec<Session>> {
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve active sessions of the current actor
+        let manager = Core::database(ctx)?;
+
+        Session::select_active_by_actor(manager, aid)
+            .await
+            .map_err(Errors::bad_request)
+    }
+}
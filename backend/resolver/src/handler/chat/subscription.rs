@@ -0,0 +1,49 @@
+use async_graphql::{Context, Result, Subscription};
+use async_graphql::futures_util::{Stream, StreamExt};
+
+use library::{Claims, Core};
+use library::prelude::CustomRole;
+use library::subscriptions::{MESSAGE_CREATED, MessageCreated};
+
+use model::{Guard, Loaders, Message, Role, Status};
+
+#[derive(Default)]
+pub struct ChatSubscription;
+
+#[Subscription]
+impl ChatSubscription {
+    /// Emits every message posted to the chat room the signed in actor has access to.
+    /// The stream ends once the session is revoked or the actor loses access to the room.
+    #[autometrics::autometrics]
+    #[graphql(guard = "Guard::authenticated()")]
+    async fn chat_messages<'ctx>(&self, ctx: &Context<'ctx>, chat_room_id: String) -> Result<impl Stream<Item = Message> + 'ctx> {
+        Message::check_room_access(ctx, &chat_room_id).await?;
+
+        let loaders = Loaders::get(ctx)?;
+
+        // Kept by the stream to check access again for every message
+        let manager = Core::database(ctx)?.clone();
+        let role = Role::get(ctx);
+        let claims = ctx.data_opt::<Claims<Role, Status>>().cloned();
+
+        let stream = Core::subscriptions(ctx)?
+            .subscribe::<MessageCreated>(MESSAGE_CREATED)
+            .filter(move |event| std::future::ready(event.chat_room_id == chat_room_id))
+            .take_while(move |event| {
+                let manager = manager.clone();
+                let claims = claims.clone();
+                let chat_room_id = event.chat_room_id.clone();
+
+                async move {
+                    Message::has_stream_access(&manager, role, claims.as_ref(), &chat_room_id)
+                        .await
+                        .unwrap_or(false)
+                }
+            })
+            .filter_map(move |event| async move {
+                loaders.messages.load_one(event.id).await.ok().flatten()
+            });
+
+        Ok(stream)
+    }
+}
@@ -0,0 +1,165 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors, Totp};
+use model::{Actor, Session};
+use model::actors::{AccountMfa, MfaEnrollment};
+
+#[derive(Default)]
+pub struct MfaMutation;
+
+#[Object]
+impl MfaMutation {
+    #[autometrics::autometrics]
+    async fn enroll(&self, ctx: &Context<'_>) -> Result<MfaEnrollment> {
+        // Second factor changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        // Retrieve actor
+        let mut actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))?;
+
+        if actor.get_account_mfa().is_some_and(|mfa| mfa.is_enabled()) {
+            return Err(Errors::bad_request(locale.lookup("mfa-already-enabled")));
+        }
+
+        // Generate secret, replacing any unconfirmed enrollment
+        let totp = Totp::generate();
+        let mfa = AccountMfa::new(&totp)
+            .map_err(|_| Errors::internal_server_error(locale.lookup("mfa-enroll-failed")))?;
+
+        actor.set_account_mfa(Some(mfa))
+            .update_account_mfa(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok(MfaEnrollment {
+            secret: totp.to_base32(),
+            uri: totp.uri(config::app_name(), actor.email.unwrap_or_default()),
+        })
+    }
+
+    #[autometrics::autometrics]
+    async fn confirm(&self, ctx: &Context<'_>, code: String) -> Result<Vec<String>> {
+        // Second factor changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        // Retrieve actor and pending enrollment
+        let mut actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))?;
+
+        let mut mfa = actor.get_account_mfa()
+            .ok_or_else(|| Errors::bad_request(locale.lookup("mfa-not-enrolled")))?;
+
+        if mfa.is_enabled() {
+            return Err(Errors::bad_request(locale.lookup("mfa-already-enabled")));
+        }
+
+        // Verify code, then enable and issue recovery codes
+        if !mfa.verify_code(&code) {
+            return Err(Errors::bad_request(locale.lookup("mfa-invalid-code")));
+        }
+
+        let recovery_codes = mfa.enable();
+
+        actor.set_account_mfa(Some(mfa))
+            .update_account_mfa(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok(recovery_codes)
+    }
+
+    #[autometrics::autometrics]
+    async fn regenerate_recovery_codes(&self, ctx: &Context<'_>, code: String) -> Result<Vec<String>> {
+        // Second factor changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        // Retrieve actor and enabled mfa
+        let mut actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))?;
+
+        let mut mfa = actor.get_account_mfa()
+            .filter(|mfa| mfa.is_enabled())
+            .ok_or_else(|| Errors::bad_request(locale.lookup("mfa-not-enabled")))?;
+
+        // Verify totp code, then replace recovery codes
+        if !mfa.verify_code(&code) {
+            return Err(Errors::bad_request(locale.lookup("mfa-invalid-code")));
+        }
+
+        let recovery_codes = mfa.regenerate_recovery_codes();
+
+        actor.set_account_mfa(Some(mfa))
+            .update_account_mfa(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok(recovery_codes)
+    }
+
+    #[autometrics::autometrics]
+    async fn disable(&self, ctx: &Context<'_>, code: String) -> Result<String> {
+        // Second factor changes are not allowed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        // Retrieve actor and enabled mfa
+        let mut actor = Actor::select_by_id(manager, aid)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("actor-not-found")))?;
+
+        let mut mfa = actor.get_account_mfa()
+            .filter(|mfa| mfa.is_enabled())
+            .ok_or_else(|| Errors::bad_request(locale.lookup("mfa-not-enabled")))?;
+
+        // Verify totp or recovery code, then remove mfa
+        if !mfa.verify(&code) {
+            return Err(Errors::bad_request(locale.lookup("mfa-invalid-code")));
+        }
+
+        actor.set_account_mfa(None)
+            .update_account_mfa(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok(locale.lookup("mfa-disable-success"))
+    }
+}
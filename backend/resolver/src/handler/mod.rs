@@ -1,7 +1,23 @@
+pub mod api_key;
+pub mod auth;
+pub mod chat;
+pub mod mfa;
+pub mod oauth;
+pub mod role;
 pub mod setup;
 pub mod version;
 
+pub use api_key::mutation::ApiKeyMutation;
+pub use api_key::query::ApiKeyQuery;
+pub use auth::mutation::AuthMutation;
+pub use auth::query::AuthQuery;
+pub use chat::subscription::ChatSubscription;
+pub use mfa::mutation::MfaMutation;
+pub use oauth::mutation::OAuthMutation;
+pub use role::mutation::RoleMutation;
+pub use role::query::RoleQuery;
 pub use version::mutation::VersionMutation;
 pub use version::query::VersionQuery;
 pub use setup::mutation::SetupMutation;
-pub use setup::query::SetupQuery;
\ No newline at end of file
+pub use setup::query::SetupQuery;
+pub use setup::subscription::SetupSubscription;
\ No newline at end of file
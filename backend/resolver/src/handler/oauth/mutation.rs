@@ -0,0 +1,176 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors, OAuthAuthorization, OAuthBinding, OAuthIdentity, OAuthProvider, OAuthRequest, OidcProvider};
+use model::{Actor, ActorOAuth, Session};
+use model::actors::{AccountVerification, SignInPayload};
+
+#[derive(Default)]
+pub struct OAuthMutation;
+
+#[Object]
+impl OAuthMutation {
+    #[autometrics::autometrics]
+    async fn authorize(&self, ctx: &Context<'_>) -> Result<OAuthAuthorization> {
+        let locale = Core::locales(ctx)?;
+
+        // Discover provider endpoints
+        let provider = OidcProvider::discover(&Core::oauth(ctx)?)
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("oauth-discovery-failed")))?;
+
+        // Generate pkce verifier & nonce sealed into the state, bound to this browser by a cookie
+        let binding = OAuthBinding::generate();
+        let authorization = OAuthAuthorization::start(&provider, &binding)
+            .map_err(Errors::internal_server_error)?;
+
+        ctx.insert_http_header("Set-Cookie", binding.cookie());
+
+        Ok(authorization)
+    }
+
+    #[autometrics::autometrics]
+    async fn callback(&self, ctx: &Context<'_>, code: String, state: String) -> Result<SignInPayload> {
+        // Retrieve locale and system database manager
+        let locale = Core::locales(ctx)?;
+        let manager = &Core::system_database(ctx)?;
+
+        // Exchange code and verify the id token
+        let identity = Self::identity(ctx, &code, &state).await?;
+
+        let link = ActorOAuth::select_by_subject(manager, &identity.issuer, &identity.subject)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        let actor = match link {
+            Some(mut link) => {
+                link.email = identity.email.clone();
+                link.update_last_sign_in(manager)
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                Actor::select_by_id(manager, &link.actor_id)
+                    .await
+                    .map_err(Errors::bad_request)?
+                    .ok_or_else(|| Errors::unauthorized(locale.lookup("oauth-callback-failed")))?
+            },
+            None => {
+                // Only verified emails may claim a new account, otherwise anyone could take over an address
+                let email = identity.email
+                    .clone()
+                    .filter(|_| identity.email_verified)
+                    .ok_or_else(|| Errors::forbidden(locale.lookup("oauth-email-unverified")))?;
+
+                let existing = Actor::select_by_email(manager, &email)
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                // Existing accounts are only linked by their signed in owner through `link`
+                if existing.is_some() {
+                    return Err(Errors::forbidden(locale.lookup("oauth-link-required")));
+                }
+
+                // Create actor without a password, it signs in through the provider
+                let mut actor = Actor::new();
+                actor.email = Some(email);
+                actor.first_name = identity.first_name.clone();
+                actor.last_name = identity.last_name.clone();
+
+                // Store the actor, its verification & the link together so a failure leaves no unlinked account
+                let mut transaction = manager.begin()
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                let mut actor = actor.insert_with(&mut *transaction)
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                actor.set_account_verification(AccountVerification::verified())
+                    .update_account_verification_with(&mut *transaction)
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                ActorOAuth::new(&actor.id, &identity)
+                    .insert_with(&mut *transaction)
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                transaction.commit()
+                    .await
+                    .map_err(Errors::bad_request)?;
+
+                actor
+            }
+        };
+
+        if actor.is_locked() {
+            let minutes = config::ATTEMPT_RETRY_DURATION.to_string();
+            return Err(Errors::too_many_requests(locale.lookup_with_args(
+                "actor-sign-in-locked",
+                &[("minutes", minutes.as_str())]
+            )));
+        }
+
+        if !actor.is_active() {
+            return Err(Errors::forbidden(locale.lookup("actor-inactive")));
+        }
+
+        // Ask for the second factor or create session and return tokens
+        SignInPayload::start(ctx, &actor).await
+    }
+
+    /// Link the provider account to the signed in actor, the authorization is started with `authorize`
+    #[autometrics::autometrics]
+    async fn link(&self, ctx: &Context<'_>, code: String, state: String) -> Result<String> {
+        // Sign in methods cannot be changed while impersonating
+        Session::deny_impersonation(ctx)?;
+
+        // Retrieve claims of the current session
+        let claims = Session::claims(ctx)?;
+        let aid = claims.aid.unwrap_or_default();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        // Exchange code and verify the id token
+        let identity = Self::identity(ctx, &code, &state).await?;
+
+        let link = ActorOAuth::select_by_subject(manager, &identity.issuer, &identity.subject)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        match link {
+            Some(link) if link.actor_id == aid => {},
+            Some(_) => return Err(Errors::forbidden(locale.lookup("oauth-link-taken"))),
+            None => {
+                ActorOAuth::new(&aid, &identity)
+                    .insert(manager)
+                    .await
+                    .map_err(Errors::bad_request)?;
+            }
+        }
+
+        Ok(locale.lookup("oauth-link-success"))
+    }
+}
+
+impl OAuthMutation {
+    /// Recover the pending authorization of this browser, then exchange the code for the verified identity
+    async fn identity(ctx: &Context<'_>, code: &str, state: &str) -> Result<OAuthIdentity> {
+        let locale = Core::locales(ctx)?;
+
+        // The binding cookie is single use, whatever the outcome
+        ctx.insert_http_header("Set-Cookie", OAuthBinding::clear_cookie());
+
+        let request = OAuthRequest::unseal(state.trim(), OAuthBinding::get(ctx).as_ref())
+            .map_err(|_| Errors::unauthorized(locale.lookup("oauth-state-invalid")))?;
+
+        let provider = OidcProvider::discover(&Core::oauth(ctx)?)
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("oauth-discovery-failed")))?;
+
+        provider.exchange(code.trim(), &request)
+            .await
+            .map_err(|_| Errors::unauthorized(locale.lookup("oauth-callback-failed")))
+    }
+}
@@ -0,0 +1,136 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors};
+use model::{Permission, RoleDefinition};
+use model::permissions::{CreatePermission, PermissionForm};
+use model::roles::{RoleForm, UpdateRole};
+
+#[derive(Default)]
+pub struct RoleMutation;
+
+#[Object]
+impl RoleMutation {
+    /// Roles are the ones of actors, only their description & grants are managed here
+    #[autometrics::autometrics]
+    async fn update(&self, ctx: &Context<'_>, id: String, mut form: RoleForm) -> Result<RoleDefinition> {
+        // Validate form and convert it to UpdateRole struct if it's valid
+        let form = form.validate(ctx)?
+            .to::<UpdateRole>();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let mut role = RoleDefinition::select_by_id(manager, &id)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("role-not-found")))?;
+
+        role.description = form.description;
+
+        role.update(manager)
+            .await
+            .map_err(Errors::bad_request)
+    }
+
+    #[autometrics::autometrics]
+    async fn create_permission(&self, ctx: &Context<'_>, mut form: PermissionForm) -> Result<Permission> {
+        // Validate form and convert it to CreatePermission struct if it's valid
+        let form = form.validate(ctx)?
+            .to::<CreatePermission>();
+
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let is_taken = Permission::select_by_name(manager, &form.name)
+            .await
+            .map_err(Errors::bad_request)?
+            .is_some();
+
+        if is_taken {
+            return Err(Errors::bad_request(locale.lookup("permission-name-taken")));
+        }
+
+        Permission::new(&form)
+            .insert(manager)
+            .await
+            .map_err(Errors::bad_request)
+    }
+
+    #[autometrics::autometrics]
+    async fn delete_permission(&self, ctx: &Context<'_>, id: String) -> Result<String> {
+        // Retrieve locale and database manager
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let count = Permission::delete(manager, &id)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        if count == 0 {
+            return Err(Errors::not_found(locale.lookup("permission-not-found")));
+        }
+
+        Self::reload(ctx).await?;
+
+        Ok(locale.lookup("permission-delete-success"))
+    }
+
+    #[autometrics::autometrics]
+    async fn grant(&self, ctx: &Context<'_>, role_id: String, permission_id: String) -> Result<RoleDefinition> {
+        let (role, permission) = Self::select_grant(ctx, &role_id, &permission_id).await?;
+        let manager = Core::database(ctx)?;
+
+        RoleDefinition::grant(manager, &role.id, &permission.id)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Self::reload(ctx).await?;
+        Self::select_grant(ctx, &role_id, &permission_id).await.map(|(role, _)| role)
+    }
+
+    #[autometrics::autometrics]
+    async fn revoke(&self, ctx: &Context<'_>, role_id: String, permission_id: String) -> Result<RoleDefinition> {
+        let (role, permission) = Self::select_grant(ctx, &role_id, &permission_id).await?;
+        let manager = Core::database(ctx)?;
+
+        RoleDefinition::revoke(manager, &role.id, &permission.id)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Self::reload(ctx).await?;
+        Self::select_grant(ctx, &role_id, &permission_id).await.map(|(role, _)| role)
+    }
+}
+
+impl RoleMutation {
+    /// Retrieve both sides of a grant, failing if either one does not exist
+    async fn select_grant(ctx: &Context<'_>, role_id: &str, permission_id: &str) -> Result<(RoleDefinition, Permission)> {
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        let role = RoleDefinition::select_by_id(manager, role_id)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("role-not-found")))?;
+
+        let permission = Permission::select_by_id(manager, permission_id)
+            .await
+            .map_err(Errors::bad_request)?
+            .ok_or_else(|| Errors::not_found(locale.lookup("permission-not-found")))?;
+
+        Ok((role, permission))
+    }
+
+    /// Refresh cached grants so changes apply to the next request right away
+    async fn reload(ctx: &Context<'_>) -> Result<()> {
+        let locale = Core::locales(ctx)?;
+        let manager = Core::database(ctx)?;
+
+        Core::permissions(ctx)?
+            .reload(manager)
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("permission-reload-failed")))
+    }
+}
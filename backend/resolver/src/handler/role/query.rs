@@ -0,0 +1,30 @@
+use async_graphql::{Context, Object, Result};
+
+use library::{Core, Errors};
+use model::{Permission, RoleDefinition};
+
+#[derive(Default)]
+pub struct RoleQuery;
+
+#[Object]
+impl RoleQuery {
+    #[autometrics::autometrics]
+    #[graphql(complexity = "1 + config::QUERY_LIST_COST * child_complexity")]
+    async fn list(&self, ctx: &Context<'_>) -> Result<Vec<RoleDefinition>> {
+        let manager = Core::database(ctx)?;
+
+        RoleDefinition::select_all(manager)
+            .await
+            .map_err(Errors::bad_request)
+    }
+
+    #[autometrics::autometrics]
+    #[graphql(complexity = "1 + config::QUERY_LIST_COST * child_complexity")]
+    async fn permissions(&self, ctx: &Context<'_>) -> Result<Vec<Permission>> {
+        let manager = Core::database(ctx)?;
+
+        Permission::select_all(manager)
+            .await
+            .map_err(Errors::bad_request)
+    }
+}
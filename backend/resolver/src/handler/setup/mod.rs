@@ -1,2 +1,3 @@
 pub mod mutation;
-pub mod query;
\ No newline at end of file
+pub mod query;
+pub mod subscription;
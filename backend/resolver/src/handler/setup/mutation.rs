@@ -6,8 +6,10 @@ use library::Core;
 use library::Errors;
 use library::{Base, BaseForm};
 use library::{Mailer, MailerCredentials, MailerForm};
+use library::{OAuth, OAuthForm, OidcProvider};
 use library::{Paseto, PasetoForm};
 use library::{S3, S3Form};
+use model::Actor;
 
 #[derive(Default)]
 pub struct SetupMutation;
@@ -20,8 +22,8 @@ impl SetupMutation {
         let form = form.validate(ctx)?
             .to::<Base>();
 
-        // Get database manager
-        let manager = Core::database(ctx)?;
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
 
         // Upsert base and update current base
         form.upsert(manager)
@@ -73,8 +75,8 @@ impl SetupMutation {
             }))
             .send(from, to, subject)?;
 
-        // Get database manager
-        let manager = Core::database(ctx)?;
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
 
         // Upsert mailer and update current mailer
         form.upsert(manager)
@@ -95,14 +97,52 @@ impl SetupMutation {
         Err(Errors::internal_server_error(error))
     }
 
+    #[autometrics::autometrics]
+    async fn oauth(&self, ctx: &Context<'_>, mut form: OAuthForm) -> Result<OAuth> {
+        // Validate form and convert it to OAuth struct if it's valid
+        let form = form.validate(ctx)?
+            .to::<OAuth>();
+
+        // Make sure the issuer publishes a discovery document before saving
+        if OidcProvider::discover(&form).await.is_err() {
+            let error = Core::locales(ctx)?.lookup("oauth-discovery-failed");
+            return Err(Errors::bad_request(error));
+        }
+
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
+
+        // Upsert oauth and update current oauth
+        form.upsert(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Parse core settings
+        if let Some(settings) = ctx.data_opt::<Arc<Core>>() {
+            if let Ok(mut settings) = settings.oauth.try_write() {
+                settings.mutate(&form);
+
+                return Ok(settings.clone());
+            }
+        }
+
+        // Retrieve locales and return error
+        let error = Core::locales(ctx)?.lookup("oauth-update-failed");
+        Err(Errors::internal_server_error(error))
+    }
+
     #[autometrics::autometrics]
     async fn paseto(&self, ctx: &Context<'_>, mut form: PasetoForm) -> Result<Paseto> {
         // Validate form and convert it to Paseto struct if it's valid
-        let form = form.validate(ctx)?
+        let mut form = form.validate(ctx)?
             .to::<Paseto>();
 
-        // Get database manager
-        let manager = Core::database(ctx)?;
+        // Keep replaced keys around so issued tokens stay valid
+        let previous = Core::paseto(ctx)?.clone();
+        form.merge_keyring(&previous);
+
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
 
         // Upsert paseto and update current paseto
         form.upsert(manager)
@@ -133,8 +173,8 @@ impl SetupMutation {
         form.test_image_upload()
             .await?;
 
-        // Get database manager
-        let manager = Core::database(ctx)?;
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
 
         // Upsert base and update current base
         form.upsert(manager)
@@ -179,4 +219,61 @@ impl SetupMutation {
         let error = Core::locales(ctx)?.lookup("s3-update-failed");
         Err(Errors::internal_server_error(error))
     }
-}
\ No newline at end of file
+
+    #[autometrics::autometrics]
+    async fn rotate_paseto_keys(&self, ctx: &Context<'_>) -> Result<Paseto> {
+        // Generate new keys, the current ones are retired
+        let mut paseto = Core::paseto(ctx)?.clone();
+        paseto.rotate_keys();
+
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
+
+        // Upsert paseto and update current paseto
+        paseto.upsert(manager)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        // Parse core settings
+        if let Some(settings) = ctx.data_opt::<Arc<Core>>() {
+            if let Ok(mut settings) = settings.paseto.try_write() {
+                settings.mutate(&paseto);
+
+                return Ok(settings.clone());
+            }
+        }
+
+        // Retrieve locales and return error
+        let error = Core::locales(ctx)?.lookup("paseto-update-failed");
+        Err(Errors::internal_server_error(error))
+    }
+
+    /// Rewrite every encrypted value & blind index under the current master key after a rotation
+    #[autometrics::autometrics]
+    async fn reencrypt(&self, ctx: &Context<'_>) -> Result<String> {
+        let locale = Core::locales(ctx)?;
+
+        // Get system database manager
+        let manager = &Core::system_database(ctx)?;
+
+        // Rewrite settings modules first, then values sealed outside of the settings table
+        let modules = Core::reencrypt_settings(manager)
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("cipher-reencrypt-failed")))?;
+
+        let mfa_secrets = Actor::reencrypt_mfa_secrets(manager)
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("cipher-reencrypt-failed")))?;
+
+        // Blind indexes are derived from the master key as well
+        let actors = Actor::reindex(manager)
+            .await
+            .map_err(|_| Errors::internal_server_error(locale.lookup("cipher-reencrypt-failed")))?;
+
+        Ok(locale.lookup_with_args("cipher-reencrypted", &[
+            ("modules", modules.join(", ")),
+            ("mfa_secrets", mfa_secrets.to_string()),
+            ("actors", actors.to_string()),
+        ]))
+    }
+}
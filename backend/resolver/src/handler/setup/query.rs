@@ -3,6 +3,7 @@ use async_graphql::{Context, Object, Result};
 use library::Core;
 use library::Base;
 use library::MailerCredentials;
+use library::OAuth;
 use library::Paseto;
 use library::S3;
 
@@ -21,6 +22,11 @@ impl SetupQuery {
         Ok(Core::mailer(ctx)?.clone().credentials)
     }
 
+    #[autometrics::autometrics]
+    async fn oauth(&self, ctx: &Context<'_>) -> Result<OAuth> {
+        Core::oauth(ctx)
+    }
+
     #[autometrics::autometrics]
     async fn paseto(&self, ctx: &Context<'_>) -> Result<Paseto> {
         Ok(Core::paseto(ctx)?.clone())
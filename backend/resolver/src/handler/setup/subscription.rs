@@ -0,0 +1,20 @@
+use async_graphql::{Context, Result, Subscription};
+use async_graphql::futures_util::Stream;
+
+use library::Core;
+use library::subscriptions::{SETTINGS_CHANGED, SettingsChanged};
+
+use model::Guard;
+
+#[derive(Default)]
+pub struct SetupSubscription;
+
+#[Subscription]
+impl SetupSubscription {
+    /// Emits whenever a settings module is written by any server process
+    #[autometrics::autometrics]
+    #[graphql(visible = "Guard::is_controller", guard = "Guard::controller()")]
+    async fn settings_changed(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = SettingsChanged>> {
+        Ok(Core::subscriptions(ctx)?.subscribe::<SettingsChanged>(SETTINGS_CHANGED))
+    }
+}
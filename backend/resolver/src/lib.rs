@@ -4,27 +4,46 @@ pub mod root;
 pub use handler::*;
 pub use root::*;
 
-use async_graphql::{ EmptySubscription, Schema };
+use async_graphql::{ Data, Result, Schema };
 use async_graphql::{ extensions::Logger };
 use std::sync::Arc;
 
-use library::{Core, GqlTokenParser, sse::Broadcaster};
-use model::{Role, Status};
+use library::{Core, GqlPersistedQueries, GqlQueryLimits, GqlTokenParser, PersistedQueries, sse::Broadcaster};
+use model::{Actor, GqlLoaders, Loaders, Role, Status};
 
-pub type ProjectSchema = Schema<RootQuery, RootMutation, EmptySubscription>;
+pub type ProjectSchema = Schema<RootQuery, RootMutation, RootSubscription>;
 
 pub fn schema(core: &Arc<Core>, sse: &Arc<Broadcaster>) -> ProjectSchema {
     let query = RootQuery;
     let mutation = RootMutation;
-    let subscription = EmptySubscription;
+    let subscription = RootSubscription::default();
 
     let role = Role::default();
     let status = Status::default();
 
     Schema::build(query, mutation, subscription)
         .extension(Logger)
+        .extension(GqlPersistedQueries)
         .extension(GqlTokenParser::new(role, status))
+        .extension(GqlLoaders)
+        .extension(GqlQueryLimits::default())
         .data(Arc::clone(core))
         .data(Arc::clone(sse))
         .finish()
-}
\ No newline at end of file
+}
+
+/// Scheduler task purging expired persisted queries & sign in attempts
+pub fn cleanup_task(core: Arc<Core>) {
+    PersistedQueries::cleanup_task(Arc::clone(&core));
+    Actor::cleanup_task(core);
+}
+
+/// Authenticate a websocket subscription's `connection_init` payload,
+/// the loaders of the connection are scoped to its tenant
+pub async fn connection_init(core: Arc<Core>, payload: serde_json::Value) -> Result<Data> {
+    let (mut data, manager) = GqlTokenParser::<Role, Status>::connection_init(core, payload).await?;
+
+    data.insert(Loaders::uncached(&manager));
+
+    Ok(data)
+}
@@ -1,5 +1,7 @@
 pub mod mutation;
 pub mod query;
+pub mod subscription;
 
 pub use mutation::RootMutation;
-pub use query::RootQuery;
\ No newline at end of file
+pub use query::RootQuery;
+pub use subscription::RootSubscription;
\ No newline at end of file
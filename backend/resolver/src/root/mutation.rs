@@ -7,6 +7,28 @@ pub struct RootMutation;
 
 #[Object]
 impl RootMutation {
+    async fn auth(&self) -> crate::AuthMutation {
+        crate::AuthMutation
+    }
+
+    #[graphql(guard = "Guard::authenticated()")]
+    async fn api_key(&self) -> crate::ApiKeyMutation {
+        crate::ApiKeyMutation
+    }
+
+    async fn mfa(&self) -> crate::MfaMutation {
+        crate::MfaMutation
+    }
+
+    async fn oauth(&self) -> crate::OAuthMutation {
+        crate::OAuthMutation
+    }
+
+    #[graphql(visible = "Guard::is_controller", guard = "Guard::controller()")]
+    async fn role(&self) -> crate::RoleMutation {
+        crate::RoleMutation
+    }
+
     #[graphql(visible = "Guard::is_controller", guard = "Guard::controller()")]
     async fn setup(&self) -> crate::SetupMutation {
         crate::SetupMutation
@@ -7,6 +7,21 @@ pub struct RootQuery;
 
 #[Object]
 impl RootQuery {
+    async fn auth(&self) -> crate::AuthQuery {
+        crate::AuthQuery
+    }
+
+    #[graphql(guard = "Guard::authenticated()")]
+    async fn api_key(&self) -> crate::ApiKeyQuery {
+        crate::ApiKeyQuery
+    }
+
+    /// Roles & permissions, readable by roles granted `role:read`
+    #[graphql(guard = "Guard::permission(\"role:read\")")]
+    async fn role(&self) -> crate::RoleQuery {
+        crate::RoleQuery
+    }
+
     #[graphql(visible = "Guard::is_controller", guard = "Guard::controller()")]
     async fn setup(&self) -> crate::SetupQuery {
         crate::SetupQuery
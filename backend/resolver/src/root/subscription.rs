@@ -0,0 +1,6 @@
+use async_graphql::MergedSubscription;
+
+use crate::{ChatSubscription, SetupSubscription};
+
+#[derive(MergedSubscription, Default)]
+pub struct RootSubscription(ChatSubscription, SetupSubscription);
@@ -78,6 +78,7 @@ async fn main() -> std::io::Result<()> {
     Scheduler::builder()
         .set_core(&core)
         .set_duration(config::CRON_DURATION)
+        .set_func(resolver::cleanup_task)
         .clone()
         .start();
 
@@ -106,17 +107,25 @@ async fn main() -> std::io::Result<()> {
                 .service(pages::metrics)
                 .default_service(web::route().to(config::page::async_not_found)))
 
+            // Create token introspection endpoint
+            .service(web::scope("/introspect")
+                .wrap(ActixTokenParser::controller())
+                .service(pages::introspect)
+                .default_service(web::route().to(config::page::async_not_found)))
+
             // Include routes
             .service(
                 web::scope(config::BASE_PATH)
                     .wrap(Compress::default())
                     .service(pages::health_check)
                     .service(pages::favicon)
+                    .service(pages::paseto_keys)
                     .service(pages::events)
                     .service(pages::broadcast)
                     .service(pages::static_files())
-                    .service(pages::playground())
+                    .service(pages::subscriptions())
                     .service(pages::resolvers())
+                    .service(pages::playground())
                     .default_service(web::route().to(config::page::async_not_found))
             )
 
@@ -1,16 +1,18 @@
 use actix_files::{Files, NamedFile};
-use actix_web::{get, guard, Result, HttpRequest, HttpResponse, Responder};
+use actix_web::{get, guard, post, Result, HttpRequest, HttpResponse, Responder};
 use actix_web::dev::HttpServiceFactory;
 use actix_web::error::ErrorInternalServerError;
-use actix_web::http::StatusCode;
+use actix_web::http::{Method, StatusCode};
 use actix_web::middleware::Compress;
 use actix_web::web::{self, Data};
 use async_graphql::http::GraphiQLSource;
-use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{Data as GraphQLData, Schema};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use autometrics::prometheus_exporter;
 use std::sync::Arc;
 
-use library::Core;
+use library::{Core, Introspection, IntrospectionRequest};
+use library::persisted_queries::ReadOnlyRequest;
 use resolver::ProjectSchema;
 
 // Get: / - Create index page as health check
@@ -23,6 +25,31 @@ pub async fn health_check(core: Data<Arc<Core>>) -> String {
     ])
 }
 
+// Get: /.well-known/paseto-keys - Public keys of v4.public token types so other services can verify tokens offline
+#[get("/.well-known/paseto-keys/")]
+pub async fn paseto_keys(core: Data<Arc<Core>>) -> Result<HttpResponse> {
+    let keys = core.paseto
+        .read()
+        .map_err(|_| ErrorInternalServerError(core.locale.lookup("paseto-retrieve-failed")))?
+        .public_keys();
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", format!("public, max-age={}", config::PASETO_KEYS_MAX_AGE)))
+        .json(serde_json::json!({ "keys": keys })))
+}
+
+// Post: /introspect - RFC 7662 token introspection, reports whether a token is active, expired or revoked
+#[post("/")]
+pub(crate) async fn introspect(core: Data<Arc<Core>>, form: web::Form<IntrospectionRequest>) -> Result<HttpResponse> {
+    let introspection = Introspection::inspect(&core, &form)
+        .await
+        .map_err(ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(("Cache-Control", "no-store"))
+        .json(introspection))
+}
+
 // Path for /metrics
 #[get("/")]
 pub(crate) async fn metrics() -> Result<HttpResponse> {
@@ -59,25 +86,51 @@ pub fn playground() -> impl HttpServiceFactory {
 pub async fn playground_page() -> HttpResponse {
     HttpResponse::Ok()
         .content_type("text/html; charset=utf-8")
-        .body(GraphiQLSource::build().endpoint("/public").finish())
+        .body(GraphiQLSource::build().endpoint("/public").subscription_endpoint("/public").finish())
 }
 
-// Set resolver
+// Set resolver, GET requests with a query string carry persisted queries
 pub fn resolvers() -> impl HttpServiceFactory {
     web::resource("/public/")
-        .guard(guard::Post())
+        .guard(guard::Any(guard::Post()).or(guard::All(guard::Get()).and(guard::fn_guard(|ctx| ctx.head().uri.query().is_some()))))
         .to(resolvers_page)
 }
 
-// Create resolver handler for post queries
+// Create resolver handler for post queries & cacheable get queries
 pub async fn resolvers_page(core:Data<Arc<Core>>, schema: Data<ProjectSchema>, req: HttpRequest, gql: GraphQLRequest) -> GraphQLResponse {
     let uap = core.user_agent_parser();
+    let mut request = library::parsers::graphql(&req, gql, uap);
+
+    if req.method() == Method::GET {
+        request = request.data(ReadOnlyRequest);
+    }
 
-    schema.execute(library::parsers::graphql(&req, gql, uap))
+    schema.execute(request)
         .await
         .into()
 }
 
+// Set subscriptions, websocket upgrades share the resolver path
+pub fn subscriptions() -> impl HttpServiceFactory {
+    web::resource("/public/")
+        .guard(guard::Get())
+        .guard(guard::Header("upgrade", "websocket"))
+        .to(subscriptions_page)
+}
+
+// Create subscription handler, the connection init payload carries the bearer token
+pub async fn subscriptions_page(core: Data<Arc<Core>>, schema: Data<ProjectSchema>, req: HttpRequest, payload: web::Payload) -> Result<HttpResponse> {
+    let mut data = GraphQLData::default();
+    data.insert(library::parsers::user_agent(&req, core.user_agent_parser()));
+
+    let core = Arc::clone(&**core);
+
+    GraphQLSubscription::new(Schema::clone(&*schema))
+        .with_data(data)
+        .on_connection_init(move |payload| resolver::connection_init(core, payload))
+        .start(&req, payload)
+}
+
 // TODO: Fix authentication and channel setup
 #[get("/events/")]
 async fn events(broadcaster: Data<Arc<library::sse::Broadcaster>>) -> impl Responder {
@@ -0,0 +1,80 @@
+use anyhow::Result;
+
+use crate::{Claims, DBManager};
+use crate::hashes::sha256;
+use crate::prelude::{CustomRole, CustomStatus};
+
+/// Scope granting the controller role, only the controller can issue it
+pub const SCOPE_CONTROLLER: &str = "controller";
+pub const SCOPE_READ: &str = "read";
+pub const SCOPE_WRITE: &str = "write";
+
+/// Api key resolved from a bearer token along with its owner's company, role & status
+#[derive(Debug, Default, Clone, PartialEq, sqlx::FromRow)]
+pub struct ApiKeyIdentity {
+    pub id: String,
+    pub actor_id: String,
+    pub company_id: Option<String>,
+    pub scopes: Vec<String>,
+    pub role: Option<String>,
+    pub status: Option<String>,
+}
+
+impl ApiKeyIdentity {
+    pub fn is_api_key(token: &str) -> bool {
+        token.starts_with(config::API_KEY_PREFIX)
+    }
+
+    /// Look up an unrevoked, unexpired key owned by an active actor and record its use
+    pub async fn authenticate(manager: &DBManager, token: &str) -> Result<Option<Self>> {
+        let identity = sqlx::query_as::<_, Self>(r#"
+            SELECT api_key.id, api_key.actor_id, actor.company_id, api_key.scopes, actor.role, actor.status
+            FROM api_key INNER JOIN actor ON actor.id = api_key.actor_id
+            WHERE api_key.key_hash = $1
+            AND api_key.revoked_at IS NULL
+            AND (api_key.expires_at IS NULL OR api_key.expires_at > NOW())
+            AND actor.status = 'ACTIVE'
+        "#).bind(sha256(token))
+            .fetch_optional(manager.reader())
+            .await?;
+
+        if let Some(identity) = &identity {
+            // Throttle writes, last used only needs to be roughly accurate
+            sqlx::query(r#"
+                UPDATE api_key SET last_used_at = NOW()
+                WHERE id = $1
+                AND (last_used_at IS NULL OR last_used_at < NOW() - MAKE_INTERVAL(secs => $2))
+            "#).bind(&identity.id)
+                .bind(config::API_KEY_LAST_USED_INTERVAL as f64)
+                .execute(manager.writer())
+                .await?;
+        }
+
+        Ok(identity)
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    /// Claims injected for requests authenticated with this key
+    pub fn to_claims<R, S>(&self) -> Claims<R, S>
+        where R: CustomRole,
+              S: CustomStatus
+    {
+        let role = match self.has_scope(SCOPE_CONTROLLER) {
+            true => R::get_controller(),
+            false => self.role.as_deref().map(R::from_str).unwrap_or_default()
+        };
+
+        Claims {
+            aid: Some(self.actor_id.clone()),
+            sid: None,
+            cid: self.company_id.clone(),
+            role: Some(role),
+            status: self.status.as_deref().map(S::from_str),
+            scopes: Some(self.scopes.clone()),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::Cipher;
+
+/// Label mixed into the master key so index keys never equal encryption keys
+const INDEX_KEY_LABEL: &[u8] = b"blind-index";
+
+/// Deterministic HMAC-SHA256 of the value under a key derived from the current master key.
+/// Equal values give equal indexes so encrypted columns can still be looked up & kept unique.
+pub fn blind_index<T>(value: T) -> Result<String>
+    where T: AsRef<str>
+{
+    let keys = Cipher::master_keys()?;
+
+    compute(&keys.current, &normalize(value.as_ref()))
+}
+
+/// Indexes of the value under the current & previous master key.
+/// Lookups match either one while rows are being re-indexed after a rotation.
+pub fn blind_indexes<T>(value: T) -> Result<Vec<String>>
+    where T: AsRef<str>
+{
+    let value = normalize(value.as_ref());
+
+    Cipher::master_keys()?
+        .all()
+        .into_iter()
+        .map(|key| compute(key, &value))
+        .collect()
+}
+
+/// Values are indexed trimmed & lowercased, so inserts & lookups agree whatever the input looks like
+fn normalize(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+fn compute(key: &[u8], value: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key)?;
+    mac.update(INDEX_KEY_LABEL);
+    let index_key = mac.finalize().into_bytes();
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&index_key)?;
+    mac.update(value.as_bytes());
+
+    Ok(base64_url::encode(&mac.finalize().into_bytes()))
+}
+
+/// Field types `#[derive(SetBlindIndex)]` can index, the index field has the same type as its source
+pub trait BlindIndexField: Sized {
+    fn blind_index(&self) -> Result<Self>;
+}
+
+impl BlindIndexField for String {
+    fn blind_index(&self) -> Result<Self> {
+        match self.is_empty() {
+            true => Ok(String::new()),
+            false => blind_index(self)
+        }
+    }
+}
+
+impl BlindIndexField for Option<String> {
+    fn blind_index(&self) -> Result<Self> {
+        self.as_deref()
+            .map(blind_index)
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KeyProvider;
+
+    struct TestKeyProvider;
+
+    impl KeyProvider for TestKeyProvider {
+        fn key(&self) -> Result<Vec<u8>> {
+            Ok(vec![1; 32])
+        }
+
+        fn previous_key(&self) -> Result<Option<Vec<u8>>> {
+            Ok(Some(vec![2; 32]))
+        }
+    }
+
+    fn init() {
+        Cipher::init(&TestKeyProvider).unwrap();
+    }
+
+    #[test]
+    fn blind_index_is_deterministic() {
+        init();
+
+        assert_eq!(blind_index("jane@example.com").unwrap(), blind_index("jane@example.com").unwrap());
+        assert_ne!(blind_index("jane@example.com").unwrap(), blind_index("john@example.com").unwrap());
+    }
+
+    #[test]
+    fn blind_index_normalizes_case_and_whitespace() {
+        init();
+
+        let index = blind_index("jane@example.com").unwrap();
+
+        assert_eq!(blind_index(" Jane@Example.COM\n").unwrap(), index);
+        assert_eq!(blind_indexes("JANE@example.com ").unwrap()[0], index);
+    }
+
+    #[test]
+    fn blind_indexes_cover_current_and_previous_key() {
+        init();
+
+        let indexes = blind_indexes("jane@example.com").unwrap();
+
+        assert_eq!(indexes.len(), 2);
+        assert_eq!(indexes[0], blind_index("jane@example.com").unwrap());
+        assert_ne!(indexes[0], indexes[1]);
+        assert_eq!(indexes[1], compute(&[2; 32], "jane@example.com").unwrap());
+    }
+
+    #[test]
+    fn blind_index_field_keeps_empty_values_empty() {
+        init();
+
+        assert_eq!(String::new().blind_index().unwrap(), "");
+        assert_eq!(None::<String>.blind_index().unwrap(), None);
+        assert_eq!(
+            Some(String::from("Jane@Example.com")).blind_index().unwrap(),
+            Some(blind_index("jane@example.com").unwrap())
+        );
+    }
+}
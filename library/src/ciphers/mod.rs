@@ -1,10 +1,27 @@
+pub mod blind_index;
+pub mod providers;
+
 use anyhow::Result;
+use std::sync::{Arc, RwLock};
+use subtle::ConstantTimeEq;
 use xsalsa20poly1305::aead::{ Aead, KeyInit };
 use xsalsa20poly1305::aead::generic_array::{ GenericArray, typenum };
 use xsalsa20poly1305::XSalsa20Poly1305;
 
+use crate::hashes::sha256;
+
+use providers::{EnvKeyProvider, KeyProvider, MasterKeys};
+
 const NONCE_LENGTH: usize = 24;
 
+/// Master keys cached by `Cipher::init`, loaded from env on first use when it was never called
+static MASTER_KEYS: RwLock<Option<Arc<MasterKeys>>> = RwLock::new(None);
+
+/// Versioned ciphertexts start with `mk1.{key id}.` so decryption knows which master key sealed them.
+/// Ciphertexts without the prefix predate key rotation and are tried against every known key.
+const VERSION_PREFIX: &[u8] = b"mk1.";
+const KEY_ID_LENGTH: usize = 8;
+
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct Cipher {
     is_encrypted: bool,
@@ -99,42 +116,50 @@ impl From<Vec<u8>> for Cipher {
 }
 
 impl Cipher {
+    /// Resolve the master keys once, every encryption afterwards uses the cached keys
+    pub fn init(provider: &dyn KeyProvider) -> Result<()> {
+        let keys = MasterKeys::load(provider)?;
+
+        if let Ok(mut cache) = MASTER_KEYS.write() {
+            *cache = Some(Arc::new(keys));
+        }
+
+        Ok(())
+    }
+
     pub fn decrypt(self) -> Result<Self> {
         // Check if self is already decrypted
         if !self.is_encrypted {
             return Ok(self);
         }
 
-        // Create bindings and then generate cipher instance
-        let bindings = base64_url::decode(&std::env::var("MASTER_KEY")?)?;
-        let key = GenericArray::from_slice(&bindings);
-        let cipher = XSalsa20Poly1305::new(key);
+        self.decrypt_with(&*Self::master_keys()?)
+    }
 
+    fn decrypt_with(self, keys: &MasterKeys) -> Result<Self> {
         // Retrieve content
         let content = match self.content.clone() {
             CipherContent::String(content) => base64_url::decode(&content)?,
             CipherContent::Vec(content) => content
         };
 
-        // Check content length
-        if content.len() <= NONCE_LENGTH {
-            return Err(anyhow::anyhow!("Invalid content length"));
-        }
-
-        // Split content
-        let (nonce, content) = content.split_at(NONCE_LENGTH);
-
-        // Set nonce & content
-        let nonce:&GenericArray<u8, typenum::U24> = GenericArray::from_slice(nonce);
+        // Versioned content names its key, legacy content is tried against the current & previous key
+        let (keys, content) = match Self::split_key_id(&content) {
+            Some((kid, content)) => (keys.all().into_iter().filter(|key| Self::key_id(key) == kid).collect(), content),
+            None => (keys.all(), content.as_slice())
+        };
 
         // Decrypt content
-        match cipher.decrypt(nonce, content) {
-            Ok(content) => Ok(Self {
-                is_encrypted: false,
-                content: CipherContent::Vec(content)
-            }),
-            Err(_) => Err(anyhow::anyhow!("Unable to decrypt content"))
+        for key in keys {
+            if let Ok(content) = open(key, content) {
+                return Ok(Self {
+                    is_encrypted: false,
+                    content: CipherContent::Vec(content)
+                });
+            }
         }
+
+        Err(anyhow::anyhow!("Unable to decrypt content"))
     }
 
     pub fn encrypt(&self) -> Result<Self> {
@@ -143,13 +168,12 @@ impl Cipher {
             return Ok(self.clone());
         }
 
-        // Create bindings and then generate cipher instance
-        let bindings = base64_url::decode(&std::env::var("MASTER_KEY")?)?;
-        let key = GenericArray::from_slice(&bindings);
-        let cipher = XSalsa20Poly1305::new(key);
+        self.encrypt_with(&*Self::master_keys()?)
+    }
 
-        // Set nonce
-        let nonce = XSalsa20Poly1305::generate_nonce(&mut rand::rngs::OsRng);
+    fn encrypt_with(&self, keys: &MasterKeys) -> Result<Self> {
+        // Name the current master key in front of the ciphertext
+        let prefix = [VERSION_PREFIX, Self::key_id(&keys.current).as_bytes(), b"."].concat();
 
         // Retrieve content
         let content = match self.content.clone() {
@@ -158,28 +182,59 @@ impl Cipher {
         };
 
         // Encrypt content
-        if let Ok(content) = cipher.encrypt(&nonce, content.as_bytes()) {
-            return Ok(Self{
-                is_encrypted: true,
-                content: CipherContent::Vec([&nonce[..], &content[..]].concat())
-            });
+        let content = seal(&keys.current, content.as_bytes())?;
+
+        Ok(Self {
+            is_encrypted: true,
+            content: CipherContent::Vec([prefix, content].concat())
+        })
+    }
+
+    /// Short fingerprint of a master key, stored in front of the ciphertexts it seals
+    fn key_id(key: &[u8]) -> String {
+        sha256(key).chars().take(KEY_ID_LENGTH).collect()
+    }
+
+    /// Cached master keys, falling back to the env provider when `Cipher::init` was never called
+    pub(crate) fn master_keys() -> Result<Arc<MasterKeys>> {
+        if let Some(keys) = MASTER_KEYS.read().ok().and_then(|keys| keys.clone()) {
+            return Ok(keys);
         }
 
-        Err(anyhow::anyhow!("Unable to encrypt content"))
+        Self::init(&EnvKeyProvider::default())?;
+
+        MASTER_KEYS.read()
+            .ok()
+            .and_then(|keys| keys.clone())
+            .ok_or_else(|| anyhow::anyhow!("Unable to load master keys"))
+    }
+
+    /// Split versioned content into its key id & sealed content
+    fn split_key_id(content: &[u8]) -> Option<(&str, &[u8])> {
+        let content = content.strip_prefix(VERSION_PREFIX)?;
+
+        match content.get(KEY_ID_LENGTH) {
+            Some(b'.') => {
+                let kid = std::str::from_utf8(&content[..KEY_ID_LENGTH]).ok()?;
+                Some((kid, &content[KEY_ID_LENGTH + 1..]))
+            },
+            _ => None
+        }
     }
 
+    /// Check if the content decrypts to the current master key, which is what controller bearer tokens carry
     pub fn is_controller(&self) -> bool {
-        if let Ok(env) = std::env::var("MASTER_KEY") {
-            if let Ok(key) = base64_url::decode(&env) {
-                if let Ok(cipher) = self.clone().decrypt() {
-                    if let Ok(cipher) = cipher.to_vec() {
-                        return key == cipher;
-                    }
-                }
-            }
+        match Self::master_keys() {
+            Ok(keys) => self.is_controller_with(&keys),
+            Err(_) => false
         }
+    }
 
-        false
+    fn is_controller_with(&self, keys: &MasterKeys) -> bool {
+        match self.clone().decrypt_with(keys).and_then(|cipher| cipher.to_vec()) {
+            Ok(content) => bool::from(content.ct_eq(&keys.current)),
+            Err(_) => false
+        }
     }
 
     pub fn set_as_decrypted(&self) -> Self {
@@ -228,4 +283,129 @@ impl Cipher {
             CipherContent::Vec(content) => Ok(base64_url::encode(content.as_slice()))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Seal content with the key, the random nonce is prepended to the ciphertext
+pub(crate) fn seal(key: &[u8], content: &[u8]) -> Result<Vec<u8>> {
+    let cipher = XSalsa20Poly1305::new_from_slice(key)
+        .map_err(|_| anyhow::anyhow!("Invalid key length"))?;
+
+    // Set nonce
+    let nonce = XSalsa20Poly1305::generate_nonce(&mut rand::rngs::OsRng);
+
+    match cipher.encrypt(&nonce, content) {
+        Ok(content) => Ok([&nonce[..], &content[..]].concat()),
+        Err(_) => Err(anyhow::anyhow!("Unable to encrypt content"))
+    }
+}
+
+/// Open content sealed by `seal`
+pub(crate) fn open(key: &[u8], content: &[u8]) -> Result<Vec<u8>> {
+    let cipher = XSalsa20Poly1305::new_from_slice(key)
+        .map_err(|_| anyhow::anyhow!("Invalid key length"))?;
+
+    // Check content length
+    if content.len() <= NONCE_LENGTH {
+        return Err(anyhow::anyhow!("Invalid content length"));
+    }
+
+    // Split content
+    let (nonce, content) = content.split_at(NONCE_LENGTH);
+
+    // Set nonce & content
+    let nonce:&GenericArray<u8, typenum::U24> = GenericArray::from_slice(nonce);
+
+    cipher.decrypt(nonce, content)
+        .map_err(|_| anyhow::anyhow!("Unable to decrypt content"))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(current: u8, previous: Option<u8>) -> MasterKeys {
+        MasterKeys {
+            current: vec![current; 32],
+            previous: previous.map(|previous| vec![previous; 32]),
+        }
+    }
+
+    fn plain(content: &str) -> Cipher {
+        Cipher::from(content).set_as_decrypted()
+    }
+
+    fn decrypt(cipher: &Cipher, keys: &MasterKeys) -> Result<String> {
+        cipher.clone().decrypt_with(keys)?.to_string()
+    }
+
+    #[test]
+    fn encrypt_prefixes_the_current_key_id_and_round_trips() {
+        let keys = keys(1, None);
+        let encrypted = plain("secret").encrypt_with(&keys).unwrap();
+        let content = encrypted.to_vec().unwrap();
+        let prefix = format!("mk1.{}.", Cipher::key_id(&keys.current));
+
+        assert!(content.starts_with(prefix.as_bytes()));
+        assert_eq!(decrypt(&encrypted, &keys).unwrap(), "secret");
+
+        // Stored ciphertexts are base64 strings
+        let stored = Cipher::from(encrypted.b64encode().unwrap());
+        assert_eq!(decrypt(&stored, &keys).unwrap(), "secret");
+    }
+
+    #[test]
+    fn decrypt_uses_the_previous_key_after_a_rotation() {
+        let encrypted = plain("secret").encrypt_with(&keys(1, None)).unwrap();
+
+        assert_eq!(decrypt(&encrypted, &keys(2, Some(1))).unwrap(), "secret");
+        assert!(decrypt(&encrypted, &keys(2, None)).is_err());
+    }
+
+    #[test]
+    fn decrypt_tries_every_key_on_legacy_content() {
+        let legacy = Cipher::from(seal(&[1; 32], b"secret").unwrap());
+
+        assert_eq!(decrypt(&legacy, &keys(1, None)).unwrap(), "secret");
+        assert_eq!(decrypt(&legacy, &keys(2, Some(1))).unwrap(), "secret");
+        assert!(decrypt(&legacy, &keys(2, Some(3))).is_err());
+    }
+
+    #[test]
+    fn decrypt_fails_closed_on_a_wrong_key_id() {
+        let keys = keys(2, Some(1));
+        let sealed = seal(&[1; 32], b"secret").unwrap();
+
+        // Sealed by the previous key but naming the current one, no other key is tried
+        let content = [VERSION_PREFIX, Cipher::key_id(&keys.current).as_bytes(), b".", &sealed].concat();
+        assert!(decrypt(&Cipher::from(content), &keys).is_err());
+
+        // Unknown key ids match no key at all
+        let content = [VERSION_PREFIX, b"unknown0.", &sealed[..]].concat();
+        assert!(decrypt(&Cipher::from(content), &keys).is_err());
+    }
+
+    #[test]
+    fn split_key_id_requires_the_full_prefix() {
+        assert_eq!(Cipher::split_key_id(b"mk1.abcdefgh.content"), Some(("abcdefgh", &b"content"[..])));
+        assert_eq!(Cipher::split_key_id(b"mk1.abcdefgh"), None);
+        assert_eq!(Cipher::split_key_id(b"mk1.abc.content"), None);
+        assert_eq!(Cipher::split_key_id(b"content"), None);
+    }
+
+    #[test]
+    fn is_controller_only_accepts_the_current_key_after_a_rotation() {
+        let token = |key: u8| {
+            let encrypted = Cipher::from(vec![key; 32])
+                .set_as_decrypted()
+                .encrypt_with(&keys(key, None))
+                .unwrap();
+
+            Cipher::from(encrypted.b64encode().unwrap())
+        };
+
+        let rotated = keys(2, Some(1));
+
+        assert!(token(1).is_controller_with(&keys(1, None)));
+        assert!(token(2).is_controller_with(&rotated));
+        assert!(!token(1).is_controller_with(&rotated));
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use crate::ciphers::{open, seal};
+
+const KEY_LENGTH: usize = 32;
+
+/// Source of the master key used by `Cipher`.
+/// Keys are url safe base64 encoded 256-bit keys, resolved once at `Core::init` and cached.
+pub trait KeyProvider: Send + Sync {
+    /// Master key every new ciphertext is sealed with
+    fn key(&self) -> Result<Vec<u8>>;
+
+    /// Key that sealed older ciphertexts, only used for decryption while re-encrypting after a rotation
+    fn previous_key(&self) -> Result<Option<Vec<u8>>>;
+}
+
+impl dyn KeyProvider {
+    /// Pick the provider named by `MASTER_KEY_PROVIDER`, defaulting to env
+    pub fn from_env() -> Result<Box<dyn KeyProvider>> {
+        let provider = std::env::var("MASTER_KEY_PROVIDER")
+            .unwrap_or_default();
+
+        match provider.to_lowercase().as_str() {
+            "" | "env" => Ok(Box::new(EnvKeyProvider::default())),
+            "file" => Ok(Box::new(FileKeyProvider::from_env()?)),
+            "envelope" => Ok(Box::new(EnvelopeKeyProvider::from_env()?)),
+            _ => Err(anyhow::anyhow!("Unknown master key provider: {provider}"))
+        }
+    }
+}
+
+/// Resolved master keys, cached by `Cipher::init`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MasterKeys {
+    pub current: Vec<u8>,
+    pub previous: Option<Vec<u8>>,
+}
+
+impl MasterKeys {
+    pub fn load(provider: &dyn KeyProvider) -> Result<Self> {
+        let current = provider.key()?;
+        let previous = provider.previous_key()?;
+
+        // Invalid keys fail here instead of on the first encryption
+        if [Some(&current), previous.as_ref()].into_iter().flatten().any(|key| key.len() != KEY_LENGTH) {
+            return Err(anyhow::anyhow!("Master keys must be {KEY_LENGTH} bytes long"));
+        }
+
+        Ok(Self {
+            current,
+            previous,
+        })
+    }
+
+    /// Current key followed by the previous one
+    pub fn all(&self) -> Vec<&[u8]> {
+        [Some(&self.current), self.previous.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|key| key.as_slice())
+            .collect()
+    }
+}
+
+/// Keys read from environment variables, `MASTER_KEY` & `MASTER_KEY_PREVIOUS` by default
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvKeyProvider {
+    key: String,
+    previous_key: String,
+}
+
+impl Default for EnvKeyProvider {
+    fn default() -> Self {
+        Self::new("MASTER_KEY", "MASTER_KEY_PREVIOUS")
+    }
+}
+
+impl EnvKeyProvider {
+    pub fn new<K, P>(key: K, previous_key: P) -> Self
+        where K: ToString,
+              P: ToString
+    {
+        Self {
+            key: key.to_string(),
+            previous_key: previous_key.to_string(),
+        }
+    }
+}
+
+impl KeyProvider for EnvKeyProvider {
+    fn key(&self) -> Result<Vec<u8>> {
+        let key = std::env::var(&self.key)
+            .map_err(|_| anyhow::anyhow!("{} is not set", self.key))?;
+
+        Ok(base64_url::decode(key.trim())?)
+    }
+
+    fn previous_key(&self) -> Result<Option<Vec<u8>>> {
+        match std::env::var(&self.previous_key) {
+            Ok(key) if !key.trim().is_empty() => Ok(Some(base64_url::decode(key.trim())?)),
+            _ => Ok(None)
+        }
+    }
+}
+
+/// Keys read from mounted secret files, `MASTER_KEY_FILE` & `MASTER_KEY_PREVIOUS_FILE`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileKeyProvider {
+    path: PathBuf,
+    previous_path: Option<PathBuf>,
+}
+
+impl FileKeyProvider {
+    pub fn new(path: PathBuf, previous_path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            previous_path,
+        }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let path = std::env::var("MASTER_KEY_FILE")
+            .map_err(|_| anyhow::anyhow!("MASTER_KEY_FILE is not set"))?;
+
+        let previous_path = std::env::var("MASTER_KEY_PREVIOUS_FILE")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from);
+
+        Ok(Self::new(PathBuf::from(path), previous_path))
+    }
+
+    fn read(path: &PathBuf) -> Result<Vec<u8>> {
+        let key = std::fs::read_to_string(path)
+            .map_err(|error| anyhow::anyhow!("Unable to read master key file {}: {error}", path.display()))?;
+
+        Ok(base64_url::decode(key.trim())?)
+    }
+}
+
+impl KeyProvider for FileKeyProvider {
+    fn key(&self) -> Result<Vec<u8>> {
+        Self::read(&self.path)
+    }
+
+    fn previous_key(&self) -> Result<Option<Vec<u8>>> {
+        self.previous_path
+            .as_ref()
+            .map(Self::read)
+            .transpose()
+    }
+}
+
+/// Envelope encryption: the data keys are stored wrapped by a key encryption key (kek) held elsewhere.
+/// The kek comes from another provider, `MASTER_KEK_FILE` or `MASTER_KEK` when built from env.
+pub struct EnvelopeKeyProvider {
+    kek: Box<dyn KeyProvider>,
+    wrapped_key: String,
+    wrapped_previous_key: Option<String>,
+}
+
+impl EnvelopeKeyProvider {
+    pub fn new(kek: Box<dyn KeyProvider>, wrapped_key: String, wrapped_previous_key: Option<String>) -> Self {
+        Self {
+            kek,
+            wrapped_key,
+            wrapped_previous_key,
+        }
+    }
+
+    /// Wrapped keys are read from `MASTER_KEY_WRAPPED` & `MASTER_KEY_PREVIOUS_WRAPPED`
+    pub fn from_env() -> Result<Self> {
+        let kek: Box<dyn KeyProvider> = match std::env::var("MASTER_KEK_FILE") {
+            Ok(path) if !path.is_empty() => Box::new(FileKeyProvider::new(PathBuf::from(path), None)),
+            _ => Box::new(EnvKeyProvider::new("MASTER_KEK", "MASTER_KEK_PREVIOUS"))
+        };
+
+        let wrapped_key = std::env::var("MASTER_KEY_WRAPPED")
+            .map_err(|_| anyhow::anyhow!("MASTER_KEY_WRAPPED is not set"))?;
+
+        let wrapped_previous_key = std::env::var("MASTER_KEY_PREVIOUS_WRAPPED")
+            .ok()
+            .filter(|key| !key.is_empty());
+
+        Ok(Self::new(kek, wrapped_key, wrapped_previous_key))
+    }
+
+    /// Wrap a data key with the kek, the result is what goes into `MASTER_KEY_WRAPPED`
+    pub fn wrap(kek: &[u8], key: &[u8]) -> Result<String> {
+        Ok(base64_url::encode(&seal(kek, key)?))
+    }
+
+    fn unwrap(&self, wrapped_key: &str) -> Result<Vec<u8>> {
+        let kek = self.kek.key()?;
+
+        open(&kek, &base64_url::decode(wrapped_key.trim())?)
+            .map_err(|_| anyhow::anyhow!("Unable to unwrap master key"))
+    }
+}
+
+impl KeyProvider for EnvelopeKeyProvider {
+    fn key(&self) -> Result<Vec<u8>> {
+        self.unwrap(&self.wrapped_key)
+    }
+
+    fn previous_key(&self) -> Result<Option<Vec<u8>>> {
+        self.wrapped_previous_key
+            .as_deref()
+            .map(|wrapped_key| self.unwrap(wrapped_key))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Tests reading the process env run one at a time
+    static ENV: Mutex<()> = Mutex::new(());
+
+    struct StaticKeyProvider(Vec<u8>, Option<Vec<u8>>);
+
+    impl KeyProvider for StaticKeyProvider {
+        fn key(&self) -> Result<Vec<u8>> {
+            Ok(self.0.clone())
+        }
+
+        fn previous_key(&self) -> Result<Option<Vec<u8>>> {
+            Ok(self.1.clone())
+        }
+    }
+
+    fn envelope(kek: &[u8], key: &[u8], previous_key: Option<&[u8]>) -> EnvelopeKeyProvider {
+        EnvelopeKeyProvider::new(
+            Box::new(StaticKeyProvider(kek.to_vec(), None)),
+            EnvelopeKeyProvider::wrap(kek, key).unwrap(),
+            previous_key.map(|previous_key| EnvelopeKeyProvider::wrap(kek, previous_key).unwrap())
+        )
+    }
+
+    #[test]
+    fn envelope_unwraps_the_wrapped_keys() {
+        let provider = envelope(&[9; 32], &[1; 32], Some(&[2; 32]));
+
+        assert_eq!(provider.key().unwrap(), vec![1; 32]);
+        assert_eq!(provider.previous_key().unwrap(), Some(vec![2; 32]));
+        assert_eq!(envelope(&[9; 32], &[1; 32], None).previous_key().unwrap(), None);
+    }
+
+    #[test]
+    fn envelope_fails_with_the_wrong_kek() {
+        let provider = EnvelopeKeyProvider::new(
+            Box::new(StaticKeyProvider(vec![8; 32], None)),
+            EnvelopeKeyProvider::wrap(&[9; 32], &[1; 32]).unwrap(),
+            None
+        );
+
+        assert!(provider.key().is_err());
+        assert!(MasterKeys::load(&provider).is_err());
+    }
+
+    #[test]
+    fn load_rejects_short_keys() {
+        let keys = MasterKeys::load(&StaticKeyProvider(vec![1; 32], Some(vec![2; 32]))).unwrap();
+        assert_eq!(keys.all(), vec![&[1; 32][..], &[2; 32][..]]);
+
+        assert!(MasterKeys::load(&StaticKeyProvider(vec![1; 16], None)).is_err());
+        assert!(MasterKeys::load(&StaticKeyProvider(vec![1; 32], Some(vec![2; 31]))).is_err());
+        assert!(MasterKeys::load(&StaticKeyProvider(Vec::new(), None)).is_err());
+    }
+
+    #[test]
+    fn load_rejects_missing_keys() {
+        let _env = ENV.lock().unwrap_or_else(|error| error.into_inner());
+        let provider = EnvKeyProvider::new("TEST_MISSING_MASTER_KEY", "TEST_MISSING_MASTER_KEY_PREVIOUS");
+
+        assert!(MasterKeys::load(&provider).is_err());
+        assert!(MasterKeys::load(&FileKeyProvider::new(PathBuf::from("/nonexistent/master.key"), None)).is_err());
+    }
+
+    #[test]
+    fn from_env_selects_the_provider() {
+        let _env = ENV.lock().unwrap_or_else(|error| error.into_inner());
+        let key = base64_url::encode(&[3; 32]);
+        let path = std::env::temp_dir().join(format!("master-key-{}", std::process::id()));
+        std::fs::write(&path, &key).unwrap();
+
+        std::env::set_var("MASTER_KEY", &key);
+        std::env::set_var("MASTER_KEY_FILE", &path);
+        std::env::set_var("MASTER_KEK", base64_url::encode(&[9; 32]));
+        std::env::set_var("MASTER_KEY_WRAPPED", EnvelopeKeyProvider::wrap(&[9; 32], &[4; 32]).unwrap());
+
+        let key = |provider: &str| {
+            std::env::set_var("MASTER_KEY_PROVIDER", provider);
+            <dyn KeyProvider>::from_env().and_then(|provider| provider.key())
+        };
+
+        assert_eq!(key("").unwrap(), vec![3; 32]);
+        assert_eq!(key("env").unwrap(), vec![3; 32]);
+        assert_eq!(key("FILE").unwrap(), vec![3; 32]);
+        assert_eq!(key("envelope").unwrap(), vec![4; 32]);
+        assert!(key("vault").is_err());
+
+        for name in ["MASTER_KEY_PROVIDER", "MASTER_KEY", "MASTER_KEY_FILE", "MASTER_KEK", "MASTER_KEY_WRAPPED"] {
+            std::env::remove_var(name);
+        }
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
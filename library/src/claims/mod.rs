@@ -1,4 +1,5 @@
 use async_graphql::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Serialize, Deserialize};
 
 use crate::Errors;
@@ -11,10 +12,31 @@ pub struct Claims<R, S> where R: CustomRole, S: CustomStatus {
     pub aid: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sid: Option<String>,
+    /// Company of the actor, scopes the rows its requests see
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cid: Option<String>,
+    /// Real actor behind an impersonation token, `aid` is the impersonated actor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iid: Option<String>,
+    /// Whether the session was signed in with a second factor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mfa: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub role: Option<R>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<S>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scopes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<DateTime<Utc>>,
 }
 
 impl <R, S> Claims <R, S> where R: CustomRole, S: CustomStatus  {
@@ -29,20 +51,65 @@ impl <R, S> Claims <R, S> where R: CustomRole, S: CustomStatus  {
     pub fn is_empty(&self) -> bool {
         *self == Self::default()
     }
+
+    /// Whether the claims come from an impersonation token
+    pub fn is_impersonated(&self) -> bool {
+        self.iid.is_some()
+    }
+
+    /// Sessions of the roles in `MFA_REQUIRED_ROLES` must be signed in with a second factor.
+    /// Api keys & impersonation tokens are not sign in sessions and are left out.
+    pub fn is_missing_mfa(&self) -> bool {
+        let is_required = self.role
+            .as_ref()
+            .is_some_and(|role| config::MFA_REQUIRED_ROLES.contains(&role.to_string().as_str()));
+
+        is_required && self.sid.is_some() && self.iid.is_none() && self.mfa != Some(true)
+    }
+
+    /// Claims without scopes (session tokens) are unrestricted
+    pub fn has_scope(&self, scope: &str) -> bool {
+        match &self.scopes {
+            Some(scopes) => scopes.iter().any(|s| s == scope),
+            None => true
+        }
+    }
+
+    /// Copy the registered claims (issuer, audience, token id & times) from the token payload
+    pub fn with_registered(mut self, payload: &serde_json::Value) -> Self {
+        let string = |key: &str| payload.get(key).and_then(|v| v.as_str()).map(|v| v.to_string());
+        let date = |key: &str| payload.get(key).and_then(|v| v.as_str()).and_then(|v| v.parse::<DateTime<Utc>>().ok());
+
+        self.iss = string("iss");
+        self.aud = string("aud");
+        self.jti = string("jti");
+        self.iat = date("iat");
+        self.nbf = date("nbf");
+        self
+    }
 }
 
 impl<R, S> From<serde_json::Value> for Claims<R, S> where R: CustomRole, S: CustomStatus {
     fn from(value: serde_json::Value) -> Self {
         let aid = value.get("aid").and_then(|v| v.as_str()).map(|v| v.to_string());
         let sid = value.get("sid").and_then(|v| v.as_str()).map(|v| v.to_string());
+        let cid = value.get("cid").and_then(|v| v.as_str()).map(|v| v.to_string());
+        let iid = value.get("iid").and_then(|v| v.as_str()).map(|v| v.to_string());
+        let mfa = value.get("mfa").and_then(|v| v.as_bool());
         let role = value.get("role").and_then(|v| v.as_str()).map(|v| R::from_str(v));
         let status = value.get("status").and_then(|v| v.as_str()).map(|v| S::from_str(v));
+        let scopes = value.get("scopes").and_then(|v| serde_json::from_value(v.clone()).ok());
 
         Self {
             aid,
             sid,
+            cid,
+            iid,
+            mfa,
             role,
             status,
+            scopes,
+            ..Default::default()
         }
     }
 }
\ No newline at end of file
@@ -12,6 +12,14 @@ pub fn mustr2str(data: MaybeUndefined<String>) -> String {
     data.take().unwrap_or_default()
 }
 
+pub fn mu2opt<T>(data: MaybeUndefined<T>) -> Option<T> {
+    data.take()
+}
+
+pub fn muvec2vec<T>(data: MaybeUndefined<Vec<T>>) -> Vec<T> {
+    data.take().unwrap_or_default()
+}
+
 pub fn str2optstr(data: String) -> Option<String> {
     match data.is_empty() {
         true => None,
@@ -1,10 +1,20 @@
+pub mod scoped;
+
 use anyhow::Result;
-use sqlx::{Pool, Postgres, postgres::PgPoolOptions};
+use sqlx::{Pool, Postgres, Transaction, postgres::PgPoolOptions};
+use std::sync::{Arc, RwLock};
+
+use crate::Tenant;
+use scoped::ScopedPool;
 
+/// Database pools & the tenant every statement is scoped to.
+/// Managers start anonymous and see no company scoped rows, requests use the manager
+/// scoped to their tenant & system work opts in with `system`.
 #[derive(Clone, Debug)]
 pub struct DBManager {
-    pub reader: Pool<Postgres>,
-    pub writer: Pool<Postgres>
+    pub(crate) reader: Pool<Postgres>,
+    pub(crate) writer: Pool<Postgres>,
+    pub(crate) tenant: Tenant
 }
 
 impl DBManager {
@@ -34,14 +44,68 @@ impl DBManager {
             .map_err(|e| anyhow::anyhow!("Failed to create reader pool: {}", e))?;
 
         // Return database manager
-        Ok(Self { reader, writer })
+        Ok(Self { reader, writer, tenant: Tenant::default() })
+    }
+
+    pub fn reader(&self) -> ScopedPool<'_> {
+        ScopedPool::new(&self.reader, &self.tenant)
+    }
+
+    pub fn writer(&self) -> ScopedPool<'_> {
+        ScopedPool::new(&self.writer, &self.tenant)
+    }
+
+    pub fn tenant(&self) -> &Tenant {
+        &self.tenant
+    }
+
+    /// Share the pools with a manager scoped to the tenant
+    pub fn scoped(&self, tenant: Tenant) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            writer: self.writer.clone(),
+            tenant
+        }
     }
 
-    pub fn reader(&self) -> &Pool<Postgres> {
-        &self.reader
+    /// Share the pools with a manager seeing every company.
+    /// Only for work that runs before or outside of a tenant, such as signing in, setup & scheduled tasks.
+    pub fn system(&self) -> Self {
+        self.scoped(Tenant::system())
     }
 
-    pub fn writer(&self) -> &Pool<Postgres> {
-        &self.writer
+    /// Begin a writer transaction in the scope of the manager.
+    /// The settings are local so they end with the transaction.
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>> {
+        let mut transaction = self.writer.begin().await?;
+
+        ScopedPool::set_scope(&mut transaction, &self.tenant, true).await?;
+
+        Ok(transaction)
+    }
+
+    /// Check if the query failed only because no row matched
+    pub fn is_not_found(error: &anyhow::Error) -> bool {
+        matches!(error.downcast_ref::<sqlx::Error>(), Some(sqlx::Error::RowNotFound))
+    }
+
+    /// Turn a missing row into `None`, every other error is kept
+    pub fn optional<T>(result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(error) if Self::is_not_found(&error) => Ok(None),
+            Err(error) => Err(error)
+        }
+    }
+
+    /// Share the stored settings of a module, modules that were never saved start from their defaults.
+    /// Stored settings that cannot be decrypted must never be replaced by defaults, that error is kept.
+    pub fn settings<T>(result: Result<T>) -> Result<Arc<RwLock<T>>>
+        where T: Default
+    {
+        let settings = Self::optional(result)?
+            .unwrap_or_default();
+
+        Ok(Arc::new(RwLock::new(settings)))
     }
 }
\ No newline at end of file
@@ -0,0 +1,96 @@
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream, StreamExt};
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgConnection, PgQueryResult, PgRow, PgStatement, PgTypeInfo};
+use sqlx::{Describe, Either, Error, Execute, Executor, Pool, Postgres};
+
+use crate::Tenant;
+use crate::tenants::{ACTOR_SETTING, BYPASS_SETTING, COMPANY_SETTING};
+
+/// Pool executor running every statement in the scope of a tenant.
+/// The row level security settings are set on the connection before each statement,
+/// so a pooled connection never carries the scope of its previous user.
+#[derive(Clone, Copy, Debug)]
+pub struct ScopedPool<'a> {
+    pool: &'a Pool<Postgres>,
+    tenant: &'a Tenant,
+}
+
+impl<'a> ScopedPool<'a> {
+    pub fn new(pool: &'a Pool<Postgres>, tenant: &'a Tenant) -> Self {
+        Self {
+            pool,
+            tenant,
+        }
+    }
+
+    /// Set the settings read by the row level security policies.
+    /// Local settings end with the transaction, the others last until the next statement sets them again.
+    pub async fn set_scope(connection: &mut PgConnection, tenant: &Tenant, is_local: bool) -> Result<(), Error> {
+        let bypass = match tenant.is_system() {
+            true => "on",
+            false => "off"
+        };
+
+        sqlx::query("SELECT set_config($1, $2, $7), set_config($3, $4, $7), set_config($5, $6, $7)")
+            .bind(COMPANY_SETTING)
+            .bind(tenant.company_id.as_deref().unwrap_or_default())
+            .bind(ACTOR_SETTING)
+            .bind(tenant.actor_id.as_deref().unwrap_or_default())
+            .bind(BYPASS_SETTING)
+            .bind(bypass)
+            .bind(is_local)
+            .execute(connection)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn acquire(self) -> Result<PoolConnection<Postgres>, Error> {
+        let mut connection = self.pool.acquire().await?;
+
+        Self::set_scope(&mut connection, self.tenant, false).await?;
+
+        Ok(connection)
+    }
+}
+
+impl<'p> Executor<'p> for ScopedPool<'p> {
+    type Database = Postgres;
+
+    /// Results are buffered, the connection is borrowed by the stream of the statement
+    fn fetch_many<'e, 'q: 'e, E>(self, query: E) -> BoxStream<'e, Result<Either<PgQueryResult, PgRow>, Error>>
+        where 'p: 'e,
+              E: Execute<'q, Postgres> + 'q
+    {
+        Box::pin(stream::once(async move {
+            let results = match self.acquire().await {
+                Ok(mut connection) => connection.fetch_many(query).collect::<Vec<_>>().await,
+                Err(error) => vec![Err(error)]
+            };
+
+            stream::iter(results)
+        }).flatten())
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(self, query: E) -> BoxFuture<'e, Result<Option<PgRow>, Error>>
+        where 'p: 'e,
+              E: Execute<'q, Postgres> + 'q
+    {
+        Box::pin(async move {
+            self.acquire().await?.fetch_optional(query).await
+        })
+    }
+
+    fn prepare_with<'e, 'q: 'e>(self, sql: &'q str, parameters: &'e [PgTypeInfo]) -> BoxFuture<'e, Result<PgStatement<'q>, Error>>
+        where 'p: 'e
+    {
+        self.pool.prepare_with(sql, parameters)
+    }
+
+    fn describe<'e, 'q: 'e>(self, sql: &'q str) -> BoxFuture<'e, Result<Describe<Postgres>, Error>>
+        where 'p: 'e
+    {
+        self.pool.describe(sql)
+    }
+}
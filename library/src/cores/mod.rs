@@ -3,6 +3,7 @@ pub mod base;
 pub mod database;
 pub mod locale;
 pub mod mailer;
+pub mod oauth;
 pub mod paseto;
 pub mod s3;
 
@@ -11,27 +12,46 @@ use std::sync::{Arc, RwLock, RwLockReadGuard};
 use user_agent_parser::UserAgentParser;
 
 use crate::Base;
+use crate::{Cipher, KeyProvider};
 use crate::DBManager;
 use crate::Errors;
 use crate::Locale;
 use crate::Mailer;
+use crate::OAuth;
 use crate::Paseto;
+use crate::PasswordPolicy;
+use crate::PermissionResolver;
+use crate::PersistedQueries;
 use crate::Response;
 use crate::S3;
+use crate::SessionCache;
+use crate::Subscriptions;
 
 /// Core struct - contains core libraries
 /// Locales - internationalization for the entire graphql system
 /// Base - base settings
 /// Mailer - mailer settings & functionalities
+/// OAuth - openid connect provider settings
 /// Paseto - paseto settings & functionalities
+/// Passwords - password policy
+/// Permissions - cached permissions granted to each role
+/// Persisted queries - automatic persisted queries & the strict mode allow-list
 /// S3 - s3 settings & functionalities
+/// Sessions - short lived cache of session lookups
+/// Subscriptions - database notifications streamed to graphql subscriptions
 pub struct Core {
     pub base: Arc<RwLock<Base>>,
     pub database: DBManager,
     pub locale: Arc<Locale>,
     pub mailer: Arc<RwLock<Mailer>>,
+    pub oauth: Arc<RwLock<OAuth>>,
     pub paseto: Arc<RwLock<Paseto>>,
+    pub passwords: PasswordPolicy,
+    pub permissions: PermissionResolver,
+    pub persisted_queries: PersistedQueries,
     pub s3: Arc<RwLock<S3>>,
+    pub sessions: SessionCache,
+    pub subscriptions: Subscriptions,
     pub user_agent_parser: UserAgentParser
 }
 
@@ -43,6 +63,9 @@ impl Core {
         let user_agent_parser = UserAgentParser::from_path(config::USER_AGENT_REGEXES)
             .unwrap_or_else(|_| { panic!("{}", locale.lookup("user-agent-parser-init-failed")) });
 
+        // Cache master keys before any settings are decrypted
+        Cipher::init(&*<dyn KeyProvider>::from_env()?)?;
+
         // Initialize database
         let database = DBManager::init()
             .await?;
@@ -60,6 +83,10 @@ impl Core {
         let mailer = Mailer::init(&database)
             .await?;
 
+        // Initialize oauth configuration
+        let oauth = OAuth::init(&database)
+            .await?;
+
         // Initialize paseto configuration
         let paseto = Paseto::init(&database)
             .await?;
@@ -68,14 +95,31 @@ impl Core {
         let s3 = S3::init(&database)
             .await?;
 
+        // Initialize role permissions
+        let permissions = PermissionResolver::init(&database)
+            .await?;
+
+        // Initialize persisted queries
+        let persisted_queries = PersistedQueries::init()?;
+
+        // Initialize subscription notifications
+        let subscriptions = Subscriptions::init(&database)
+            .await?;
+
         // Initialize core
         let core = Arc::new(Self {
             base,
             database,
             locale,
             mailer,
+            oauth,
             paseto,
+            passwords: PasswordPolicy::init(),
+            permissions,
+            persisted_queries,
             s3,
+            sessions: SessionCache::default(),
+            subscriptions,
             user_agent_parser
         });
 
@@ -83,6 +127,39 @@ impl Core {
         Ok(core)
     }
 
+    /// Rewrite every stored settings module under the current master key.
+    /// Run after rotating `MASTER_KEY` while the old key is still set as `MASTER_KEY_PREVIOUS`.
+    pub async fn reencrypt_settings(manager: &DBManager) -> anyhow::Result<Vec<String>> {
+        let mut modules = Vec::new();
+
+        if let Some(settings) = DBManager::optional(Base::select(manager).await)? {
+            settings.upsert(manager).await?;
+            modules.push(String::from("BASE"));
+        }
+
+        if let Some(settings) = DBManager::optional(Mailer::select(manager).await)? {
+            settings.upsert(manager).await?;
+            modules.push(String::from("MAILER"));
+        }
+
+        if let Some(settings) = DBManager::optional(OAuth::select(manager).await)? {
+            settings.upsert(manager).await?;
+            modules.push(String::from("OAUTH"));
+        }
+
+        if let Some(settings) = DBManager::optional(Paseto::select(manager).await)? {
+            settings.upsert(manager).await?;
+            modules.push(String::from("PASETO"));
+        }
+
+        if let Some(settings) = DBManager::optional(S3::select(manager).await)? {
+            settings.upsert(manager).await?;
+            modules.push(String::from("S3"));
+        }
+
+        Ok(modules)
+    }
+
     pub fn locales<'a>(ctx: &Context<'a>) -> Result<&'a Arc<Locale>> {
         let response = Response::InternalServerError;
         let error = "Unable to retrieve locale settings";
@@ -93,11 +170,17 @@ impl Core {
         }
     }
 
+    /// Database manager scoped to the tenant of the request.
+    /// Requests without one fall back to the anonymous manager, which sees no company scoped rows.
     pub fn database<'a>(ctx: &Context<'a>) -> Result<&'a DBManager> {
         let locale = Self::locales(ctx)?;
         let response = Response::InternalServerError;
         let error = locale.lookup("db-retrieve-failed");
 
+        if let Some(manager) = ctx.data_opt::<DBManager>() {
+            return Ok(manager);
+        }
+
         if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
             return Ok(&settings.database);
         }
@@ -105,6 +188,20 @@ impl Core {
         Err(Errors::to(response, error))
     }
 
+    /// Database manager seeing every company, for work done before the actor is known
+    /// such as signing in, or outside of any tenant such as setup
+    pub fn system_database(ctx: &Context<'_>) -> Result<DBManager> {
+        let locale = Self::locales(ctx)?;
+        let response = Response::InternalServerError;
+        let error = locale.lookup("db-retrieve-failed");
+
+        if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
+            return Ok(settings.database.system());
+        }
+
+        Err(Errors::to(response, error))
+    }
+
     pub fn base<'a>(ctx: &'a Context<'a>) -> Result<RwLockReadGuard<'a, Base>> {
         let locale = Self::locales(ctx)?;
         let response = Response::InternalServerError;
@@ -133,6 +230,20 @@ impl Core {
         Err(Errors::to(response, error))
     }
 
+    pub fn oauth<'a>(ctx: &'a Context<'a>) -> Result<OAuth> {
+        let locale = Self::locales(ctx)?;
+        let response = Response::InternalServerError;
+        let error = locale.lookup("oauth-retrieve-failed");
+
+        if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
+            if let Ok(settings) = settings.oauth.try_read() {
+                return Ok(settings.clone());
+            }
+        }
+
+        Err(Errors::to(response, error))
+    }
+
     pub fn paseto<'a>(ctx: &'a Context<'a>) -> Result<RwLockReadGuard<'a, Paseto>> {
         let locale = Self::locales(ctx)?;
         let response = Response::InternalServerError;
@@ -147,6 +258,30 @@ impl Core {
         Err(Errors::to(response, error))
     }
 
+    pub fn passwords<'a>(ctx: &Context<'a>) -> Result<&'a PasswordPolicy> {
+        let locale = Self::locales(ctx)?;
+        let response = Response::InternalServerError;
+        let error = locale.lookup("password-retrieve-failed");
+
+        if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
+            return Ok(&settings.passwords);
+        }
+
+        Err(Errors::to(response, error))
+    }
+
+    pub fn permissions<'a>(ctx: &Context<'a>) -> Result<&'a PermissionResolver> {
+        let locale = Self::locales(ctx)?;
+        let response = Response::InternalServerError;
+        let error = locale.lookup("permission-retrieve-failed");
+
+        if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
+            return Ok(&settings.permissions);
+        }
+
+        Err(Errors::to(response, error))
+    }
+
     pub fn s3<'a>(ctx: &'a Context<'a>) -> Result<RwLockReadGuard<'a, S3>> {
         let locale = Self::locales(ctx)?;
         let response = Response::InternalServerError;
@@ -161,6 +296,30 @@ impl Core {
         Err(Errors::to(response, error))
     }
 
+    pub fn sessions<'a>(ctx: &Context<'a>) -> Result<&'a SessionCache> {
+        let locale = Self::locales(ctx)?;
+        let response = Response::InternalServerError;
+        let error = locale.lookup("session-retrieve-failed");
+
+        if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
+            return Ok(&settings.sessions);
+        }
+
+        Err(Errors::to(response, error))
+    }
+
+    pub fn subscriptions<'a>(ctx: &Context<'a>) -> Result<&'a Subscriptions> {
+        let locale = Self::locales(ctx)?;
+        let response = Response::InternalServerError;
+        let error = locale.lookup("subscription-retrieve-failed");
+
+        if let Some(settings) = ctx.data_opt::<Arc<Self>>() {
+            return Ok(&settings.subscriptions);
+        }
+
+        Err(Errors::to(response, error))
+    }
+
     pub fn user_agent_parser(&self) -> &UserAgentParser {
         &self.user_agent_parser
     }
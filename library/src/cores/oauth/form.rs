@@ -0,0 +1,112 @@
+use async_graphql::{Context, MaybeUndefined, InputObject, Result};
+use serde::{Serialize, Deserialize};
+
+use macros::{AsForm, SetIsEmpty};
+
+use crate::{Core, Errors, Validator, Response};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
+#[derive(AsForm, SetIsEmpty)]
+#[form(to = crate::OAuth, error = "OAuthError")]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthForm {
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub provider: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub issuer: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub client_id: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub client_secret: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub redirect_uri: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub scopes: MaybeUndefined<String>,
+}
+
+impl OAuthForm {
+    pub fn validate(&mut self, ctx: &Context<'_>) -> Result<&mut Self> {
+        let locale = Core::locales(ctx)?;
+        let data = self.sanitize();
+
+        // Client secret & scopes are optional, public clients rely on pkce alone
+        let is_present = |value: &MaybeUndefined<String>| value
+            .value()
+            .is_some_and(|value| !value.is_empty());
+
+        // Urls must be absolute, plain http is allowed for local issuers
+        let is_invalid_url = |value: &MaybeUndefined<String>| value
+            .value()
+            .is_some_and(|url| reqwest::Url::parse(url).is_err());
+
+        let issuer_error = Validator::new(locale, "oauth-issuer")
+            .set_min(3)
+            .set_max(255)
+            .set_as_required(true)
+            .set_string_value(&data.issuer)
+            .validate_string()
+            .or_else(|| is_invalid_url(&data.issuer).then(|| locale.lookup("oauth-issuer-invalid")));
+
+        let redirect_uri_error = Validator::new(locale, "oauth-redirect-uri")
+            .set_min(3)
+            .set_max(255)
+            .set_as_required(true)
+            .set_string_value(&data.redirect_uri)
+            .validate_string()
+            .or_else(|| is_invalid_url(&data.redirect_uri).then(|| locale.lookup("oauth-redirect-uri-invalid")));
+
+        let error = OAuthError {
+            provider: Validator::new(locale, "oauth-provider")
+                .set_min(2)
+                .set_max(50)
+                .set_as_required(true)
+                .set_string_value(&data.provider)
+                .validate_string(),
+            issuer: issuer_error,
+            client_id: Validator::new(locale, "oauth-client-id")
+                .set_min(3)
+                .set_max(255)
+                .set_as_required(true)
+                .set_string_value(&data.client_id)
+                .validate_string(),
+            client_secret: is_present(&data.client_secret).then(|| Validator::new(locale, "oauth-client-secret")
+                .set_min(3)
+                .set_max(255)
+                .set_string_value(&data.client_secret)
+                .validate_string())
+                .flatten(),
+            redirect_uri: redirect_uri_error,
+            scopes: is_present(&data.scopes).then(|| Validator::new(locale, "oauth-scopes")
+                .set_min(6)
+                .set_max(255)
+                .set_string_value(&data.scopes)
+                .validate_string())
+                .flatten(),
+        };
+
+        let response = Response::BadRequest;
+
+        match error.is_empty() {
+            true => Ok(data),
+            false => Err(Errors::to(response, error))
+        }
+    }
+}
@@ -0,0 +1,52 @@
+pub mod form;
+pub mod queries;
+
+use arraygen::Arraygen;
+use async_graphql::SimpleObject;
+use serde::{Serialize, Deserialize};
+
+pub use form::{OAuthForm, OAuthError};
+
+/// OpenID Connect client credentials.
+/// The issuer is discovered through `{issuer}/.well-known/openid-configuration`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Arraygen)]
+#[derive(macros::SetCipher, macros::SetIsEmpty, macros::SetMutate, sqlx::Type)]
+#[derive(SimpleObject)]
+#[gen_array(fn get_ciphers: &mut String)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(type_name = "JSONB")]
+pub struct OAuth {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub provider: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub issuer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub client_id: String,
+    #[graphql(skip)]
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub client_secret: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub redirect_uri: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub scopes: String,
+}
+
+impl OAuth {
+    /// Whether the provider has every credential needed to sign in
+    pub fn is_configured(&self) -> bool {
+        !self.issuer.is_empty() && !self.client_id.is_empty() && !self.redirect_uri.is_empty()
+    }
+
+    pub fn get_scopes(&self) -> &str {
+        match self.scopes.is_empty() {
+            true => config::OAUTH_DEFAULT_SCOPES,
+            false => &self.scopes
+        }
+    }
+}
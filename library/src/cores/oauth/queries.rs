@@ -0,0 +1,49 @@
+use anyhow::Result;
+use chrono::Utc;
+use nanoid::nanoid;
+use sqlx::types::Json;
+use std::sync::{Arc, RwLock};
+
+use crate::OAuth;
+use crate::DBManager;
+
+impl OAuth {
+    pub async fn init(manager: &DBManager) -> Result<Arc<RwLock<Self>>> {
+        DBManager::settings(Self::select(manager).await)
+    }
+
+    pub async fn select(manager: &DBManager) -> Result<Self> {
+        #[derive(Debug, sqlx::FromRow)]
+        struct Settings {
+            content: Json<OAuth>
+        }
+
+        let result = sqlx::query_as::<_, Settings>("SELECT content FROM settings WHERE module = 'OAUTH'")
+            .fetch_one(manager.reader())
+            .await?
+            .content
+            .decrypt()?;
+
+        Ok(result)
+    }
+
+    pub async fn upsert(&self, manager: &DBManager) -> Result<Self> {
+        let id = nanoid!();
+        let content = Json::from(self.encrypt()?);
+        let timestamp = Utc::now();
+
+        sqlx::query(r#"
+            INSERT INTO settings (id, module, content, created_at, updated_at)
+            VALUES ($1, 'OAUTH', $2, $3, $4)
+            ON CONFLICT (module)
+            DO UPDATE SET content = $2, updated_at = $4
+        "#).bind(id)
+            .bind(content)
+            .bind(timestamp)
+            .bind(timestamp)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(self.clone())
+    }
+}
@@ -4,6 +4,7 @@ use serde::{Serialize, Deserialize};
 use macros::{AsForm, SetIsEmpty};
 
 use crate::Paseto;
+use crate::cores::paseto::keyring::PUBLIC;
 use crate::{Core, Errors, Validator, Response};
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, InputObject)]
@@ -44,6 +45,16 @@ pub struct PasetoForm {
     #[sanitize(crate::sanitize::mustring)]
     #[error(String)]
     pub refresh_token_key_signing: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub access_token_purpose: MaybeUndefined<String>,
+    #[serde(skip_serializing_if = "MaybeUndefined::is_undefined")]
+    #[conversion(crate::conversions::mustr2str)]
+    #[sanitize(crate::sanitize::mustring)]
+    #[error(String)]
+    pub refresh_token_purpose: MaybeUndefined<String>,
 }
 
 impl PasetoForm {
@@ -51,6 +62,16 @@ impl PasetoForm {
         let locale = Core::locales(ctx)?;
         let data = self.sanitize();
 
+        // Purposes are optional and default to local
+        let is_present = |value: &MaybeUndefined<String>| value
+            .value()
+            .is_some_and(|value| !value.is_empty());
+
+        // Public keys are generated server side, a submitted signing key only applies to local tokens
+        let is_public = |value: &MaybeUndefined<String>| value
+            .value()
+            .is_some_and(|value| value == PUBLIC);
+
         let error = PasetoError {
             app_name: Validator::new(locale, "paseto-app-name")
                 .set_min(3)
@@ -70,12 +91,15 @@ impl PasetoForm {
                 .set_as_case_sensitive(false)
                 .set_string_value(&data.access_token_key_time)
                 .validate_list_string(),
-            access_token_key_signing: Validator::new(locale, "paseto-access-token-key-signing")
-                .set_min(3)
-                .set_max(100)
-                .set_as_required(true)
-                .set_string_value(&data.access_token_key_signing)
-                .validate_string(),
+            access_token_key_signing: match is_public(&data.access_token_purpose) {
+                true => None,
+                false => Validator::new(locale, "paseto-access-token-key-signing")
+                    .set_min(3)
+                    .set_max(100)
+                    .set_as_required(true)
+                    .set_string_value(&data.access_token_key_signing)
+                    .validate_string()
+            },
             refresh_token_key_unit: Validator::new(locale, "paseto-refresh-token-key-unit")
                 .set_min(1)
                 .set_max(1000)
@@ -88,12 +112,25 @@ impl PasetoForm {
                 .set_as_case_sensitive(false)
                 .set_string_value(&data.refresh_token_key_time)
                 .validate_list_string(),
-            refresh_token_key_signing: Validator::new(locale, "paseto-refresh-token-key-signing")
-                .set_min(3)
-                .set_max(100)
-                .set_as_required(true)
-                .set_string_value(&data.refresh_token_key_signing)
-                .validate_string()
+            refresh_token_key_signing: match is_public(&data.refresh_token_purpose) {
+                true => None,
+                false => Validator::new(locale, "paseto-refresh-token-key-signing")
+                    .set_min(3)
+                    .set_max(100)
+                    .set_as_required(true)
+                    .set_string_value(&data.refresh_token_key_signing)
+                    .validate_string()
+            },
+            access_token_purpose: Validator::new(locale, "paseto-token-purpose")
+                .set_as_required(is_present(&data.access_token_purpose))
+                .set_option_list_string(&["local", "public"])
+                .set_string_value(&data.access_token_purpose)
+                .validate_list_string(),
+            refresh_token_purpose: Validator::new(locale, "paseto-token-purpose")
+                .set_as_required(is_present(&data.refresh_token_purpose))
+                .set_option_list_string(&["local", "public"])
+                .set_string_value(&data.refresh_token_purpose)
+                .validate_list_string(),
         };
 
         let response = Response::BadRequest;
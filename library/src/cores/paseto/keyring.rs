@@ -0,0 +1,358 @@
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use rand::RngCore;
+use serde::{Serialize, Deserialize};
+
+use crate::Paseto;
+use crate::cores::paseto::public;
+
+/// Token types, each one has its own key
+pub const ACCESS: &str = "access";
+pub const REFRESH: &str = "refresh";
+
+/// Token purposes, local tokens are encrypted with a shared secret while public tokens are signed
+pub const LOCAL: &str = "local";
+pub const PUBLIC: &str = "public";
+
+/// Retired signing key, tokens it issued stay valid until `expires_at`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct PasetoKey {
+    pub kid: String,
+    pub token_type: String,
+    pub purpose: String,
+    #[graphql(skip)]
+    pub key: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl PasetoKey {
+    pub fn is_public(&self) -> bool {
+        self.purpose == PUBLIC
+    }
+}
+
+/// Verification key of a public token type, published so other services can verify tokens offline
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasetoPublicKey {
+    pub kid: String,
+    pub token_type: String,
+    pub version: String,
+    pub public_key: String,
+    pub footer: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl Paseto {
+    /// Generate a random 256-bit local key or an Ed25519 key pair for public tokens
+    pub fn generate_key(purpose: &str) -> String {
+        if purpose == PUBLIC {
+            return public::generate_key().unwrap_or_default();
+        }
+
+        let mut key = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+
+        base64_url::encode(&key)
+    }
+
+    pub fn generate_kid() -> String {
+        nanoid::nanoid!(16)
+    }
+
+    /// Retired keys, stored as a single encrypted json string
+    pub fn get_retired_keys(&self) -> Vec<PasetoKey> {
+        serde_json::from_str(&self.retired_keys).unwrap_or_default()
+    }
+
+    pub fn set_retired_keys(&mut self, keys: Vec<PasetoKey>) -> &mut Self {
+        let now = Utc::now();
+        let keys: Vec<PasetoKey> = keys.into_iter()
+            .filter(|key| key.expires_at.is_some_and(|expires_at| expires_at > now))
+            .collect();
+
+        self.retired_keys = match keys.is_empty() {
+            true => String::new(),
+            false => serde_json::to_string(&keys).unwrap_or_default()
+        };
+
+        self
+    }
+
+    /// Retire the current keys and sign new tokens with freshly generated ones
+    pub fn rotate_keys(&mut self) -> &mut Self {
+        let mut retired_keys = self.get_retired_keys();
+        retired_keys.extend([self.retire_key(ACCESS), self.retire_key(REFRESH)]);
+
+        self.access_token_key_id = Self::generate_kid();
+        self.access_token_key_signing = Self::generate_key(self.get_purpose(ACCESS));
+        self.refresh_token_key_id = Self::generate_kid();
+        self.refresh_token_key_signing = Self::generate_key(self.get_purpose(REFRESH));
+
+        self.set_retired_keys(retired_keys)
+    }
+
+    /// Carry the keyring of the previous settings over, retiring keys that were replaced.
+    /// Public keys are always generated server side, a submitted signing key only applies to local tokens.
+    pub fn merge_keyring(&mut self, previous: &Self) -> &mut Self {
+        let mut retired_keys = previous.get_retired_keys();
+
+        for token_type in [ACCESS, REFRESH] {
+            let purpose = self.get_purpose(token_type);
+            let (previous_kid, previous_purpose, previous_key) = previous.current_key(token_type);
+
+            let key = match purpose == PUBLIC {
+                true if previous_purpose == PUBLIC => None,
+                true => Some(Self::generate_key(PUBLIC)),
+                false => Some(self.current_key(token_type).2.to_string())
+            };
+
+            let (kid, key) = match key {
+                Some(key) if previous_purpose != purpose || key != previous_key => {
+                    retired_keys.push(previous.retire_key(token_type));
+                    (Self::generate_kid(), key)
+                },
+                _ => (previous_kid.to_string(), previous_key.to_string())
+            };
+
+            match token_type {
+                REFRESH => {
+                    self.refresh_token_key_id = kid;
+                    self.refresh_token_key_signing = key;
+                },
+                _ => {
+                    self.access_token_key_id = kid;
+                    self.access_token_key_signing = key;
+                }
+            }
+        }
+
+        self.set_retired_keys(retired_keys)
+    }
+
+    /// Purpose of the token type, defaulting to local
+    pub fn get_purpose(&self, token_type: &str) -> &'static str {
+        let purpose = match token_type {
+            REFRESH => &self.refresh_token_purpose,
+            _ => &self.access_token_purpose
+        };
+
+        match purpose.as_str() {
+            PUBLIC => PUBLIC,
+            _ => LOCAL
+        }
+    }
+
+    /// Current key id, purpose & signing key of the token type
+    pub fn current_key(&self, token_type: &str) -> (&str, &'static str, &str) {
+        match token_type {
+            REFRESH => (&self.refresh_token_key_id, self.get_purpose(REFRESH), &self.refresh_token_key_signing),
+            _ => (&self.access_token_key_id, self.get_purpose(ACCESS), &self.access_token_key_signing)
+        }
+    }
+
+    /// Look up signing key by id, falling back to retired keys within their grace window
+    pub fn find_key(&self, token_type: &str, kid: &str) -> Option<PasetoKey> {
+        let (current_kid, purpose, key) = self.current_key(token_type);
+
+        if current_kid == kid {
+            return Some(PasetoKey {
+                kid: kid.to_string(),
+                token_type: token_type.to_string(),
+                purpose: purpose.to_string(),
+                key: key.to_string(),
+                expires_at: None,
+            });
+        }
+
+        let now = Utc::now();
+
+        self.get_retired_keys()
+            .into_iter()
+            .find(|key| key.token_type == token_type && key.kid == kid && key.expires_at.is_some_and(|expires_at| expires_at > now))
+    }
+
+    /// Verification keys of every public token type, including retired ones still within their grace window
+    pub fn public_keys(&self) -> Vec<PasetoPublicKey> {
+        let current = [ACCESS, REFRESH].map(|token_type| self.find_key(token_type, self.current_key(token_type).0));
+
+        current.into_iter()
+            .flatten()
+            .chain(self.get_retired_keys())
+            .filter(PasetoKey::is_public)
+            .filter_map(|key| Some(PasetoPublicKey {
+                public_key: public::public_key(&key.key).ok()?,
+                footer: self.footer("", &key.kid),
+                version: String::from("v4.public"),
+                kid: key.kid,
+                token_type: key.token_type,
+                expires_at: key.expires_at,
+            }))
+            .collect()
+    }
+
+    /// Footer of tokens signed with the key, tokens issued before key ids existed carry none
+    pub fn footer(&self, suffix: &str, kid: &str) -> String {
+        match kid.is_empty() {
+            true => format!("key-id:{}{suffix}", self.app_name),
+            false => format!("key-id:{}{suffix}:{kid}", self.app_name)
+        }
+    }
+
+    /// Read the key id from the token footer without verifying the token
+    pub fn parse_kid(&self, token: &str, suffix: &str) -> Option<String> {
+        let footer = token.split('.').nth(3)?;
+        let footer = String::from_utf8(base64_url::decode(footer).ok()?).ok()?;
+        let prefix = format!("key-id:{}{suffix}", self.app_name);
+
+        match footer.strip_prefix(&prefix)? {
+            "" => Some(String::new()),
+            kid => kid.strip_prefix(':').map(String::from)
+        }
+    }
+
+    /// Retired copy of the current key, kept as long as the tokens it signed can live
+    fn retire_key(&self, token_type: &str) -> PasetoKey {
+        let (kid, purpose, key) = self.current_key(token_type);
+
+        let expires_at = match token_type {
+            REFRESH => self.get_refresh_token_expiry(),
+            _ => self.get_access_token_expiry()
+        };
+
+        PasetoKey {
+            kid: kid.to_string(),
+            token_type: token_type.to_string(),
+            purpose: purpose.to_string(),
+            key: key.to_string(),
+            expires_at: Some(expires_at),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn paseto(purpose: &str) -> Paseto {
+        Paseto {
+            app_name: String::from("app"),
+            access_token_key_unit: String::from("5"),
+            access_token_key_time: String::from("minutes"),
+            access_token_key_signing: Paseto::generate_key(purpose),
+            access_token_key_id: Paseto::generate_kid(),
+            access_token_purpose: purpose.to_string(),
+            refresh_token_key_unit: String::from("30"),
+            refresh_token_key_time: String::from("days"),
+            refresh_token_key_signing: Paseto::generate_key(purpose),
+            refresh_token_key_id: Paseto::generate_kid(),
+            refresh_token_purpose: purpose.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn token(paseto: &Paseto, token_type: &str) -> String {
+        let expiry = Utc::now() + Duration::minutes(5);
+
+        paseto.build_token(token_type, "", "aid", &expiry, serde_json::json!({})).unwrap()
+    }
+
+    #[test]
+    fn footer_carries_the_key_id() {
+        let paseto = paseto(LOCAL);
+        let token = token(&paseto, ACCESS);
+
+        assert_eq!(paseto.parse_kid(&token, ""), Some(paseto.access_token_key_id.clone()));
+        assert_eq!(paseto.parse_kid(&token, ":mfa"), None);
+        assert_eq!(paseto.parse_kid("v2.local.payload", ""), None);
+    }
+
+    #[test]
+    fn footer_without_key_id_is_legacy() {
+        let paseto = paseto(LOCAL);
+        let footer = base64_url::encode(&paseto.footer("", ""));
+
+        assert_eq!(paseto.parse_kid(&format!("v2.local.payload.{footer}"), ""), Some(String::new()));
+    }
+
+    #[test]
+    fn find_key_selects_by_kid_and_token_type() {
+        let paseto = paseto(LOCAL);
+
+        let key = paseto.find_key(ACCESS, &paseto.access_token_key_id).unwrap();
+        assert_eq!(key.key, paseto.access_token_key_signing);
+        assert_eq!(key.purpose, LOCAL);
+
+        assert!(paseto.find_key(REFRESH, &paseto.access_token_key_id).is_none());
+        assert!(paseto.find_key(ACCESS, "unknown").is_none());
+    }
+
+    #[test]
+    fn rotated_keys_stay_valid_within_their_grace_window() {
+        let mut paseto = paseto(LOCAL);
+        let previous_kid = paseto.access_token_key_id.clone();
+        let previous_token = token(&paseto, ACCESS);
+
+        paseto.rotate_keys();
+
+        assert_ne!(paseto.access_token_key_id, previous_kid);
+        assert!(paseto.find_key(ACCESS, &previous_kid).is_some_and(|key| key.expires_at.is_some()));
+        assert!(paseto.validate_token(&previous_token, ACCESS, "").is_ok());
+        assert!(paseto.validate_token(&token(&paseto, ACCESS), ACCESS, "").is_ok());
+
+        // Refresh tokens are never verified with access keys
+        assert!(paseto.validate_token(&previous_token, REFRESH, "").is_err());
+    }
+
+    #[test]
+    fn retired_keys_past_their_grace_window_are_ignored() {
+        let mut paseto = paseto(LOCAL);
+        let expired = PasetoKey {
+            kid: String::from("expired"),
+            token_type: ACCESS.to_string(),
+            purpose: LOCAL.to_string(),
+            key: Paseto::generate_key(LOCAL),
+            expires_at: Some(Utc::now() - Duration::minutes(1)),
+        };
+
+        paseto.retired_keys = serde_json::to_string(&vec![expired.clone()]).unwrap();
+        assert!(paseto.find_key(ACCESS, &expired.kid).is_none());
+
+        paseto.set_retired_keys(vec![expired]);
+        assert!(paseto.retired_keys.is_empty());
+    }
+
+    #[test]
+    fn merge_keyring_retires_replaced_keys_only() {
+        let previous = paseto(LOCAL);
+
+        let mut unchanged = previous.clone();
+        unchanged.access_token_key_id = String::new();
+        unchanged.merge_keyring(&previous);
+        assert_eq!(unchanged.access_token_key_id, previous.access_token_key_id);
+        assert!(unchanged.get_retired_keys().is_empty());
+
+        let mut replaced = previous.clone();
+        replaced.access_token_key_signing = Paseto::generate_key(LOCAL);
+        replaced.merge_keyring(&previous);
+        assert_ne!(replaced.access_token_key_id, previous.access_token_key_id);
+        assert_eq!(replaced.refresh_token_key_id, previous.refresh_token_key_id);
+        assert_eq!(replaced.get_retired_keys().len(), 1);
+        assert!(replaced.find_key(ACCESS, &previous.access_token_key_id).is_some());
+    }
+
+    #[test]
+    fn public_keys_only_list_public_token_types() {
+        assert!(paseto(LOCAL).public_keys().is_empty());
+
+        let paseto = paseto(PUBLIC);
+        let keys = paseto.public_keys();
+
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().all(|key| key.version == "v4.public"));
+        assert!(keys.iter().any(|key| key.kid == paseto.access_token_key_id));
+    }
+}
@@ -1,24 +1,40 @@
 pub mod form;
+pub mod keyring;
+pub mod public;
 pub mod queries;
 
 use anyhow::Result;
 use arraygen::Arraygen;
-use async_graphql::SimpleObject;
+use async_graphql::{ComplexObject, SimpleObject};
 use chrono::{DateTime, Duration, Utc};
-use pasetolib::tokens::{validate_local_token, PasetoBuilder, TimeBackend};
+use pasetolib::tokens::PasetoBuilder;
+use pasetolib::v2::local::decrypt_paseto;
 use serde::{Serialize, Deserialize};
 
 use crate::prelude::{CustomRole, CustomStatus};
 use crate::{Errors, Response};
 use crate::Claims;
-use crate::Token;
+use crate::{Introspection, Token};
 
 pub use form::{PasetoForm, PasetoError};
+pub use keyring::{PasetoKey, PasetoPublicKey};
+
+use keyring::{ACCESS, PUBLIC, REFRESH};
+
+/// Footer suffix of mfa tokens, keeps them from being accepted as access tokens
+const MFA_SUFFIX: &str = ":mfa";
+
+/// Reason a token failed validation
+enum TokenError {
+    Expired,
+    Invalid,
+}
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Arraygen)]
 #[derive(macros::SetCipher, macros::SetIsEmpty, macros::SetMutate, sqlx::Type)]
 #[derive(SimpleObject)]
 #[gen_array(fn get_ciphers: &mut String)]
+#[graphql(complex)]
 #[serde(rename_all = "camelCase")]
 #[sqlx(type_name = "JSONB")]
 pub struct Paseto {
@@ -42,7 +58,27 @@ pub struct Paseto {
     pub refresh_token_key_time: String,
     #[serde(skip_serializing_if = "String::is_empty")]
     #[in_array(get_ciphers)]
-    pub refresh_token_key_signing: String
+    pub refresh_token_key_signing: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub access_token_purpose: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub refresh_token_purpose: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub access_token_key_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub refresh_token_key_id: String,
+    #[graphql(skip)]
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    #[in_array(get_ciphers)]
+    pub retired_keys: String,
+}
+
+#[ComplexObject]
+impl Paseto {
+    /// Keys still accepted for tokens issued before the last rotation
+    async fn retired_keys(&self) -> Vec<PasetoKey> {
+        self.get_retired_keys()
+    }
 }
 
 impl Paseto {
@@ -55,6 +91,7 @@ impl Paseto {
             refresh_token_key_unit: config::PASETO_REFRESH_TOKEN_KEY_UNIT.to_string(),
             refresh_token_key_time: config::PASETO_REFRESH_TOKEN_KEY_TIME.to_string(),
             refresh_token_key_signing: config::PASETO_REFRESH_TOKEN_KEY_SIGNING.to_string(),
+            ..Default::default()
         }
     }
 
@@ -105,56 +142,19 @@ impl Paseto {
         // Retrieve aid (actor id)
         let aid = aid.to_string();
 
-        // Set app name
-        let app_name = self.app_name.clone();
-
-        // Retrieve access token values
-        let response = Response::InternalServerError;
-        let error = "Unable to generate access token";
-        let access_token_duration = Self::get_duration(&self.access_token_key_unit, &self.access_token_key_time, 5);
-        let access_token_expiry = Self::get_expiration_date(&access_token_duration);
-        let access_token_signing = match base64_url::decode(&self.access_token_key_signing.clone()) {
-            Ok(d) => d,
-            Err(_) => return Err(Errors::to(response, error))
-        };
-
         // Set access token
         let response = Response::InternalServerError;
         let error = "Unable to generate access token";
-        let access_token = match PasetoBuilder::new()
-            .set_encryption_key(&access_token_signing[..])
-            .set_expiration(&access_token_expiry)
-            .set_subject(&aid)
-            .set_footer(&format!("key-id:{app_name}"))
-            .set_claim("data", claims.clone())
-            .build() {
-            Ok(d) => d,
-            Err(_) => return Err(Errors::to(response, error))
-        };
-
-        // Retrieve refresh token values
-        let response = Response::InternalServerError;
-        let error = "Unable to generate refresh token";
-        let refresh_token_duration = Self::get_duration(&self.refresh_token_key_unit, &self.refresh_token_key_time, 30);
-        let refresh_token_expiry = Self::get_expiration_date(&refresh_token_duration);
-        let refresh_token_signing = match base64_url::decode(&self.refresh_token_key_signing.clone()) {
-            Ok(d) => d,
-            Err(_) => return Err(Errors::to(response, error))
-        };
+        let access_token_expiry = self.get_access_token_expiry();
+        let access_token = self.build_token(ACCESS, "", &aid, &access_token_expiry, claims.clone())
+            .ok_or_else(|| Errors::to(response, error))?;
 
         // Set refresh token
         let response = Response::InternalServerError;
         let error = "Unable to generate refresh token";
-        let refresh_token = match PasetoBuilder::new()
-            .set_encryption_key(&refresh_token_signing[..])
-            .set_expiration(&refresh_token_expiry)
-            .set_subject(&aid)
-            .set_footer(&format!("key-id:{app_name}"))
-            .set_claim("data", claims)
-            .build() {
-            Ok(d) => d,
-            Err(_) => return Err(Errors::to(response, error))
-        };
+        let refresh_token_expiry = self.get_refresh_token_expiry();
+        let refresh_token = self.build_token(REFRESH, "", &aid, &refresh_token_expiry, claims)
+            .ok_or_else(|| Errors::to(response, error))?;
 
         // Create mutable token
         let tokens = Token {
@@ -166,43 +166,73 @@ impl Paseto {
         Ok(tokens)
     }
 
-    pub fn validate_access_token<R, S>(&self, token: &str) -> Result<Claims<R, S>>
-        where R: CustomRole,
-              S: CustomStatus
+    /// Generate a short lived access token for an impersonation, it comes without a refresh token
+    pub fn generate_impersonation_token<I, C>(&self, aid: I, claims: &C, expires_at: &DateTime<Utc>) -> async_graphql::Result<String>
+        where I: ToString,
+              C: serde::Serialize
+    {
+        let error = "Unable to generate impersonation token";
+
+        let claims = serde_json::to_value(claims)
+            .map_err(|_| Errors::to(Response::InternalServerError, error))?;
+
+        self.build_token(ACCESS, "", &aid.to_string(), expires_at, claims)
+            .ok_or_else(|| Errors::to(Response::InternalServerError, error))
+    }
+
+    /// Generate a short lived token proving the first sign in factor, it cannot be used as an access token
+    pub fn generate_mfa_token<I>(&self, aid: I) -> async_graphql::Result<String>
+        where I: ToString
     {
-        // Decrypt access token signing
-        let access_token_signing = match base64_url::decode(&self.access_token_key_signing) {
+        let response = Response::InternalServerError;
+        let error = "Unable to generate mfa token";
+
+        // Retrieve mfa token values
+        let aid = aid.to_string();
+        let mfa_token_expiry = Self::get_expiration_date(&Duration::minutes(config::MFA_TOKEN_TTL));
+        let data = serde_json::json!({ "aid": aid });
+
+        // Set mfa token
+        self.build_token(ACCESS, MFA_SUFFIX, &aid, &mfa_token_expiry, data)
+            .ok_or_else(|| Errors::to(response, error))
+    }
+
+    /// Validate mfa token and return the actor id it was issued for
+    pub fn validate_mfa_token(&self, token: &str) -> Result<String> {
+        // Verify token
+        let result = match self.validate_token(token, ACCESS, MFA_SUFFIX) {
             Ok(value) => value,
-            Err(error) => return Err(anyhow::anyhow!(error))
+            Err(TokenError::Expired) => return Err(anyhow::anyhow!("Your mfa token has expired")),
+            Err(TokenError::Invalid) => return Err(anyhow::anyhow!("Invalid mfa token"))
         };
 
-        // Set default values
-        let app_name = format!("key-id:{}", self.app_name);
-        let footer = Some(app_name.as_str());
-        let key = &access_token_signing[..];
-        let backend = TimeBackend::Chrono;
+        // Retrieve actor id from paseto
+        result.get("data")
+            .and_then(|data| data.get("aid"))
+            .and_then(|aid| aid.as_str())
+            .map(|aid| aid.to_string())
+            .ok_or_else(|| anyhow::anyhow!("Invalid mfa token"))
+    }
 
+    pub fn validate_access_token<R, S>(&self, token: &str) -> Result<Claims<R, S>>
+        where R: CustomRole,
+              S: CustomStatus
+    {
         // Verify token
-        let result = match validate_local_token(token, footer, key, &backend) {
+        let result = match self.validate_token(token, ACCESS, "") {
             Ok(value) => value,
-            Err(error) => return match error
-                .to_string()
-                .to_lowercase()
-                .as_str() == "this token is expired (exp claim)."
-            {
-                true => Err(anyhow::anyhow!("Your authentication token has expired")),
-                false => Err(anyhow::anyhow!("Invalid authentication token"))
-            }
+            Err(TokenError::Expired) => return Err(anyhow::anyhow!("Your authentication token has expired")),
+            Err(TokenError::Invalid) => return Err(anyhow::anyhow!("Invalid authentication token"))
         };
 
         // Retrieve values from paseto
-        let result = match result.get("data") {
+        let data = match result.get("data") {
             Some(value) => value.to_owned(),
             None => return Err(anyhow::anyhow!("Invalid authentication token"))
         };
 
         // Return value to custom struct
-        let claims = Claims::from(result);
+        let claims = Claims::from(data).with_registered(&result);
 
         // Return claims
         Ok(claims)
@@ -212,42 +242,197 @@ impl Paseto {
         where R: CustomRole,
               S: CustomStatus
     {
-        // Decrypt refresh token signing
-        let refresh_token_signing = match base64_url::decode(&self.refresh_token_key_signing) {
-            Ok(value) => value,
-            Err(error) => return Err(anyhow::anyhow!(error))
-        };
-
-        // Set default values
-        let app_name = format!("key-id:{}", self.app_name);
-        let footer = Some(app_name.as_str());
-        let key = &refresh_token_signing[..];
-        let backend = TimeBackend::Chrono;
-
         // Verify token
-        let result = match validate_local_token(token, footer, key, &backend) {
+        let result = match self.validate_token(token, REFRESH, "") {
             Ok(value) => value,
-            Err(error) => return match error
-                .to_string()
-                .to_lowercase()
-                .as_str() == "this token is expired (exp claim)."
-            {
-                true => Err(anyhow::anyhow!("Your refresh token has expired")),
-                false => Err(anyhow::anyhow!("Invalid refresh token"))
-            }
+            Err(TokenError::Expired) => return Err(anyhow::anyhow!("Your refresh token has expired")),
+            Err(TokenError::Invalid) => return Err(anyhow::anyhow!("Invalid refresh token"))
         };
 
         // Retrieve values from paseto
-        let result = result.get("data");
-        if result.is_none() {
+        let data = result.get("data");
+        if data.is_none() {
             return Err(anyhow::anyhow!("Invalid refresh token"));
         }
 
         // Return value to custom struct
-        let result = result.map(|value| value.to_owned()).unwrap_or_default();
-        let claims = Claims::from(result);
+        let data = data.map(|value| value.to_owned()).unwrap_or_default();
+        let claims = Claims::from(data).with_registered(&result);
 
         // Return claims
         Ok(claims)
     }
-}
\ No newline at end of file
+
+    /// Inspect a token without failing on expiry so the introspection endpoint can report why it is inactive.
+    /// Access tokens are tried first unless the RFC 7662 hint says otherwise.
+    pub fn introspect(&self, token: &str, token_type_hint: Option<&str>) -> Introspection {
+        let token_types = match token_type_hint {
+            Some("refresh_token") => [REFRESH, ACCESS],
+            _ => [ACCESS, REFRESH]
+        };
+
+        let opened = token_types.into_iter()
+            .find_map(|token_type| self.open_token(token, token_type, "")
+                .ok()
+                .map(|payload| (token_type, payload)));
+
+        let Some((token_type, payload)) = opened else {
+            return Introspection::invalid();
+        };
+
+        match self.check_claims(&payload) {
+            Ok(()) => Introspection::active(token_type, &payload),
+            Err(TokenError::Expired) => Introspection::expired(token_type, &payload),
+            Err(TokenError::Invalid) => Introspection::invalid()
+        }
+    }
+
+    /// Sign token with the current key of the token type, its key id goes into the footer.
+    /// Tokens are issued by and for this app, the app name is both issuer and audience.
+    fn build_token(&self, token_type: &str, suffix: &str, aid: &str, expiry: &DateTime<Utc>, data: serde_json::Value) -> Option<String> {
+        let (kid, purpose, key) = self.current_key(token_type);
+        let footer = self.footer(suffix, kid);
+        let jti = nanoid::nanoid!();
+        let now = Utc::now();
+
+        if purpose == PUBLIC {
+            let claims = serde_json::json!({
+                "iss": self.app_name,
+                "aud": self.app_name,
+                "sub": aid,
+                "jti": jti,
+                "iat": now,
+                "nbf": now,
+                "exp": expiry,
+                "data": data,
+            });
+
+            return public::sign(key, &claims, &footer).ok();
+        }
+
+        let signing = base64_url::decode(key).ok()?;
+
+        PasetoBuilder::new()
+            .set_encryption_key(&signing[..])
+            .set_issuer(&self.app_name)
+            .set_audience(&self.app_name)
+            .set_jti(&jti)
+            .set_issued_at(Some(now))
+            .set_not_before(&now)
+            .set_expiration(expiry)
+            .set_subject(aid)
+            .set_footer(&footer)
+            .set_claim("data", data)
+            .build()
+            .ok()
+    }
+
+    /// Verify token and its claims
+    fn validate_token(&self, token: &str, token_type: &str, suffix: &str) -> std::result::Result<serde_json::Value, TokenError> {
+        let payload = self.open_token(token, token_type, suffix)?;
+
+        self.check_claims(&payload)?;
+
+        Ok(payload)
+    }
+
+    /// Decrypt or verify token with the key referenced by its footer, the token version must match the key purpose
+    fn open_token(&self, token: &str, token_type: &str, suffix: &str) -> std::result::Result<serde_json::Value, TokenError> {
+        // Pick key by footer
+        let key = self.parse_kid(token, suffix)
+            .and_then(|kid| self.find_key(token_type, &kid))
+            .ok_or(TokenError::Invalid)?;
+
+        let footer = self.footer(suffix, &key.kid);
+
+        match (key.is_public(), token.starts_with("v2.local.")) {
+            (false, true) => {
+                let signing = base64_url::decode(&key.key)
+                    .map_err(|_| TokenError::Invalid)?;
+
+                decrypt_paseto(token, Some(footer.as_str()), &signing[..])
+                    .ok()
+                    .and_then(|message| serde_json::from_str(&message).ok())
+                    .ok_or(TokenError::Invalid)
+            },
+            (true, false) => {
+                let public_key = public::public_key(&key.key)
+                    .and_then(|public_key| Ok(base64_url::decode(&public_key)?))
+                    .map_err(|_| TokenError::Invalid)?;
+
+                public::verify(token, &footer, &public_key)
+                    .map_err(|_| TokenError::Invalid)
+            },
+            _ => Err(TokenError::Invalid)
+        }
+    }
+
+    /// Check audience, issuer & expiry, issued at & not before times may be off by the clock skew leeway
+    fn check_claims(&self, payload: &serde_json::Value) -> std::result::Result<(), TokenError> {
+        let string = |key: &str| payload.get(key).and_then(|value| value.as_str());
+        let date = |key: &str| string(key).and_then(|value| value.parse::<DateTime<Utc>>().ok());
+
+        // Tokens meant for another app are never accepted.
+        // Tokens issued before issuer & audience were set carry neither, they stay valid until they expire.
+        if [string("aud"), string("iss")].into_iter().flatten().any(|value| value != self.app_name) {
+            return Err(TokenError::Invalid);
+        }
+
+        let now = Utc::now();
+        let leeway = Duration::seconds(config::PASETO_LEEWAY);
+
+        match date("exp") {
+            Some(expiry) if expiry > now => (),
+            Some(_) => return Err(TokenError::Expired),
+            None => return Err(TokenError::Invalid)
+        }
+
+        match [date("iat"), date("nbf")].into_iter().flatten().any(|date| date > now + leeway) {
+            true => Err(TokenError::Invalid),
+            false => Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paseto() -> Paseto {
+        Paseto {
+            app_name: String::from("app"),
+            ..Default::default()
+        }
+    }
+
+    fn payload(claims: serde_json::Value) -> serde_json::Value {
+        let mut payload = serde_json::json!({ "exp": Utc::now() + Duration::minutes(5) });
+        payload.as_object_mut().unwrap().extend(claims.as_object().unwrap().clone());
+        payload
+    }
+
+    #[test]
+    fn check_claims_requires_this_app_as_issuer_and_audience() {
+        let paseto = paseto();
+
+        assert!(paseto.check_claims(&payload(serde_json::json!({ "iss": "app", "aud": "app" }))).is_ok());
+        assert!(paseto.check_claims(&payload(serde_json::json!({ "iss": "app", "aud": "other" }))).is_err());
+        assert!(paseto.check_claims(&payload(serde_json::json!({ "iss": "other" }))).is_err());
+    }
+
+    #[test]
+    fn check_claims_accepts_tokens_issued_without_issuer_and_audience() {
+        assert!(paseto().check_claims(&payload(serde_json::json!({}))).is_ok());
+    }
+
+    #[test]
+    fn check_claims_rejects_expired_and_future_tokens() {
+        let paseto = paseto();
+        let expired = serde_json::json!({ "exp": Utc::now() - Duration::minutes(1) });
+        let future = payload(serde_json::json!({ "iat": Utc::now() + Duration::minutes(5) }));
+
+        assert!(matches!(paseto.check_claims(&expired), Err(TokenError::Expired)));
+        assert!(matches!(paseto.check_claims(&future), Err(TokenError::Invalid)));
+        assert!(matches!(paseto.check_claims(&serde_json::json!({})), Err(TokenError::Invalid)));
+    }
+}
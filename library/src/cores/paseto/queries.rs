@@ -9,12 +9,15 @@ use crate::DBManager;
 
 impl Paseto {
     pub async fn init(manager: &DBManager) -> Result<Arc<RwLock<Self>>> {
-        if let Ok(settings) = Paseto::select(manager).await {
-            return Ok(Arc::new(RwLock::new(settings)));
+        match Paseto::select(manager).await {
+            Ok(settings) => Ok(Arc::new(RwLock::new(settings))),
+            Err(error) if DBManager::is_not_found(&error) => {
+                let settings = Self::new().upsert(manager).await?;
+                Ok(Arc::new(RwLock::new(settings)))
+            },
+            // Stored signing keys that cannot be decrypted must never be overwritten
+            Err(error) => Err(error)
         }
-
-        let settings = Self::new().upsert(manager).await?;
-        Ok(Arc::new(RwLock::new(settings)))
     }
 
     pub async fn select(manager: &DBManager) -> Result<Self> {
@@ -9,11 +9,7 @@ use crate::DBManager;
 
 impl S3 {
     pub async fn init(manager: &DBManager) -> Result<Arc<RwLock<Self>>> {
-        if let Ok(settings) = Self::select(manager).await {
-            return Ok(Arc::new(RwLock::new(settings)));
-        }
-
-        Ok(Arc::new(RwLock::new(Self::default())))
+        DBManager::settings(Self::select(manager).await)
     }
 
     pub async fn select(manager: &DBManager) -> Result<Self> {
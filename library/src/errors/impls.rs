@@ -19,6 +19,8 @@ impl Errors {
             Response::PaymentRequired => errors.to_payment_required(),
             Response::Forbidden => errors.to_forbidden(),
             Response::NotFound => errors.to_not_found(),
+            Response::TooManyRequests => errors.to_too_many_requests(),
+            Response::QueryLimitExceeded => errors.to_query_limit_exceeded(),
             Response::InternalServerError => errors.to_internal_server_error(),
             Response::ErrorWithoutExtensions => errors.to_error_without_extensions(),
         }
@@ -149,6 +151,56 @@ impl Errors {
         error.extend()
     }
 
+    #[allow(dead_code)]
+    pub fn too_many_requests<T>(error: T) -> async_graphql::Error
+        where T: ToString
+    {
+        Errors::to(Response::TooManyRequests, error.to_string())
+    }
+
+    pub fn to_too_many_requests(&self) -> async_graphql::Error {
+        // Set initial error
+        let error = ErrorResult::TooManyRequests;
+
+        // Check if message is set
+        if let Some(message) = &self.message {
+            return error.extend_with(|_, e| e.set("error", message.to_string()))
+        }
+
+        // Check if errors is set
+        if let Some(errors) = &self.errors {
+            return error.extend_with(|_, e| e.set("errors", errors.clone()))
+        }
+
+        // Return error
+        error.extend()
+    }
+
+    #[allow(dead_code)]
+    pub fn query_limit_exceeded<T>(error: T) -> async_graphql::Error
+        where T: ToString
+    {
+        Errors::to(Response::QueryLimitExceeded, error.to_string())
+    }
+
+    pub fn to_query_limit_exceeded(&self) -> async_graphql::Error {
+        // Set initial error
+        let error = ErrorResult::QueryLimitExceeded;
+
+        // Check if message is set
+        if let Some(message) = &self.message {
+            return error.extend_with(|_, e| e.set("error", message.to_string()))
+        }
+
+        // Check if errors is set
+        if let Some(errors) = &self.errors {
+            return error.extend_with(|_, e| e.set("errors", errors.clone()))
+        }
+
+        // Return error
+        error.extend()
+    }
+
     #[allow(dead_code)]
     pub fn internal_server_error<T>(error: T) -> async_graphql::Error
         where T: ToString
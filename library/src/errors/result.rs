@@ -17,6 +17,12 @@ pub enum ErrorResult {
     #[error("Resource Not Found")]
     NotFound,
 
+    #[error("Too Many Requests")]
+    TooManyRequests,
+
+    #[error("Query Limit Exceeded")]
+    QueryLimitExceeded,
+
     #[error("Internal Server Error")]
     InternalServerError,
 
@@ -33,6 +39,8 @@ impl ErrorExtensions for ErrorResult {
                 Self::PaymentRequired => e.set("code", 402),
                 Self::Forbidden => e.set("code", 403),
                 Self::NotFound => e.set("code", 404),
+                Self::TooManyRequests => e.set("code", 429),
+                Self::QueryLimitExceeded => e.set("code", 413),
                 Self::InternalServerError => e.set("code", 500),
                 Self::ErrorWithoutExtensions => {}
             })
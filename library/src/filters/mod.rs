@@ -0,0 +1,258 @@
+pub mod operators;
+
+use anyhow::Result;
+use async_graphql::Enum;
+use sqlx::{Postgres, QueryBuilder};
+
+pub use operators::{BooleanFilter, Condition, DateTimeFilter, EnumFilter, NumberFilter, StringFilter};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortDirection {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC"
+        }
+    }
+
+    pub fn reverse(&self) -> Self {
+        match self {
+            Self::Asc => Self::Desc,
+            Self::Desc => Self::Asc
+        }
+    }
+}
+
+/// Compiled ordering on an allow-listed column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Order {
+    pub column: &'static str,
+    pub direction: SortDirection,
+}
+
+/// Entity filter input, e.g. `{ name: { contains: "ci" }, or: [{ revokedAt: { isNull: true } }] }`.
+/// The columns returned by `conditions` are the entity's allow-list,
+/// clients only ever pick operators & values, never column names.
+pub trait Filterable: Sized {
+    /// Conditions keyed by column, unset inputs are skipped
+    fn conditions(&self) -> Vec<(&'static str, Option<&dyn Condition>)>;
+
+    /// Filters that must all match
+    fn and(&self) -> &[Self];
+
+    /// Filters of which at least one must match
+    fn or(&self) -> &[Self];
+
+    /// Filter that must not match
+    fn not(&self) -> Option<&Self>;
+}
+
+/// Entity ordering input, the order field enum maps onto the allow-listed columns.
+/// Sortable columns must be `NOT NULL` so keyset pagination can seek on them.
+pub trait Sortable {
+    fn column(&self) -> &'static str;
+
+    fn direction(&self) -> SortDirection;
+}
+
+/// Erase an optional operator input, e.g. `("name", filters::condition(&self.name))`
+pub fn condition<C>(filter: &Option<C>) -> Option<&dyn Condition>
+    where C: Condition
+{
+    filter.as_ref().map(|filter| filter as &dyn Condition)
+}
+
+/// Append the filter as a single `AND (...)` predicate, the query must already have a `WHERE` clause.
+/// Rejects filters nesting deeper than `FILTER_MAX_DEPTH` or binding more than `FILTER_MAX_VALUES` values.
+pub fn push_filter<F>(builder: &mut QueryBuilder<'_, Postgres>, filter: &F) -> Result<()>
+    where F: Filterable
+{
+    let values = count_values(filter, 1)?;
+
+    if values > config::FILTER_MAX_VALUES {
+        return Err(anyhow::anyhow!("Filter binds {values} values, the limit is {}", config::FILTER_MAX_VALUES));
+    }
+
+    builder.push(" AND ");
+    push_group(builder, filter);
+
+    Ok(())
+}
+
+/// Compile the ordering input, rejecting more than `FILTER_MAX_ORDERS` fields
+pub fn orders<S>(order_by: &[S]) -> Result<Vec<Order>>
+    where S: Sortable
+{
+    if order_by.len() > config::FILTER_MAX_ORDERS {
+        return Err(anyhow::anyhow!("Ordering by {} fields, the limit is {}", order_by.len(), config::FILTER_MAX_ORDERS));
+    }
+
+    let orders = order_by
+        .iter()
+        .map(|order| Order {
+            column: order.column(),
+            direction: order.direction(),
+        })
+        .collect();
+
+    Ok(orders)
+}
+
+fn count_values<F>(filter: &F, depth: usize) -> Result<usize>
+    where F: Filterable
+{
+    if depth > config::FILTER_MAX_DEPTH {
+        return Err(anyhow::anyhow!("Filter nests deeper than {} levels", config::FILTER_MAX_DEPTH));
+    }
+
+    let mut values = filter.conditions()
+        .into_iter()
+        .filter_map(|(_, condition)| condition)
+        .map(|condition| condition.values())
+        .sum();
+
+    for group in filter.and().iter().chain(filter.or()).chain(filter.not()) {
+        values += count_values(group, depth + 1)?;
+    }
+
+    Ok(values)
+}
+
+/// Empty groups compile to `TRUE`, so an empty filter matches every row
+fn push_group<F>(builder: &mut QueryBuilder<'_, Postgres>, filter: &F)
+    where F: Filterable
+{
+    builder.push("(TRUE");
+
+    for (column, condition) in filter.conditions() {
+        if let Some(condition) = condition {
+            condition.push(builder, column);
+        }
+    }
+
+    for group in filter.and() {
+        builder.push(" AND ");
+        push_group(builder, group);
+    }
+
+    if !filter.or().is_empty() {
+        builder.push(" AND (FALSE");
+
+        for group in filter.or() {
+            builder.push(" OR ");
+            push_group(builder, group);
+        }
+
+        builder.push(")");
+    }
+
+    if let Some(group) = filter.not() {
+        builder.push(" AND NOT ");
+        push_group(builder, group);
+    }
+
+    builder.push(")");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestFilter {
+        name: Option<StringFilter>,
+        count: Option<NumberFilter>,
+        and: Vec<TestFilter>,
+        or: Vec<TestFilter>,
+        not: Option<Box<TestFilter>>,
+    }
+
+    impl Filterable for TestFilter {
+        fn conditions(&self) -> Vec<(&'static str, Option<&dyn Condition>)> {
+            vec![
+                ("name", condition(&self.name)),
+                ("count", condition(&self.count)),
+            ]
+        }
+
+        fn and(&self) -> &[Self] {
+            &self.and
+        }
+
+        fn or(&self) -> &[Self] {
+            &self.or
+        }
+
+        fn not(&self) -> Option<&Self> {
+            self.not.as_deref()
+        }
+    }
+
+    fn compile(filter: &TestFilter) -> Result<String> {
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM test WHERE TRUE");
+        push_filter(&mut builder, filter)?;
+
+        Ok(builder.into_sql())
+    }
+
+    #[test]
+    fn push_filter_binds_every_value() {
+        let filter = TestFilter {
+            name: Some(StringFilter {
+                eq: Some(String::from("a")),
+                is_in: Some(vec![String::from("b"), String::from("c")]),
+                ..Default::default()
+            }),
+            count: Some(NumberFilter { gte: Some(3), is_null: Some(false), ..Default::default() }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            compile(&filter).unwrap(),
+            "SELECT * FROM test WHERE TRUE AND (TRUE AND name = $1 AND name IN ($2, $3) AND count >= $4 AND count IS NOT NULL)"
+        );
+    }
+
+    #[test]
+    fn push_filter_nests_groups() {
+        let filter = TestFilter {
+            or: vec![
+                TestFilter { name: Some(StringFilter { contains: Some(String::from("50%_off")), ..Default::default() }), ..Default::default() },
+                TestFilter { count: Some(NumberFilter { lt: Some(1), ..Default::default() }), ..Default::default() },
+            ],
+            not: Some(Box::new(TestFilter { name: Some(StringFilter { is_null: Some(true), ..Default::default() }), ..Default::default() })),
+            and: vec![TestFilter::default()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            compile(&filter).unwrap(),
+            "SELECT * FROM test WHERE TRUE AND (TRUE AND (TRUE) AND (FALSE OR (TRUE AND name ILIKE $1) OR (TRUE AND count < $2)) AND NOT (TRUE AND name IS NULL))"
+        );
+        assert_eq!(compile(&TestFilter::default()).unwrap(), "SELECT * FROM test WHERE TRUE AND (TRUE)");
+    }
+
+    #[test]
+    fn push_filter_rejects_deep_or_large_filters() {
+        let mut deep = TestFilter::default();
+
+        for _ in 0..config::FILTER_MAX_DEPTH {
+            deep = TestFilter { not: Some(Box::new(deep)), ..Default::default() };
+        }
+
+        assert!(compile(&deep).is_err());
+
+        let large = TestFilter {
+            count: Some(NumberFilter { is_in: Some(vec![1; config::FILTER_MAX_VALUES + 1]), ..Default::default() }),
+            ..Default::default()
+        };
+
+        assert!(compile(&large).is_err());
+    }
+}
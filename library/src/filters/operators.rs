@@ -0,0 +1,284 @@
+use async_graphql::{InputObject, InputObjectType, InputType, InputValueError, InputValueResult, Name, Value};
+use async_graphql::indexmap::IndexMap;
+use async_graphql::registry::{MetaInputValue, MetaType, MetaTypeId, Registry};
+use chrono::{DateTime, Utc};
+use sqlx::{Encode, Postgres, QueryBuilder, Type};
+use std::borrow::Cow;
+
+/// Operators of a single column, every set operator must match.
+/// Each operator is appended as ` AND <predicate>` with its values bound as parameters.
+pub trait Condition: Send + Sync {
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>, column: &str);
+
+    /// Values bound by `push`, counted against the filter limit
+    fn values(&self) -> usize;
+}
+
+#[derive(Debug, Default, Clone, PartialEq, InputObject)]
+pub struct StringFilter {
+    pub eq: Option<String>,
+    pub ne: Option<String>,
+    #[graphql(name = "in")]
+    pub is_in: Option<Vec<String>>,
+    /// Case insensitive substring match
+    pub contains: Option<String>,
+    /// Case insensitive prefix match
+    pub starts_with: Option<String>,
+    pub is_null: Option<bool>,
+}
+
+impl Condition for StringFilter {
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>, column: &str) {
+        push_compare(builder, column, "=", &self.eq);
+        push_compare(builder, column, "<>", &self.ne);
+        push_in(builder, column, &self.is_in);
+        push_compare(builder, column, "ILIKE", &self.contains.as_ref().map(|value| format!("%{}%", escape_like(value))));
+        push_compare(builder, column, "ILIKE", &self.starts_with.as_ref().map(|value| format!("{}%", escape_like(value))));
+        push_null(builder, column, self.is_null);
+    }
+
+    fn values(&self) -> usize {
+        count(&self.eq) + count(&self.ne) + count_in(&self.is_in) + count(&self.contains) + count(&self.starts_with)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, InputObject)]
+pub struct NumberFilter {
+    pub eq: Option<i64>,
+    pub ne: Option<i64>,
+    #[graphql(name = "in")]
+    pub is_in: Option<Vec<i64>>,
+    pub gt: Option<i64>,
+    pub gte: Option<i64>,
+    pub lt: Option<i64>,
+    pub lte: Option<i64>,
+    pub is_null: Option<bool>,
+}
+
+impl Condition for NumberFilter {
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>, column: &str) {
+        push_compare(builder, column, "=", &self.eq);
+        push_compare(builder, column, "<>", &self.ne);
+        push_in(builder, column, &self.is_in);
+        push_compare(builder, column, ">", &self.gt);
+        push_compare(builder, column, ">=", &self.gte);
+        push_compare(builder, column, "<", &self.lt);
+        push_compare(builder, column, "<=", &self.lte);
+        push_null(builder, column, self.is_null);
+    }
+
+    fn values(&self) -> usize {
+        count(&self.eq) + count(&self.ne) + count_in(&self.is_in) + count(&self.gt) + count(&self.gte) + count(&self.lt) + count(&self.lte)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, InputObject)]
+pub struct DateTimeFilter {
+    pub eq: Option<DateTime<Utc>>,
+    pub ne: Option<DateTime<Utc>>,
+    pub gt: Option<DateTime<Utc>>,
+    pub gte: Option<DateTime<Utc>>,
+    pub lt: Option<DateTime<Utc>>,
+    pub lte: Option<DateTime<Utc>>,
+    pub is_null: Option<bool>,
+}
+
+impl Condition for DateTimeFilter {
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>, column: &str) {
+        push_compare(builder, column, "=", &self.eq);
+        push_compare(builder, column, "<>", &self.ne);
+        push_compare(builder, column, ">", &self.gt);
+        push_compare(builder, column, ">=", &self.gte);
+        push_compare(builder, column, "<", &self.lt);
+        push_compare(builder, column, "<=", &self.lte);
+        push_null(builder, column, self.is_null);
+    }
+
+    fn values(&self) -> usize {
+        count(&self.eq) + count(&self.ne) + count(&self.gt) + count(&self.gte) + count(&self.lt) + count(&self.lte)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, InputObject)]
+pub struct BooleanFilter {
+    pub eq: Option<bool>,
+    pub is_null: Option<bool>,
+}
+
+impl Condition for BooleanFilter {
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>, column: &str) {
+        push_compare(builder, column, "=", &self.eq);
+        push_null(builder, column, self.is_null);
+    }
+
+    fn values(&self) -> usize {
+        count(&self.eq)
+    }
+}
+
+/// Operators of an enum column, named after the enum, e.g. `StatusFilter`.
+/// Input objects cannot be derived generically, so the graphql type is registered by hand.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumFilter<T> {
+    pub eq: Option<T>,
+    pub ne: Option<T>,
+    pub is_in: Option<Vec<T>>,
+    pub is_null: Option<bool>,
+}
+
+impl<T> Default for EnumFilter<T> {
+    fn default() -> Self {
+        Self {
+            eq: None,
+            ne: None,
+            is_in: None,
+            is_null: None,
+        }
+    }
+}
+
+impl<T> Condition for EnumFilter<T>
+    where T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + Sync + 'static
+{
+    fn push(&self, builder: &mut QueryBuilder<'_, Postgres>, column: &str) {
+        push_compare(builder, column, "=", &self.eq);
+        push_compare(builder, column, "<>", &self.ne);
+        push_in(builder, column, &self.is_in);
+        push_null(builder, column, self.is_null);
+    }
+
+    fn values(&self) -> usize {
+        count(&self.eq) + count(&self.ne) + count_in(&self.is_in)
+    }
+}
+
+impl<T> InputType for EnumFilter<T>
+    where T: InputType
+{
+    type RawValueType = Self;
+
+    fn type_name() -> Cow<'static, str> {
+        Cow::Owned(format!("{}Filter", T::type_name()))
+    }
+
+    fn create_type_info(registry: &mut Registry) -> String {
+        registry.create_input_type::<Self, _>(MetaTypeId::InputObject, |registry| {
+            let mut input_fields = IndexMap::new();
+
+            for (name, ty) in [
+                ("eq", <Option<T>>::create_type_info(registry)),
+                ("ne", <Option<T>>::create_type_info(registry)),
+                ("in", <Option<Vec<T>>>::create_type_info(registry)),
+                ("isNull", <Option<bool>>::create_type_info(registry)),
+            ] {
+                input_fields.insert(name.to_string(), MetaInputValue {
+                    name: name.to_string(),
+                    description: None,
+                    ty,
+                    default_value: None,
+                    visible: None,
+                    inaccessible: false,
+                    tags: Vec::new(),
+                    is_secret: false,
+                });
+            }
+
+            MetaType::InputObject {
+                name: Self::type_name().into_owned(),
+                description: None,
+                input_fields,
+                visible: None,
+                inaccessible: false,
+                tags: Vec::new(),
+                rust_typename: Some(std::any::type_name::<Self>()),
+                oneof: false,
+            }
+        })
+    }
+
+    fn parse(value: Option<Value>) -> InputValueResult<Self> {
+        let Some(Value::Object(object)) = value else {
+            return Err(InputValueError::expected_type(value.unwrap_or_default()));
+        };
+
+        Ok(Self {
+            eq: InputType::parse(object.get("eq").cloned()).map_err(InputValueError::propagate)?,
+            ne: InputType::parse(object.get("ne").cloned()).map_err(InputValueError::propagate)?,
+            is_in: InputType::parse(object.get("in").cloned()).map_err(InputValueError::propagate)?,
+            is_null: InputType::parse(object.get("isNull").cloned()).map_err(InputValueError::propagate)?,
+        })
+    }
+
+    fn to_value(&self) -> Value {
+        let mut object = IndexMap::new();
+
+        object.insert(Name::new("eq"), self.eq.to_value());
+        object.insert(Name::new("ne"), self.ne.to_value());
+        object.insert(Name::new("in"), self.is_in.to_value());
+        object.insert(Name::new("isNull"), self.is_null.to_value());
+
+        Value::Object(object)
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+}
+
+impl<T> InputObjectType for EnumFilter<T> where T: InputType {}
+
+fn push_compare<T>(builder: &mut QueryBuilder<'_, Postgres>, column: &str, operator: &str, value: &Option<T>)
+    where T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + 'static
+{
+    if let Some(value) = value {
+        builder.push(format!(" AND {column} {operator} "))
+            .push_bind(value.clone());
+    }
+}
+
+/// Binds every value separately so any encodable type works, an empty list matches nothing
+fn push_in<T>(builder: &mut QueryBuilder<'_, Postgres>, column: &str, values: &Option<Vec<T>>)
+    where T: for<'q> Encode<'q, Postgres> + Type<Postgres> + Clone + Send + 'static
+{
+    match values {
+        Some(values) if values.is_empty() => {
+            builder.push(" AND FALSE");
+        },
+        Some(values) => {
+            builder.push(format!(" AND {column} IN ("));
+
+            let mut separated = builder.separated(", ");
+
+            for value in values {
+                separated.push_bind(value.clone());
+            }
+
+            separated.push_unseparated(")");
+        },
+        None => ()
+    }
+}
+
+fn push_null(builder: &mut QueryBuilder<'_, Postgres>, column: &str, is_null: Option<bool>) {
+    match is_null {
+        Some(true) => builder.push(format!(" AND {column} IS NULL")),
+        Some(false) => builder.push(format!(" AND {column} IS NOT NULL")),
+        None => builder
+    };
+}
+
+/// Match `%`, `_` & `\` literally, backslash is the default `LIKE` escape
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+fn count<T>(value: &Option<T>) -> usize {
+    usize::from(value.is_some())
+}
+
+fn count_in<T>(values: &Option<Vec<T>>) -> usize {
+    values.as_ref().map_or(0, Vec::len)
+}
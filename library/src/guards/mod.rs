@@ -1,18 +1,23 @@
 use async_graphql::{ Context, Guard as AsyncGQLGuard, Result };
+use std::sync::Arc;
 
 use crate::Claims;
+use crate::Core;
 use crate::{Errors, Response};
 use crate::{ExpiredToken, InvalidToken};
 use crate::prelude::{CustomRole, CustomStatus};
 
 const EXPIRED: &str = "We're sorry, but your authentication token has expired. Please sign in again to continue.";
 const FORBIDDEN: &str = "Sorry, you do not have the required permissions to perform this action.";
+const INACTIVE: &str = "Sorry, your account is not allowed to perform this action.";
+const MFA_REQUIRED: &str = "Sorry, your role requires two-factor authentication. Please enable it to continue.";
 
 #[derive(Default, Debug, Clone, PartialEq)]
 pub struct Guard<R, S> where R: CustomRole, S: CustomStatus {
     authentication: bool,
     role: Option<Vec<R>>,
     status: Option<Vec<S>>,
+    permission: Option<String>,
 }
 
 #[async_graphql::async_trait::async_trait]
@@ -36,17 +41,47 @@ impl<R, S> AsyncGQLGuard for Guard<R, S> where R: CustomRole, S: CustomStatus {
             None => Claims::default()
         };
 
+        // Roles that require a second factor are refused until the session was signed in with it
+        if claims.is_missing_mfa() {
+            return Err(Self::mfa_required(ctx));
+        }
+
+        // Grants are only looked up for a valid session whose status passes the guard
+        if let Some(permission) = &self.permission {
+            if is_expired {
+                return Err(Errors::to(Response::Unauthorized, EXPIRED));
+            }
+
+            if is_invalid {
+                return Err(Errors::to(Response::Forbidden, FORBIDDEN));
+            }
+
+            if !claims.is_empty() {
+                self.check_status(ctx, &claims)?;
+            }
+
+            return Self::check_permission(ctx, &role, permission).await;
+        }
+
         if !self.authentication && roles.contains(&role) {
             return Ok(());
         }
 
+        // The controller signs in with the master key and carries no claims
+        if self.authentication && claims.is_empty() && role.is_controller()
+            && (roles.is_empty() || roles.contains(&role)) {
+            return Ok(());
+        }
+
         if self.authentication && !claims.is_empty()
             && !is_expired && !is_invalid {
             let role = claims.role
+                .clone()
                 .unwrap_or_default();
 
-            if roles.contains(&role) {
-                return Ok(());
+            // Authenticated guards without roles allow every role
+            if roles.is_empty() || roles.contains(&role) {
+                return self.check_status(ctx, &claims);
             }
         }
 
@@ -71,6 +106,45 @@ impl <R,S> Guard<R, S> where R: CustomRole, S: CustomStatus {
         }
     }
 
+    /// Allow roles granted the permission, e.g. `company:update`, the controller holds every permission
+    pub fn permission<T>(permission: T) -> Self
+        where T: ToString
+    {
+        Self {
+            permission: Some(permission.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Allow roles granted the permission whose session has one of the statuses
+    pub fn permission_and_status<T>(permission: T, status: Vec<S>) -> Self
+        where T: ToString
+    {
+        Self {
+            permission: Some(permission.to_string()),
+            status: (!status.is_empty()).then_some(status),
+            ..Default::default()
+        }
+    }
+
+    /// Allow any signed in actor, whatever their role & status
+    pub fn authenticated() -> Self {
+        Self {
+            authentication: true,
+            ..Default::default()
+        }
+    }
+
+    /// Allow signed in actors with one of the roles & statuses, an empty list allows any of them
+    pub fn role_and_status(role: Vec<R>, status: Vec<S>) -> Self {
+        Self {
+            authentication: true,
+            role: (!role.is_empty()).then_some(role),
+            status: (!status.is_empty()).then_some(status),
+            ..Default::default()
+        }
+    }
+
     pub fn controller() -> Self {
         Self::role(vec![R::get_controller()])
     }
@@ -86,4 +160,207 @@ impl <R,S> Guard<R, S> where R: CustomRole, S: CustomStatus {
     pub fn is_guest(ctx: &Context) -> bool {
         R::get(ctx).is_guest()
     }
-}
\ No newline at end of file
+
+    fn check_status(&self, ctx: &Context<'_>, claims: &Claims<R, S>) -> Result<()> {
+        let Some(statuses) = &self.status else {
+            return Ok(());
+        };
+
+        let status = claims.status
+            .clone()
+            .unwrap_or_default();
+
+        if statuses.contains(&status) {
+            return Ok(());
+        }
+
+        let error = match ctx.data_opt::<Arc<Core>>() {
+            Some(core) => core.locale.lookup_with_args(
+                "guard-status-forbidden",
+                &[("status", status.to_string().to_lowercase())]
+            ),
+            None => INACTIVE.to_string()
+        };
+
+        Err(Errors::to(Response::Forbidden, error))
+    }
+
+    fn mfa_required(ctx: &Context<'_>) -> async_graphql::Error {
+        let error = match ctx.data_opt::<Arc<Core>>() {
+            Some(core) => core.locale.lookup("guard-mfa-required"),
+            None => MFA_REQUIRED.to_string()
+        };
+
+        Errors::to(Response::Forbidden, error)
+    }
+
+    async fn check_permission(ctx: &Context<'_>, role: &R, permission: &str) -> Result<()> {
+        if role.is_controller() {
+            return Ok(());
+        }
+
+        let is_granted = match ctx.data_opt::<Arc<Core>>() {
+            Some(core) => core.permissions
+                .has_permission(&core.database, &role.to_string(), permission)
+                .await
+                .map_err(|error| Errors::to(Response::InternalServerError, error.to_string()))?,
+            None => false
+        };
+
+        if is_granted {
+            return Ok(());
+        }
+
+        Err(Errors::to(Response::Forbidden, FORBIDDEN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Schema, Value};
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize)]
+    enum TestRole {
+        Controller,
+        Admin,
+        #[default]
+        Guest
+    }
+
+    impl CustomRole for TestRole {
+        fn get(ctx: &Context<'_>) -> Self {
+            ctx.data_opt::<Self>().copied().unwrap_or_default()
+        }
+
+        fn get_controller() -> Self {
+            Self::Controller
+        }
+
+        fn get_admin() -> Self {
+            Self::Admin
+        }
+
+        fn get_guest() -> Self {
+            Self::Guest
+        }
+
+        fn from_str(s: &str) -> Self {
+            match s {
+                "CONTROLLER" => Self::Controller,
+                "ADMIN" => Self::Admin,
+                _ => Self::Guest
+            }
+        }
+
+        fn to_string(&self) -> String {
+            format!("{self:?}").to_uppercase()
+        }
+    }
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Serialize)]
+    enum TestStatus {
+        Active,
+        #[default]
+        Inactive
+    }
+
+    impl CustomStatus for TestStatus {
+        fn from_str(s: &str) -> Self {
+            match s {
+                "ACTIVE" => Self::Active,
+                _ => Self::Inactive
+            }
+        }
+
+        fn to_string(&self) -> String {
+            format!("{self:?}").to_uppercase()
+        }
+    }
+
+    type TestGuard = Guard<TestRole, TestStatus>;
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        #[graphql(guard = "TestGuard::permission(\"role:read\")")]
+        async fn roles(&self) -> bool {
+            true
+        }
+
+        #[graphql(guard = "TestGuard::permission_and_status(\"role:read\", vec![TestStatus::Active])")]
+        async fn active_roles(&self) -> bool {
+            true
+        }
+    }
+
+    fn claims(role: TestRole, status: TestStatus) -> Claims<TestRole, TestStatus> {
+        Claims {
+            aid: Some(String::from("actor")),
+            role: Some(role),
+            status: Some(status),
+            ..Default::default()
+        }
+    }
+
+    /// Run the request and return the detail of its first error, if any
+    async fn execute(request: Request) -> Option<String> {
+        let schema = Schema::new(Query, EmptyMutation, EmptySubscription);
+        let response = schema.execute(request).await;
+        let error = response.errors.first()?;
+
+        match error.extensions.as_ref().and_then(|extensions| extensions.get("errors")) {
+            Some(Value::String(detail)) => Some(detail.clone()),
+            _ => Some(error.message.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn permission_lets_the_controller_through() {
+        let request = Request::new("{ roles }")
+            .data(TestRole::Controller);
+
+        assert_eq!(execute(request).await, None);
+    }
+
+    #[tokio::test]
+    async fn permission_refuses_roles_without_the_grant() {
+        let request = Request::new("{ roles }")
+            .data(TestRole::Admin)
+            .data(claims(TestRole::Admin, TestStatus::Active));
+
+        assert_eq!(execute(request).await.as_deref(), Some(FORBIDDEN));
+        assert_eq!(execute(Request::new("{ roles }")).await.as_deref(), Some(FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn permission_refuses_expired_and_invalid_tokens() {
+        let expired = Request::new("{ roles }")
+            .data(TestRole::Controller)
+            .data(ExpiredToken::new("token"));
+
+        let invalid = Request::new("{ roles }")
+            .data(TestRole::Controller)
+            .data(InvalidToken::new("token"));
+
+        assert_eq!(execute(expired).await.as_deref(), Some(EXPIRED));
+        assert_eq!(execute(invalid).await.as_deref(), Some(FORBIDDEN));
+    }
+
+    #[tokio::test]
+    async fn permission_and_status_refuses_other_statuses() {
+        let inactive = Request::new("{ activeRoles }")
+            .data(TestRole::Controller)
+            .data(claims(TestRole::Controller, TestStatus::Inactive));
+
+        let active = Request::new("{ activeRoles }")
+            .data(TestRole::Controller)
+            .data(claims(TestRole::Controller, TestStatus::Active));
+
+        assert_eq!(execute(inactive).await.as_deref(), Some(INACTIVE));
+        assert_eq!(execute(active).await, None);
+    }
+}
@@ -0,0 +1,19 @@
+use sha2::{Digest, Sha256};
+
+/// Hash value using sha256 and return it as a url safe base64 string.
+/// Use this for high entropy secrets (tokens, keys), never for passwords.
+pub fn sha256<T>(value: T) -> String
+    where T: AsRef<[u8]>
+{
+    base64_url::encode(&Sha256::digest(value.as_ref()))
+}
+
+/// Hash value using sha256 and return it as a lowercase hex string, e.g. persisted query hashes
+pub fn sha256_hex<T>(value: T) -> String
+    where T: AsRef<[u8]>
+{
+    Sha256::digest(value.as_ref())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
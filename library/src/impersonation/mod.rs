@@ -0,0 +1,72 @@
+use anyhow::Result;
+
+use crate::{Claims, DBManager};
+use crate::prelude::{CustomRole, CustomStatus};
+
+/// Operation type recorded when an impersonation starts
+pub const START: &str = "IMPERSONATE";
+
+/// Audit row of an impersonation, kept even after the impersonated actor is deleted
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImpersonationAudit {
+    pub id: String,
+    pub impersonator_id: String,
+    pub actor_id: String,
+    pub session_id: Option<String>,
+    pub operation_type: String,
+    pub operation_name: Option<String>,
+    pub query: Option<String>,
+    pub ip: Option<String>,
+}
+
+impl ImpersonationAudit {
+    /// Audit row for the claims, none unless they come from an impersonation token
+    pub fn from_claims<R, S>(claims: &Claims<R, S>, operation_type: &str) -> Option<Self>
+        where R: CustomRole,
+              S: CustomStatus
+    {
+        Some(Self {
+            id: nanoid::nanoid!(),
+            impersonator_id: claims.iid.clone()?,
+            actor_id: claims.aid.clone()?,
+            session_id: claims.sid.clone(),
+            operation_type: operation_type.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn set_operation_name(&mut self, operation_name: Option<String>) -> &mut Self {
+        self.operation_name = operation_name;
+        self
+    }
+
+    pub fn set_query<T>(&mut self, query: T) -> &mut Self
+        where T: ToString
+    {
+        self.query = Some(query.to_string());
+        self
+    }
+
+    pub fn set_ip(&mut self, ip: Option<String>) -> &mut Self {
+        self.ip = ip;
+        self
+    }
+
+    pub async fn insert(&self, manager: &DBManager) -> Result<()> {
+        sqlx::query(r#"
+            INSERT INTO impersonation_audit (id, impersonator_id, actor_id, session_id, operation_type, operation_name, query, ip)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "#).bind(&self.id)
+            .bind(&self.impersonator_id)
+            .bind(&self.actor_id)
+            .bind(&self.session_id)
+            .bind(&self.operation_type)
+            .bind(&self.operation_name)
+            .bind(&self.query)
+            .bind(&self.ip)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(())
+    }
+}
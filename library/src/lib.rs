@@ -1,18 +1,31 @@
+pub mod api_keys;
 pub mod assets;
 pub mod ciphers;
 pub mod claims;
 pub mod conversions;
 pub mod cores;
 pub mod errors;
+pub mod filters;
 pub mod guards;
+pub mod hashes;
+pub mod impersonation;
 pub mod middlewares;
+pub mod oauth;
+pub mod pagination;
 pub mod parsers;
+pub mod passwords;
+pub mod permissions;
+pub mod persisted_queries;
 pub mod prelude;
 pub mod responses;
 pub mod sanitize;
 pub mod scheduler;
+pub mod sessions;
 pub mod sse;
+pub mod subscriptions;
+pub mod tenants;
 pub mod tokens;
+pub mod totp;
 pub mod validator;
 pub mod websockets;
 
@@ -25,22 +38,38 @@ pub use cores::mailer::credentials::MailerCredentials;
 
 pub use cores::base::{Base, BaseForm, BaseError};
 pub use cores::mailer::{Mailer, MailerForm, MailerError};
-pub use cores::paseto::{Paseto, PasetoForm, PasetoError};
+pub use cores::oauth::{OAuth, OAuthForm, OAuthError};
+pub use cores::paseto::{Paseto, PasetoForm, PasetoError, PasetoKey, PasetoPublicKey};
 pub use cores::s3::{S3, S3Form, S3Error};
 
+pub use api_keys::ApiKeyIdentity;
 pub use assets::Asset;
 pub use ciphers::Cipher;
+pub use ciphers::blind_index::BlindIndexField;
+pub use ciphers::providers::{EnvKeyProvider, EnvelopeKeyProvider, FileKeyProvider, KeyProvider, MasterKeys};
 pub use claims::Claims;
 pub use errors::Errors;
 pub use guards::Guard;
+pub use impersonation::ImpersonationAudit;
+pub use oauth::{OAuthAuthorization, OAuthBinding, OAuthIdentity, OAuthProvider, OAuthRequest, OidcProvider};
+pub use passwords::{Password, PasswordPolicy};
+pub use permissions::PermissionResolver;
+pub use persisted_queries::PersistedQueries;
 pub use responses::Response;
+pub use sessions::SessionCache;
+pub use subscriptions::Subscriptions;
+pub use tenants::Tenant;
+pub use totp::Totp;
 pub use validator::Validator;
 
 pub use middlewares::actix_token_parser::ActixTokenParser;
+pub use middlewares::gql_persisted_queries::GqlPersistedQueries;
+pub use middlewares::gql_query_limits::GqlQueryLimits;
 pub use middlewares::gql_token_parser::GqlTokenParser;
 
 pub use tokens::bearer::BearerToken;
 pub use tokens::expired::ExpiredToken;
+pub use tokens::introspection::{Introspection, IntrospectionRequest};
 pub use tokens::invalid::InvalidToken;
 pub use tokens::token::Token;
 
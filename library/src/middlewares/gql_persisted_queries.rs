@@ -0,0 +1,99 @@
+use async_graphql::{ ErrorExtensions, Request, ServerError, ServerResult, Variables };
+use async_graphql::async_trait::async_trait;
+use async_graphql::extensions::{ Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextPrepareRequest };
+use async_graphql::parser::types::{ ExecutableDocument, OperationType };
+use std::sync::Arc;
+
+use crate::{Core, Errors};
+use crate::persisted_queries::{EXTENSION, PersistedQuery, PersistedQueryError, ReadOnlyRequest};
+
+/// Resolves automatic persisted queries & enforces the manifest allow-list in strict mode
+#[derive(Debug, Default, Clone)]
+pub struct GqlPersistedQueries;
+
+impl ExtensionFactory for GqlPersistedQueries {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GqlPersistedQueriesExtension)
+    }
+}
+
+pub struct GqlPersistedQueriesExtension;
+
+impl GqlPersistedQueriesExtension {
+    fn to_server_error(core: &Core, error: PersistedQueryError) -> ServerError {
+        let error = match error {
+            // Clients match the message & code of the protocol to retry with the full query
+            PersistedQueryError::NotFound => async_graphql::Error::new("PersistedQueryNotFound")
+                .extend_with(|_, e| e.set("code", "PERSISTED_QUERY_NOT_FOUND")),
+            PersistedQueryError::HashMismatch => Errors::bad_request(core.locale.lookup("persisted-query-hash-mismatch")),
+            PersistedQueryError::UnsupportedVersion => Errors::bad_request(core.locale.lookup("persisted-query-version-unsupported")),
+            PersistedQueryError::NotAllowed => Errors::forbidden(core.locale.lookup("persisted-query-not-allowed"))
+        };
+
+        let mut server_error = ServerError::new(error.message, None);
+        server_error.extensions = error.extensions;
+
+        server_error
+    }
+}
+
+#[async_trait]
+impl Extension for GqlPersistedQueriesExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        mut request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let Some(core) = ctx.data_opt::<Arc<Core>>() else {
+            return next.run(ctx, request).await;
+        };
+
+        let persisted = match request.extensions.remove(EXTENSION) {
+            Some(value) => match value.into_json().map(serde_json::from_value::<PersistedQuery>) {
+                Ok(Ok(persisted)) => Some(persisted),
+                _ => return Err(ServerError::new(core.locale.lookup("persisted-query-invalid"), None))
+            },
+            None => None
+        };
+
+        let query = core.persisted_queries
+            .resolve(&core.database, &request.query, persisted)
+            .await
+            .map_err(|error| Self::to_server_error(core, error))?;
+
+        if let Some(query) = query {
+            request.query = query;
+        }
+
+        next.run(ctx, request).await
+    }
+
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        // Cacheable GET requests must not change anything
+        if ctx.data_opt::<ReadOnlyRequest>().is_some() {
+            let is_mutation = document.operations
+                .iter()
+                .any(|(_, operation)| operation.node.ty == OperationType::Mutation);
+
+            if is_mutation {
+                let message = match ctx.data_opt::<Arc<Core>>() {
+                    Some(core) => core.locale.lookup("persisted-query-get-mutation"),
+                    None => String::from("Mutations must be sent over POST")
+                };
+
+                return Err(ServerError::new(message, None));
+            }
+        }
+
+        Ok(document)
+    }
+}
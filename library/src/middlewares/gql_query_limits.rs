@@ -0,0 +1,326 @@
+use async_graphql::{ ServerError, ServerResult, ValidationResult, Variables };
+use async_graphql::async_trait::async_trait;
+use async_graphql::extensions::{ Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextValidation };
+use async_graphql::parser::types::{ ExecutableDocument, Selection, SelectionSet };
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
+
+use crate::{Core, Errors, Locale, Response};
+
+/// Rejects queries that are too deep, too complex or select too many aliases & root fields.
+/// Field costs come from `#[graphql(complexity = ...)]` annotations, every other field costs one.
+#[derive(Debug, Clone)]
+pub struct GqlQueryLimits {
+    depth: usize,
+    complexity: usize,
+    aliases: usize,
+    root_fields: usize,
+}
+
+impl Default for GqlQueryLimits {
+    fn default() -> Self {
+        Self::new(
+            config::QUERY_MAX_DEPTH,
+            config::QUERY_MAX_COMPLEXITY,
+            config::QUERY_MAX_ALIASES,
+            config::QUERY_MAX_ROOT_FIELDS,
+        )
+    }
+}
+
+impl GqlQueryLimits {
+    pub fn new(depth: usize, complexity: usize, aliases: usize, root_fields: usize) -> Self {
+        Self {
+            depth,
+            complexity,
+            aliases,
+            root_fields,
+        }
+    }
+}
+
+impl ExtensionFactory for GqlQueryLimits {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(GqlQueryLimitsExtension {
+            limits: self.clone(),
+        })
+    }
+}
+
+pub struct GqlQueryLimitsExtension {
+    limits: GqlQueryLimits,
+}
+
+/// Every check is instrumented, rejections show up as errors of the check in the metrics
+impl GqlQueryLimitsExtension {
+    #[autometrics::autometrics]
+    fn check_depth(&self, depth: usize) -> Result<(), usize> {
+        Self::check(depth, self.limits.depth)
+    }
+
+    #[autometrics::autometrics]
+    fn check_complexity(&self, complexity: usize) -> Result<(), usize> {
+        Self::check(complexity, self.limits.complexity)
+    }
+
+    #[autometrics::autometrics]
+    fn check_aliases(&self, aliases: usize) -> Result<(), usize> {
+        Self::check(aliases, self.limits.aliases)
+    }
+
+    #[autometrics::autometrics]
+    fn check_root_fields(&self, root_fields: usize) -> Result<(), usize> {
+        Self::check(root_fields, self.limits.root_fields)
+    }
+}
+
+impl GqlQueryLimitsExtension {
+    fn check(actual: usize, max: usize) -> Result<(), usize> {
+        match actual > max {
+            true => Err(max),
+            false => Ok(())
+        }
+    }
+
+    fn locale<'a>(ctx: &ExtensionContext<'a>) -> Option<&'a Locale> {
+        ctx.data_opt::<Arc<Core>>().map(|core| core.locale.as_ref())
+    }
+
+    /// Collect the exceeded limits into a single localized error keyed by limit
+    fn exceeded(locale: Option<&Locale>, checks: &[(&str, &str, usize, Result<(), usize>)]) -> Option<ServerError> {
+        let mut errors = BTreeMap::new();
+
+        for (name, key, actual, result) in checks {
+            if let Err(max) = result {
+                let message = match locale {
+                    Some(locale) => locale.lookup_with_args(key, &[
+                        ("actual", actual.to_string()),
+                        ("max", max.to_string())
+                    ]),
+                    None => format!("Query exceeds the {name} limit of {max}")
+                };
+
+                errors.insert(*name, message);
+            }
+        }
+
+        if errors.is_empty() {
+            return None;
+        }
+
+        // The limits apply to the whole document, so the error has no location
+        let error = Errors::to(Response::QueryLimitExceeded, errors);
+        let mut server_error = ServerError::new(error.message, None);
+        server_error.extensions = error.extensions;
+
+        Some(server_error)
+    }
+
+    /// Count aliased fields across every operation & fragment
+    fn count_aliases(document: &ExecutableDocument) -> usize {
+        fn count(selection_set: &SelectionSet) -> usize {
+            selection_set.items.iter().map(|selection| match &selection.node {
+                Selection::Field(field) => {
+                    usize::from(field.node.alias.is_some()) + count(&field.node.selection_set.node)
+                },
+                Selection::InlineFragment(fragment) => count(&fragment.node.selection_set.node),
+                Selection::FragmentSpread(_) => 0
+            }).sum()
+        }
+
+        let operations: usize = document.operations
+            .iter()
+            .map(|(_, operation)| count(&operation.node.selection_set.node))
+            .sum();
+
+        let fragments: usize = document.fragments
+            .values()
+            .map(|fragment| count(&fragment.node.selection_set.node))
+            .sum();
+
+        operations + fragments
+    }
+
+    /// Count the root fields of the largest operation, spreading root fragments.
+    /// Runs before validation so fragment cycles are skipped instead of followed.
+    fn count_root_fields(document: &ExecutableDocument) -> usize {
+        fn count<'a>(document: &'a ExecutableDocument, selection_set: &'a SelectionSet, visited: &mut HashSet<&'a str>) -> usize {
+            selection_set.items.iter().map(|selection| match &selection.node {
+                Selection::Field(_) => 1,
+                Selection::InlineFragment(fragment) => count(document, &fragment.node.selection_set.node, visited),
+                Selection::FragmentSpread(spread) => {
+                    let name = spread.node.fragment_name.node.as_str();
+
+                    match (visited.insert(name), document.fragments.get(&spread.node.fragment_name.node)) {
+                        (true, Some(fragment)) => count(document, &fragment.node.selection_set.node, visited),
+                        _ => 0
+                    }
+                }
+            }).sum()
+        }
+
+        document.operations
+            .iter()
+            .map(|(_, operation)| count(document, &operation.node.selection_set.node, &mut HashSet::new()))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl Extension for GqlQueryLimitsExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        let aliases = Self::count_aliases(&document);
+        let root_fields = Self::count_root_fields(&document);
+
+        let error = Self::exceeded(Self::locale(ctx), &[
+            ("aliases", "query-limit-aliases", aliases, self.check_aliases(aliases)),
+            ("rootFields", "query-limit-root-fields", root_fields, self.check_root_fields(root_fields)),
+        ]);
+
+        match error {
+            Some(error) => Err(error),
+            None => Ok(document)
+        }
+    }
+
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>,
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        let result = next.run(ctx).await?;
+
+        let error = Self::exceeded(Self::locale(ctx), &[
+            ("depth", "query-limit-depth", result.depth, self.check_depth(result.depth)),
+            ("complexity", "query-limit-complexity", result.complexity, self.check_complexity(result.complexity)),
+        ]);
+
+        match error {
+            Some(error) => Err(vec![error]),
+            None => Ok(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Value;
+    use async_graphql::parser::parse_query;
+
+    use super::*;
+
+    fn aliases(query: &str) -> usize {
+        GqlQueryLimitsExtension::count_aliases(&parse_query(query).unwrap())
+    }
+
+    fn root_fields(query: &str) -> usize {
+        GqlQueryLimitsExtension::count_root_fields(&parse_query(query).unwrap())
+    }
+
+    #[test]
+    fn count_aliases_covers_operations_fragments_and_inline_fragments() {
+        assert_eq!(aliases("{ me { id } }"), 0);
+        assert_eq!(aliases("{ a: me { id } b: me { i: id } }"), 3);
+        assert_eq!(aliases("{ me { ... on Actor { a: id b: slug } } }"), 2);
+
+        // Fragments are counted once where they are defined, not per spread
+        assert_eq!(aliases(r#"
+            { a: me { ...Fields } b: me { ...Fields } }
+            fragment Fields on Actor { c: id }
+        "#), 3);
+    }
+
+    #[test]
+    fn count_aliases_terminates_on_fragment_cycles() {
+        assert_eq!(aliases(r#"
+            { me { ...A } }
+            fragment A on Actor { a: id ...B }
+            fragment B on Actor { b: id ...A }
+        "#), 2);
+    }
+
+    #[test]
+    fn count_root_fields_spreads_fragments_and_inline_fragments() {
+        assert_eq!(root_fields("{ me { id } }"), 1);
+        assert_eq!(root_fields("{ me { id slug } sessions { id } }"), 2);
+        assert_eq!(root_fields("{ me { id } ... on Query { a: me { id } b: me { id } } }"), 3);
+
+        assert_eq!(root_fields(r#"
+            { me { id } ...Root }
+            fragment Root on Query { a: me { id } ... on Query { b: me { id } } }
+        "#), 3);
+    }
+
+    #[test]
+    fn count_root_fields_takes_the_largest_operation() {
+        assert_eq!(root_fields(r#"
+            query One { me { id } }
+            query Three { a: me { id } b: me { id } c: me { id } }
+        "#), 3);
+    }
+
+    #[test]
+    fn count_root_fields_skips_fragment_cycles_and_unknown_fragments() {
+        assert_eq!(root_fields(r#"
+            { ...A }
+            fragment A on Query { a: me { id } ...B }
+            fragment B on Query { b: me { id } ...A }
+        "#), 2);
+
+        assert_eq!(root_fields("{ me { id } ...Missing }"), 1);
+    }
+
+    #[test]
+    fn exceeded_reports_only_the_exceeded_limits_localized() {
+        std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/..")).unwrap();
+        let locale = Locale::default();
+
+        assert!(GqlQueryLimitsExtension::exceeded(Some(&locale), &[
+            ("aliases", "query-limit-aliases", 2, Ok(())),
+        ]).is_none());
+
+        let error = GqlQueryLimitsExtension::exceeded(Some(&locale), &[
+            ("aliases", "query-limit-aliases", 31, Err(30)),
+            ("rootFields", "query-limit-root-fields", 2, Ok(())),
+        ]).unwrap();
+
+        let extensions = error.extensions.unwrap();
+        let expected = locale.lookup_with_args("query-limit-aliases", &[
+            ("actual", String::from("31")),
+            ("max", String::from("30"))
+        ]);
+
+        assert_eq!(error.message, "Query Limit Exceeded");
+        assert_eq!(extensions.get("code"), Some(&Value::from(413)));
+        assert!(expected.contains("31") && expected.contains("30"));
+
+        let Some(Value::Object(errors)) = extensions.get("errors") else {
+            panic!("errors should be keyed by limit");
+        };
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.get("aliases"), Some(&Value::String(expected)));
+    }
+
+    #[test]
+    fn exceeded_falls_back_to_english_without_a_locale() {
+        let error = GqlQueryLimitsExtension::exceeded(None, &[
+            ("depth", "query-limit-depth", 12, Err(10)),
+        ]).unwrap();
+
+        let Some(Value::Object(errors)) = error.extensions.unwrap().get("errors").cloned() else {
+            panic!("errors should be keyed by limit");
+        };
+
+        assert_eq!(errors.get("depth"), Some(&Value::from("Query exceeds the depth limit of 10")));
+    }
+}
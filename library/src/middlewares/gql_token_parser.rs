@@ -1,11 +1,15 @@
-use async_graphql::{ Request, ServerResult };
+use async_graphql::{ Data, Request, ServerError, ServerResult, Variables };
 use async_graphql::async_trait::async_trait;
-use async_graphql::extensions::{ Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest };
-use std::sync::{ Arc, RwLock };
+use async_graphql::extensions::{ Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextPrepareRequest };
+use async_graphql::parser::types::{ ExecutableDocument, OperationType };
+use std::sync::Arc;
 
+use crate::api_keys::{SCOPE_READ, SCOPE_WRITE};
 use crate::ciphers::Cipher;
 use crate::{BearerToken, ExpiredToken, InvalidToken};
-use crate::Paseto;
+use crate::{ApiKeyIdentity, Claims, Core, DBManager, Tenant, UserAgent};
+use crate::impersonation::ImpersonationAudit;
+use crate::parsers;
 use crate::prelude::{CustomRole, CustomStatus};
 
 pub struct GqlTokenParser<R, S> where R: CustomRole, S: CustomStatus {
@@ -20,6 +24,109 @@ impl <R, S> GqlTokenParser<R, S> where R: CustomRole, S: CustomStatus {
             status,
         }
     }
+
+    /// Resolve a bearer token the way every request is authenticated:
+    /// master key, api key or paseto access token bound to an active session.
+    /// The lookups run before the tenant is known and see every company.
+    pub async fn authenticate(core: &Core, bearer: &str) -> Authentication<R, S> {
+        if bearer.is_empty() {
+            return Authentication::Guest;
+        }
+
+        if Cipher::from(bearer.to_string()).is_controller() {
+            return Authentication::Controller;
+        }
+
+        // Api keys inject the owner's claims restricted by the key's scopes
+        if ApiKeyIdentity::is_api_key(bearer) {
+            return match ApiKeyIdentity::authenticate(&core.database.system(), bearer).await {
+                Ok(Some(identity)) => Authentication::Claims(Box::new(identity.to_claims())),
+                _ => Authentication::Invalid
+            };
+        }
+
+        // Clone paseto settings so the lock is not held across awaits
+        let Some(paseto) = core.paseto
+            .try_read()
+            .map(|paseto| paseto.clone())
+            .ok() else {
+            return Authentication::Guest;
+        };
+
+        // Paseto tokens are issued & sent in plain text, only the master key goes through the cipher.
+        let result: anyhow::Result<Claims<R, S>> = paseto
+            .validate_access_token(bearer);
+
+        match result {
+            Ok(claims) if claims.is_empty() => Authentication::Guest,
+            Ok(claims) => match Self::is_session_active(core, &claims).await {
+                true => Authentication::Claims(Box::new(claims)),
+                false => Authentication::Invalid
+            },
+            Err(error) => match error
+                .to_string()
+                .to_lowercase()
+                .as_str()
+            {
+                "your authentication token has expired" |
+                "your refresh token has expired" => Authentication::Expired,
+                _ => Authentication::Invalid
+            }
+        }
+    }
+
+    /// Authenticate the `connection_init` payload of a websocket subscription.
+    /// Expired or invalid tokens refuse the connection, the token is kept as
+    /// connection data so every operation is authenticated again by the extension.
+    /// Returns the database manager scoped to the tenant of the connection along with its data.
+    pub async fn connection_init(core: Arc<Core>, payload: serde_json::Value) -> async_graphql::Result<(Data, DBManager)> {
+        let mut data = Data::default();
+
+        let Some(token) = parsers::bearer_token_payload(&payload) else {
+            return Ok((data, core.database.clone()));
+        };
+
+        match Self::authenticate(&core, &token.to_string()).await {
+            Authentication::Expired => Err(core.locale.lookup("subscription-token-expired").into()),
+            Authentication::Invalid => Err(core.locale.lookup("subscription-token-invalid").into()),
+            authentication => {
+                data.insert(token);
+                Ok((data, core.database.scoped(authentication.tenant())))
+            }
+        }
+    }
+
+    /// Check that the session referenced by the claims was not revoked or expired.
+    /// Impersonation tokens live on the impersonator's session.
+    async fn is_session_active(core: &Core, claims: &Claims<R, S>) -> bool {
+        match (&claims.sid, claims.iid.as_ref().or(claims.aid.as_ref())) {
+            (Some(sid), Some(aid)) => core.sessions
+                .is_active(&core.database.system(), sid, aid)
+                .await
+                .unwrap_or(false),
+            _ => false
+        }
+    }
+}
+
+/// Outcome of authenticating a bearer token
+pub enum Authentication<R, S> where R: CustomRole, S: CustomStatus {
+    Guest,
+    Controller,
+    Claims(Box<Claims<R, S>>),
+    Expired,
+    Invalid,
+}
+
+impl<R, S> Authentication<R, S> where R: CustomRole, S: CustomStatus {
+    /// Tenant the request is scoped to, requests without valid claims see no company scoped rows
+    pub fn tenant(&self) -> Tenant {
+        match self {
+            Self::Controller => Tenant::system(),
+            Self::Claims(claims) => Tenant::from_claims(claims),
+            _ => Tenant::default()
+        }
+    }
 }
 
 impl<R, S> ExtensionFactory for GqlTokenParser<R, S> where R: CustomRole, S: CustomStatus {
@@ -45,6 +152,39 @@ impl <R, S> GqlTokenParserExtension<R, S> where R: CustomRole, S: CustomStatus {
     }
 }
 
+impl <R, S> GqlTokenParserExtension<R, S> where R: CustomRole, S: CustomStatus {
+    async fn audit(
+        ctx: &ExtensionContext<'_>,
+        core: &Core,
+        claims: &Claims<R, S>,
+        document: &ExecutableDocument,
+        query: &str,
+    ) -> ServerResult<()> {
+        let operations: Vec<_> = document.operations.iter().collect();
+
+        let operation_type = match () {
+            _ if operations.iter().any(|(_, operation)| operation.node.ty == OperationType::Mutation) => "MUTATION",
+            _ if operations.iter().any(|(_, operation)| operation.node.ty == OperationType::Subscription) => "SUBSCRIPTION",
+            _ => "QUERY"
+        };
+
+        let Some(mut audit) = ImpersonationAudit::from_claims(claims, operation_type) else {
+            return Ok(());
+        };
+
+        let operation_name = operations.iter()
+            .find_map(|(name, _)| name.map(|name| name.to_string()));
+
+        audit.set_operation_name(operation_name)
+            .set_query(query)
+            .set_ip(ctx.data_opt::<UserAgent>().and_then(|user_agent| user_agent.ip.clone()));
+
+        audit.insert(&core.database)
+            .await
+            .map_err(|_| ServerError::new(core.locale.lookup("session-impersonation-audit-failed"), None))
+    }
+}
+
 #[async_trait]
 impl<R, S> Extension for GqlTokenParserExtension<R, S> where R: CustomRole, S: CustomStatus  {
     async fn prepare_request(
@@ -53,49 +193,70 @@ impl<R, S> Extension for GqlTokenParserExtension<R, S> where R: CustomRole, S: C
         request: Request,
         next: NextPrepareRequest<'_>,
     ) -> ServerResult<Request> {
-        let token = Cipher::from(match ctx.data_opt::<BearerToken>() {
+        let bearer = match ctx.data_opt::<BearerToken>() {
             Some(token) => token.to_string(),
             None => String::new()
-        }).decrypt()
-            .unwrap_or_default()
-            .b64encode()
-            .unwrap_or_default();
+        };
+
+        let guest = R::get_guest();
+
+        let request = match ctx.data_opt::<Arc<Core>>() {
+            Some(core) => {
+                let authentication = GqlTokenParser::<R, S>::authenticate(core, &bearer).await;
 
-        let master = std::env::var("MASTER_KEY")
-            .expect("MASTER_KEY is not set");
+                // Derive the tenant once, resolvers retrieve the scoped manager with `Core::database`
+                let request = request.data(core.database.scoped(authentication.tenant()));
 
-        let role = match token == master && !token.is_empty() && !master.is_empty() {
-            true => R::get_controller(),
-            false => R::get_guest()
+                match authentication {
+                    Authentication::Guest => request.data(guest),
+                    Authentication::Controller => request.data(R::get_controller()),
+                    Authentication::Claims(claims) => request.data(claims.role.clone().unwrap_or_default()).data(*claims),
+                    Authentication::Expired => request.data(guest).data(ExpiredToken::default()),
+                    Authentication::Invalid => request.data(guest).data(InvalidToken::default())
+                }
+            },
+            None => match !bearer.is_empty() && Cipher::from(bearer.clone()).is_controller() {
+                true => request.data(R::get_controller()),
+                false => request.data(guest)
+            }
         };
 
-        let mut request = request.data(role.clone());
-
-        if role.is_guest() {
-            if let Some(paseto) = ctx.data_opt::<Arc<RwLock<Paseto>>>() {
-                if let Ok(paseto) = paseto.try_read() {
-                    let result: anyhow::Result<crate::Claims<R, S>> = paseto
-                        .clone()
-                        .validate_access_token(&token);
-
-                    match result {
-                        Ok(claims) => if !claims.is_empty() {
-                            request = request.data(claims.clone().role.unwrap_or_default()).data(claims);
-                        },
-                        Err(error) => match error
-                            .to_string()
-                            .to_lowercase()
-                            .as_str()
-                        {
-                            "your authentication token has expired" |
-                            "your refresh token has expired" => request = request.data(role).data(ExpiredToken::default()),
-                            _ => request = request.data(role).data(InvalidToken::default())
-                        }
-                    }
+        next.run(ctx, request).await
+    }
+
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        // Enforce api key scopes, session claims are unrestricted
+        if let Some(claims) = ctx.data_opt::<Claims<R, S>>() {
+            for (_, operation) in document.operations.iter() {
+                let (scope, key) = match operation.node.ty {
+                    OperationType::Mutation => (SCOPE_WRITE, "api-key-scope-write"),
+                    _ => (SCOPE_READ, "api-key-scope-read")
+                };
+
+                if !claims.has_scope(scope) {
+                    let message = match ctx.data_opt::<Arc<Core>>() {
+                        Some(core) => core.locale.lookup(key),
+                        None => String::from("Insufficient api key scope")
+                    };
+
+                    return Err(ServerError::new(message, None));
                 }
             }
+
+            // Record every impersonated request, failing closed when it cannot be recorded
+            if let Some(core) = ctx.data_opt::<Arc<Core>>() {
+                Self::audit(ctx, core, claims, &document, query).await?;
+            }
         }
 
-        next.run(ctx, request).await
+        Ok(document)
     }
-}
\ No newline at end of file
+}
@@ -1,2 +1,4 @@
 pub mod actix_token_parser;
+pub mod gql_persisted_queries;
+pub mod gql_query_limits;
 pub mod gql_token_parser;
\ No newline at end of file
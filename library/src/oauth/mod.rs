@@ -0,0 +1,147 @@
+pub mod oidc;
+
+use anyhow::Result;
+use async_graphql::async_trait::async_trait;
+use async_graphql::{Context, SimpleObject};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use subtle::ConstantTimeEq;
+
+use crate::Cipher;
+use crate::hashes::sha256;
+
+pub use oidc::OidcProvider;
+
+/// Identity provider able to sign actors in with the authorization code flow
+#[async_trait]
+pub trait OAuthProvider: Send + Sync {
+    /// Provider name stored alongside linked accounts
+    fn name(&self) -> &str;
+
+    /// Url the actor is redirected to in order to authorize
+    fn authorization_url(&self, request: &OAuthRequest, state: &str) -> String;
+
+    /// Exchange the authorization code and return the verified identity
+    async fn exchange(&self, code: &str, request: &OAuthRequest) -> Result<OAuthIdentity>;
+}
+
+/// Pending authorization, sealed into the `state` parameter so callbacks need no server side storage
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthRequest {
+    pub nonce: String,
+    pub code_verifier: String,
+    /// sha256 of the binding cookie of the browser that started the authorization
+    pub binding: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl OAuthRequest {
+    pub fn new(binding: &OAuthBinding) -> Self {
+        Self {
+            nonce: nanoid::nanoid!(32),
+            code_verifier: nanoid::nanoid!(64),
+            binding: sha256(&binding.0),
+            expires_at: Some(Utc::now() + Duration::minutes(config::OAUTH_STATE_TTL)),
+        }
+    }
+
+    /// PKCE S256 challenge of the code verifier
+    pub fn code_challenge(&self) -> String {
+        sha256(&self.code_verifier)
+    }
+
+    /// Encrypt request into an opaque state value
+    pub fn seal(&self) -> Result<String> {
+        Cipher::from(serde_json::to_string(self)?)
+            .set_as_decrypted()
+            .encrypt()?
+            .b64encode()
+    }
+
+    /// Decrypt state value, rejecting tampered or expired requests.
+    /// The state must come back to the browser that started it, otherwise anyone could sign a victim into their account.
+    pub fn unseal(state: &str, binding: Option<&OAuthBinding>) -> Result<Self> {
+        let request: Self = serde_json::from_str(&Cipher::from(state)
+            .decrypt()?
+            .to_string()?)?;
+
+        if request.expires_at.is_none_or(|expires_at| expires_at <= Utc::now()) {
+            anyhow::bail!("Your oauth request has expired");
+        }
+
+        let is_bound = binding
+            .is_some_and(|binding| bool::from(sha256(&binding.0).as_bytes().ct_eq(request.binding.as_bytes())));
+
+        if !is_bound {
+            anyhow::bail!("Your oauth request was started in another browser");
+        }
+
+        Ok(request)
+    }
+}
+
+/// Random value kept in an http only cookie while an authorization is pending
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct OAuthBinding(pub String);
+
+impl OAuthBinding {
+    pub fn generate() -> Self {
+        Self(nanoid::nanoid!(32))
+    }
+
+    pub fn get(ctx: &Context<'_>) -> Option<Self> {
+        ctx.data_opt::<Self>().cloned()
+    }
+
+    /// `Set-Cookie` value keeping the binding until the state expires
+    pub fn cookie(&self) -> String {
+        Self::set_cookie(&self.0, config::OAUTH_STATE_TTL * 60)
+    }
+
+    /// `Set-Cookie` value removing the binding once the callback used it
+    pub fn clear_cookie() -> String {
+        Self::set_cookie("", 0)
+    }
+
+    fn set_cookie(value: &str, max_age: i64) -> String {
+        format!(
+            "{}={value}; Path=/; Max-Age={max_age}; HttpOnly; Secure; SameSite=Lax",
+            config::OAUTH_BINDING_COOKIE
+        )
+    }
+}
+
+/// Verified identity returned by the provider
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthIdentity {
+    pub provider: String,
+    pub issuer: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+/// Authorization url & sealed state handed to the client
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthAuthorization {
+    pub url: String,
+    pub state: String,
+}
+
+impl OAuthAuthorization {
+    /// Start a new authorization against the provider, bound to the browser holding the binding
+    pub fn start(provider: &dyn OAuthProvider, binding: &OAuthBinding) -> Result<Self> {
+        let request = OAuthRequest::new(binding);
+        let state = request.seal()?;
+
+        Ok(Self {
+            url: provider.authorization_url(&request, &state),
+            state,
+        })
+    }
+}
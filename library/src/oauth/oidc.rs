@@ -0,0 +1,219 @@
+use anyhow::Result;
+use async_graphql::async_trait::async_trait;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use jsonwebtoken::jwk::JwkSet;
+use serde::Deserialize;
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+
+use crate::OAuth;
+use crate::oauth::{OAuthIdentity, OAuthProvider, OAuthRequest};
+
+/// Signing algorithms accepted on id tokens, symmetric & unsigned tokens are rejected
+const ALGORITHMS: [Algorithm; 9] = [
+    Algorithm::RS256, Algorithm::RS384, Algorithm::RS512,
+    Algorithm::PS256, Algorithm::PS384, Algorithm::PS512,
+    Algorithm::ES256, Algorithm::ES384, Algorithm::EdDSA,
+];
+
+/// Subset of the discovery document used by the authorization code flow
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcDiscovery {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcTokenResponse {
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OidcClaims {
+    sub: String,
+    azp: Option<String>,
+    nonce: Option<String>,
+    email: Option<String>,
+    email_verified: Option<serde_json::Value>,
+    given_name: Option<String>,
+    family_name: Option<String>,
+}
+
+/// OpenID Connect provider for any issuer publishing a discovery document.
+/// Plain http issuers are accepted so a local mock issuer can stand in for the real one.
+#[derive(Debug, Clone)]
+pub struct OidcProvider {
+    settings: OAuth,
+    discovery: OidcDiscovery,
+    jwks: JwkSet,
+    client: reqwest::Client,
+}
+
+impl OidcProvider {
+    /// Fetch discovery document & signing keys of the configured issuer
+    pub async fn discover(settings: &OAuth) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config::OAUTH_HTTP_TIMEOUT))
+            .build()?;
+
+        Self::discover_with(settings, client).await
+    }
+
+    /// Same as `discover` but with a caller provided http client
+    pub async fn discover_with(settings: &OAuth, client: reqwest::Client) -> Result<Self> {
+        if !settings.is_configured() {
+            anyhow::bail!("OAuth provider is not configured");
+        }
+
+        let issuer = settings.issuer.trim_end_matches('/');
+        let url = format!("{issuer}/.well-known/openid-configuration");
+
+        let discovery = client.get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<OidcDiscovery>()
+            .await?;
+
+        // Discovery documents must describe the issuer they were fetched from
+        if discovery.issuer.trim_end_matches('/') != issuer {
+            anyhow::bail!("OAuth issuer mismatch");
+        }
+
+        let jwks = client.get(&discovery.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<JwkSet>()
+            .await?;
+
+        Ok(Self {
+            settings: settings.clone(),
+            discovery,
+            jwks,
+            client,
+        })
+    }
+
+    pub fn discovery(&self) -> &OidcDiscovery {
+        &self.discovery
+    }
+
+    /// Verify id token signature, issuer, audience, expiry & nonce
+    fn validate_id_token(&self, id_token: &str, request: &OAuthRequest) -> Result<OidcClaims> {
+        let header = jsonwebtoken::decode_header(id_token)?;
+
+        if !ALGORITHMS.contains(&header.alg) {
+            anyhow::bail!("Unsupported id token algorithm");
+        }
+
+        // Pick the key referenced by the token, issuers with a single key may omit the kid
+        let jwk = match header.kid.as_deref() {
+            Some(kid) => self.jwks.find(kid),
+            None if self.jwks.keys.len() == 1 => self.jwks.keys.first(),
+            None => None
+        }.ok_or_else(|| anyhow::anyhow!("Unknown id token signing key"))?;
+
+        let key = DecodingKey::from_jwk(jwk)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = config::OAUTH_LEEWAY;
+        validation.set_issuer(&[&self.discovery.issuer]);
+        validation.set_audience(&[&self.settings.client_id]);
+        validation.set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+
+        let claims = jsonwebtoken::decode::<OidcClaims>(id_token, &key, &validation)?
+            .claims;
+
+        // Authorized party must be us when present
+        if claims.azp.as_ref().is_some_and(|azp| azp != &self.settings.client_id) {
+            anyhow::bail!("Invalid id token authorized party");
+        }
+
+        let is_valid_nonce = claims.nonce
+            .as_ref()
+            .is_some_and(|nonce| bool::from(nonce.as_bytes().ct_eq(request.nonce.as_bytes())));
+
+        if !is_valid_nonce {
+            anyhow::bail!("Invalid id token nonce");
+        }
+
+        Ok(claims)
+    }
+}
+
+#[async_trait]
+impl OAuthProvider for OidcProvider {
+    fn name(&self) -> &str {
+        &self.settings.provider
+    }
+
+    fn authorization_url(&self, request: &OAuthRequest, state: &str) -> String {
+        let params = [
+            ("response_type", "code"),
+            ("client_id", self.settings.client_id.as_str()),
+            ("redirect_uri", self.settings.redirect_uri.as_str()),
+            ("scope", self.settings.get_scopes()),
+            ("state", state),
+            ("nonce", request.nonce.as_str()),
+            ("code_challenge", &request.code_challenge()),
+            ("code_challenge_method", "S256"),
+        ];
+
+        let query = params.iter()
+            .map(|(key, value)| format!("{key}={}", urlencoding::encode(value)))
+            .collect::<Vec<String>>()
+            .join("&");
+
+        let endpoint = &self.discovery.authorization_endpoint;
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+
+        format!("{endpoint}{separator}{query}")
+    }
+
+    async fn exchange(&self, code: &str, request: &OAuthRequest) -> Result<OAuthIdentity> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", self.settings.redirect_uri.as_str()),
+            ("client_id", self.settings.client_id.as_str()),
+            ("code_verifier", request.code_verifier.as_str()),
+        ];
+
+        if !self.settings.client_secret.is_empty() {
+            params.push(("client_secret", self.settings.client_secret.as_str()));
+        }
+
+        let response = self.client.post(&self.discovery.token_endpoint)
+            .form(&params)
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<OidcTokenResponse>()
+            .await?;
+
+        let id_token = response.id_token
+            .ok_or_else(|| anyhow::anyhow!("Missing id token"))?;
+
+        let claims = self.validate_id_token(&id_token, request)?;
+
+        // Some issuers send the verified flag as a string
+        let email_verified = match claims.email_verified {
+            Some(serde_json::Value::Bool(verified)) => verified,
+            Some(serde_json::Value::String(verified)) => verified == "true",
+            _ => false
+        };
+
+        Ok(OAuthIdentity {
+            provider: self.settings.provider.clone(),
+            issuer: self.discovery.issuer.clone(),
+            subject: claims.sub,
+            email: claims.email.map(|email| email.to_lowercase()),
+            email_verified,
+            first_name: claims.given_name,
+            last_name: claims.family_name,
+        })
+    }
+}
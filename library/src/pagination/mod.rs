@@ -0,0 +1,336 @@
+use anyhow::Result;
+use async_graphql::connection::{self, Connection, CursorType, Edge, EmptyFields};
+use async_graphql::{Context, OutputType, SimpleObject};
+use sqlx::{Postgres, QueryBuilder};
+use std::future::Future;
+
+use crate::Errors;
+use crate::filters::{Order, SortDirection};
+
+/// Keeps encoded cursors opaque & apart from other base64 values
+const CURSOR_PREFIX: &str = "cursor:";
+
+/// Last ordering of every page, rows are listed newest first unless ordered otherwise
+const TIE_BREAKER: Order = Order { column: "cursor", direction: SortDirection::Desc };
+
+/// Position of a row, the value of its `cursor BIGSERIAL` column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageCursor(pub i64);
+
+impl CursorType for PageCursor {
+    type Error = String;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        base64_url::decode(s)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|value| value.strip_prefix(CURSOR_PREFIX).and_then(|cursor| cursor.parse().ok()))
+            .map(Self)
+            .ok_or_else(|| format!("Invalid cursor: {s}"))
+    }
+
+    fn encode_cursor(&self) -> String {
+        base64_url::encode(&format!("{CURSOR_PREFIX}{}", self.0))
+    }
+}
+
+/// Fields added to every connection
+#[derive(Debug, Default, Clone, Copy, SimpleObject)]
+pub struct PageTotal {
+    /// Rows matching the filters across every page
+    pub total_count: i64,
+}
+
+pub type PageConnection<T> = Connection<PageCursor, T, PageTotal, EmptyFields>;
+
+/// Rows paginated by their `cursor BIGSERIAL` column
+pub trait Paginated {
+    fn cursor(&self) -> i64;
+}
+
+/// Parsed relay arguments of a single page
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Page {
+    pub after: Option<i64>,
+    pub before: Option<i64>,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+    /// Whether `totalCount` was selected, skip the count query otherwise
+    pub is_counted: bool,
+}
+
+impl Page {
+    /// Rows of the page, `first` or `last` capped to the max limit
+    pub fn limit(&self) -> usize {
+        self.first
+            .or(self.last)
+            .unwrap_or(config::PAGINATION_DEFAULT_LIMIT)
+            .min(config::PAGINATION_MAX_LIMIT)
+    }
+
+    /// `last` pages read backwards from `before`
+    pub fn is_backward(&self) -> bool {
+        self.last.is_some()
+    }
+
+    /// Append keyset predicates, order & limit, rows are ordered by `orders` then newest first.
+    /// The query must already have a `WHERE` clause, e.g. `WHERE TRUE`, since predicates start with `AND`.
+    /// `scope` appends the `AND ...` predicates of the rows the caller may see, e.g. their own rows & the filter.
+    /// One extra row is fetched to tell if another page follows.
+    pub fn push_keyset<'a, S>(&self, builder: &mut QueryBuilder<'a, Postgres>, table: &str, orders: &[Order], scope: S) -> Result<()>
+        where S: Fn(&mut QueryBuilder<'a, Postgres>) -> Result<()>
+    {
+        if let Some(after) = self.after {
+            Self::push_seek(builder, table, orders, after, true, &scope)?;
+        }
+
+        if let Some(before) = self.before {
+            Self::push_seek(builder, table, orders, before, false, &scope)?;
+        }
+
+        // Backward pages read the ordering in reverse, `connection` restores it
+        let order_by = orders
+            .iter()
+            .chain([&TIE_BREAKER])
+            .map(|order| match self.is_backward() {
+                true => format!("{} {}", order.column, order.direction.reverse().as_sql()),
+                false => format!("{} {}", order.column, order.direction.as_sql())
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        builder.push(format!(" ORDER BY {order_by} LIMIT "))
+            .push_bind((self.limit() + 1) as i64);
+
+        Ok(())
+    }
+
+    /// Keep rows strictly past the cursor row in the ordering.
+    /// Cursors only carry the row position, the sort values are read back from the cursor row within `scope`,
+    /// so forged cursors never reveal rows the caller cannot see & a cursor of a deleted row ends the pagination.
+    fn push_seek<'a, S>(builder: &mut QueryBuilder<'a, Postgres>, table: &str, orders: &[Order], cursor: i64, is_after: bool, scope: &S) -> Result<()>
+        where S: Fn(&mut QueryBuilder<'a, Postgres>) -> Result<()>
+    {
+        builder.push(" AND (FALSE");
+
+        // Rows tied on the leading orders & past the cursor row on the next one
+        for index in 0..=orders.len() {
+            builder.push(" OR (TRUE");
+
+            for order in &orders[..index] {
+                builder.push(format!(" AND {} = ", order.column));
+                Self::push_cursor_value(builder, table, order.column, cursor, scope)?;
+            }
+
+            let operator = |direction: SortDirection| match (direction, is_after) {
+                (SortDirection::Asc, true) | (SortDirection::Desc, false) => ">",
+                (SortDirection::Asc, false) | (SortDirection::Desc, true) => "<"
+            };
+
+            match orders.get(index) {
+                Some(order) => {
+                    builder.push(format!(" AND {} {} ", order.column, operator(order.direction)));
+                    Self::push_cursor_value(builder, table, order.column, cursor, scope)?;
+                },
+                None => {
+                    builder.push(format!(" AND {} {} ", TIE_BREAKER.column, operator(TIE_BREAKER.direction)))
+                        .push_bind(cursor);
+                }
+            };
+
+            builder.push(")");
+        }
+
+        builder.push(")");
+
+        Ok(())
+    }
+
+    /// Sort value of the cursor row, `NULL` when the row is outside of `scope`
+    fn push_cursor_value<'a, S>(builder: &mut QueryBuilder<'a, Postgres>, table: &str, column: &str, cursor: i64, scope: &S) -> Result<()>
+        where S: Fn(&mut QueryBuilder<'a, Postgres>) -> Result<()>
+    {
+        builder.push(format!("(SELECT {column} FROM {table} WHERE cursor = "))
+            .push_bind(cursor);
+
+        scope(builder)?;
+        builder.push(")");
+
+        Ok(())
+    }
+
+    /// Build the connection from the rows fetched with `push_keyset`
+    pub fn connection<T>(&self, mut rows: Vec<T>, total_count: i64) -> PageConnection<T>
+        where T: Paginated + OutputType
+    {
+        let limit = self.limit();
+        let has_more = rows.len() > limit;
+
+        rows.truncate(limit);
+
+        // Rows past the requested cursors are not looked up, their existence is inferred from the cursors
+        let (has_previous_page, has_next_page) = match self.is_backward() {
+            true => {
+                rows.reverse();
+                (has_more, self.before.is_some())
+            },
+            false => (self.after.is_some(), has_more)
+        };
+
+        let mut connection = Connection::with_additional_fields(has_previous_page, has_next_page, PageTotal { total_count });
+
+        connection.edges.extend(rows
+            .into_iter()
+            .map(|row| Edge::new(PageCursor(row.cursor()), row)));
+
+        connection
+    }
+}
+
+/// Parse relay arguments & load a single page.
+/// The loader returns the page rows & the total count, which is only needed when `page.is_counted`.
+pub async fn paginate<T, F, R>(
+    ctx: &Context<'_>,
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    loader: F,
+) -> async_graphql::Result<PageConnection<T>>
+    where T: Paginated + OutputType,
+          F: FnOnce(Page) -> R,
+          R: Future<Output = anyhow::Result<(Vec<T>, i64)>>
+{
+    let is_counted = ctx.look_ahead()
+        .field("totalCount")
+        .exists();
+
+    connection::query(after, before, first, last, |after: Option<PageCursor>, before: Option<PageCursor>, first, last| async move {
+        let page = Page {
+            after: after.map(|cursor| cursor.0),
+            before: before.map(|cursor| cursor.0),
+            first,
+            last,
+            is_counted,
+        };
+
+        let (rows, total_count) = loader(page)
+            .await
+            .map_err(Errors::bad_request)?;
+
+        Ok::<_, async_graphql::Error>(page.connection(rows, total_count))
+    }).await
+}
+
+/// Complexity of a connection field, its selection costs once per requested row
+pub fn complexity(first: Option<i32>, last: Option<i32>, child_complexity: usize) -> usize {
+    let limit = first
+        .or(last)
+        .map_or(config::PAGINATION_DEFAULT_LIMIT, |limit| limit.max(0) as usize)
+        .min(config::PAGINATION_MAX_LIMIT);
+
+    1 + limit * child_complexity
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAME: Order = Order { column: "name", direction: SortDirection::Asc };
+    const CREATED_AT: Order = Order { column: "created_at", direction: SortDirection::Desc };
+
+    /// Scope of the rows the caller may see, as list queries pass their own conditions
+    fn scope(builder: &mut QueryBuilder<'_, Postgres>) -> Result<()> {
+        builder.push(" AND actor_id = ").push_bind("actor");
+
+        Ok(())
+    }
+
+    fn compile(page: Page, orders: &[Order]) -> String {
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM test WHERE TRUE");
+        scope(&mut builder).unwrap();
+        page.push_keyset(&mut builder, "test", orders, scope).unwrap();
+
+        builder.into_sql()
+    }
+
+    #[test]
+    fn page_cursor_round_trips() {
+        for cursor in [0, 1, 42, i64::MAX, -7] {
+            let encoded = PageCursor(cursor).encode_cursor();
+
+            assert_eq!(PageCursor::decode_cursor(&encoded), Ok(PageCursor(cursor)));
+        }
+    }
+
+    #[test]
+    fn page_cursor_is_opaque() {
+        let encoded = PageCursor(42).encode_cursor();
+
+        assert_ne!(encoded, "42");
+        assert!(!encoded.contains("42"));
+        assert_eq!(base64_url::decode(&encoded).unwrap(), b"cursor:42");
+    }
+
+    #[test]
+    fn push_keyset_defaults_to_newest_first() {
+        assert_eq!(
+            compile(Page::default(), &[]),
+            "SELECT * FROM test WHERE TRUE AND actor_id = $1 ORDER BY cursor DESC LIMIT $2"
+        );
+    }
+
+    #[test]
+    fn push_seek_without_orders_compares_cursors() {
+        let page = Page { after: Some(7), first: Some(10), ..Default::default() };
+
+        assert_eq!(
+            compile(page, &[]),
+            "SELECT * FROM test WHERE TRUE AND actor_id = $1 AND (FALSE OR (TRUE AND cursor < $2)) ORDER BY cursor DESC LIMIT $3"
+        );
+
+        let page = Page { before: Some(7), last: Some(10), ..Default::default() };
+
+        assert_eq!(
+            compile(page, &[]),
+            "SELECT * FROM test WHERE TRUE AND actor_id = $1 AND (FALSE OR (TRUE AND cursor > $2)) ORDER BY cursor ASC LIMIT $3"
+        );
+    }
+
+    #[test]
+    fn push_seek_reads_sort_values_within_scope() {
+        let page = Page { after: Some(7), ..Default::default() };
+
+        assert_eq!(
+            compile(page, &[NAME, CREATED_AT]),
+            "SELECT * FROM test WHERE TRUE AND actor_id = $1 AND (FALSE \
+                OR (TRUE AND name > (SELECT name FROM test WHERE cursor = $2 AND actor_id = $3)) \
+                OR (TRUE AND name = (SELECT name FROM test WHERE cursor = $4 AND actor_id = $5) \
+                    AND created_at < (SELECT created_at FROM test WHERE cursor = $6 AND actor_id = $7)) \
+                OR (TRUE AND name = (SELECT name FROM test WHERE cursor = $8 AND actor_id = $9) \
+                    AND created_at = (SELECT created_at FROM test WHERE cursor = $10 AND actor_id = $11) \
+                    AND cursor < $12)) \
+                ORDER BY name ASC, created_at DESC, cursor DESC LIMIT $13"
+        );
+    }
+
+    #[test]
+    fn push_seek_reverses_backward_pages() {
+        let page = Page { before: Some(7), last: Some(5), ..Default::default() };
+
+        assert_eq!(
+            compile(page, &[NAME]),
+            "SELECT * FROM test WHERE TRUE AND actor_id = $1 AND (FALSE \
+                OR (TRUE AND name < (SELECT name FROM test WHERE cursor = $2 AND actor_id = $3)) \
+                OR (TRUE AND name = (SELECT name FROM test WHERE cursor = $4 AND actor_id = $5) AND cursor > $6)) \
+                ORDER BY name DESC, cursor ASC LIMIT $7"
+        );
+    }
+
+    #[test]
+    fn page_cursor_rejects_invalid_values() {
+        for value in ["", "42", "not base64!", &base64_url::encode("42"), &base64_url::encode("cursor:"), &base64_url::encode("cursor:abc"), &base64_url::encode("other:42")] {
+            assert!(PageCursor::decode_cursor(value).is_err(), "{value} should be rejected");
+        }
+    }
+}
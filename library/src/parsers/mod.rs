@@ -1,9 +1,11 @@
 use actix_web::HttpRequest;
 use async_graphql::Request;
 use async_graphql_actix_web::GraphQLRequest;
+use std::net::IpAddr;
 use user_agent_parser::UserAgentParser;
 
 use crate::BearerToken;
+use crate::OAuthBinding;
 use crate::UserAgent;
 
 /// Parse graphql token
@@ -16,6 +18,11 @@ pub fn graphql(req: &HttpRequest, gql: GraphQLRequest, uap: &UserAgentParser) ->
         request = request.data(token);
     }
 
+    // Retrieve pending oauth authorization binding if available
+    if let Some(cookie) = req.cookie(config::OAUTH_BINDING_COOKIE) {
+        request = request.data(OAuthBinding(cookie.value().to_string()));
+    }
+
     // Retrieve user agent if available
     request = request.data(user_agent(req, uap));
 
@@ -36,6 +43,26 @@ pub fn bearer_token(req: &HttpRequest) -> Option<BearerToken> {
     None
 }
 
+/// Parse websocket connection init payload, e.g. `{ "Authorization": "Bearer …" }` or `{ "token": "…" }`
+pub fn bearer_token_payload(payload: &serde_json::Value) -> Option<BearerToken> {
+    let serde_json::Value::Object(payload) = payload else {
+        return None;
+    };
+
+    let token = ["Authorization", "authorization"]
+        .iter()
+        .find_map(|key| match payload.get(*key) {
+            Some(serde_json::Value::String(header)) => bearer_token_value(header),
+            _ => None
+        })
+        .or_else(|| match payload.get("token") {
+            Some(serde_json::Value::String(token)) if !token.trim().is_empty() => Some(token.trim().to_string()),
+            _ => None
+        });
+
+    token.map(BearerToken::new)
+}
+
 /// Extract bearer token
 fn bearer_token_value(auth_header: &str) -> Option<String> {
     const BEARER_PREFIX: &str = "Bearer ";
@@ -65,14 +92,27 @@ fn bearer_token_value(auth_header: &str) -> Option<String> {
     None
 }
 
+// Retrieve client ip, forwarded headers are client controlled unless set by a trusted proxy
+pub fn client_ip(req: &HttpRequest) -> Option<String> {
+    let peer = req.peer_addr()?.ip();
+    let is_trusted = config::TRUSTED_PROXIES
+        .iter()
+        .any(|proxy| proxy.parse::<IpAddr>().is_ok_and(|proxy| proxy == peer));
+
+    if is_trusted {
+        if let Some(ip) = req.connection_info().realip_remote_addr() {
+            return Some(ip.to_string());
+        }
+    }
+
+    Some(peer.to_string())
+}
+
 // Retrieve user agent
 pub fn user_agent(req: &HttpRequest,  uap: &UserAgentParser) -> UserAgent {
     // Retrieve ip address
     let mut user_agent = UserAgent {
-        ip: req
-            .connection_info()
-            .realip_remote_addr()
-            .map( | item| item.to_string()),
+        ip: client_ip(req),
         ..Default::default()
     };
 
@@ -221,4 +261,30 @@ pub fn ext_from_mime<T: ToString>(value: T) -> &'static str {
         "application/x-7z-compressed" => ".7z",
         _ => ""
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    #[test]
+    fn client_ip_ignores_forwarded_headers_from_untrusted_peers() {
+        let req = TestRequest::default()
+            .peer_addr("10.0.0.1:4000".parse().unwrap())
+            .insert_header(("x-forwarded-for", "1.2.3.4"))
+            .insert_header(("forwarded", "for=5.6.7.8"))
+            .to_http_request();
+
+        assert_eq!(client_ip(&req), Some(String::from("10.0.0.1")));
+    }
+
+    #[test]
+    fn client_ip_is_none_without_a_peer() {
+        let req = TestRequest::default()
+            .insert_header(("x-forwarded-for", "1.2.3.4"))
+            .to_http_request();
+
+        assert_eq!(client_ip(&req), None);
+    }
+}
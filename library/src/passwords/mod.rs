@@ -0,0 +1,182 @@
+pub mod policy;
+
+use anyhow::Result;
+use argon2::{Algorithm, Argon2, Params, PasswordHash, PasswordHasher, PasswordVerifier, Version};
+use argon2::password_hash::{rand_core::OsRng, SaltString};
+use serde::{Serialize, Deserialize};
+use std::sync::OnceLock;
+
+pub use policy::PasswordPolicy;
+
+const ALGORITHM: &str = "argon2id";
+
+/// Argon2id cost parameters used to produce a hash
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordParams {
+    pub memory_cost: u32,
+    pub time_cost: u32,
+    pub parallelism: u32,
+}
+
+impl Default for PasswordParams {
+    fn default() -> Self {
+        Self {
+            memory_cost: config::PASSWORD_MEMORY_COST,
+            time_cost: config::PASSWORD_TIME_COST,
+            parallelism: config::PASSWORD_PARALLELISM,
+        }
+    }
+}
+
+impl PasswordParams {
+    fn argon2(&self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_cost, self.time_cost, self.parallelism, None)
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+/// Password stored as JSONB, e.g. `actor.password`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Password {
+    pub algorithm: String,
+    #[serde(default)]
+    pub params: Option<PasswordParams>,
+    pub hash: String,
+}
+
+impl Password {
+    /// Hash password using the configured parameters
+    pub fn hash<T>(password: T) -> Result<Self>
+        where T: AsRef<[u8]>
+    {
+        Self::hash_with(password, PasswordParams::default())
+    }
+
+    /// Hash password using the given parameters
+    pub fn hash_with<T>(password: T, params: PasswordParams) -> Result<Self>
+        where T: AsRef<[u8]>
+    {
+        // Generate salt and hash password
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = params.argon2()?
+            .hash_password(password.as_ref(), &salt)
+            .map_err(|e| anyhow::anyhow!(e))?
+            .to_string();
+
+        Ok(Self {
+            algorithm: ALGORITHM.to_string(),
+            params: Some(params),
+            hash,
+        })
+    }
+
+    /// Verify password against the stored hash.
+    /// Parameters are read from the stored hash and the digests are compared in constant time.
+    pub fn verify<T>(&self, password: T) -> bool
+        where T: AsRef<[u8]>
+    {
+        if self.algorithm != ALGORITHM {
+            return false;
+        }
+
+        let hash = match PasswordHash::new(&self.hash) {
+            Ok(hash) => hash,
+            Err(_) => return false
+        };
+
+        Argon2::default()
+            .verify_password(password.as_ref(), &hash)
+            .is_ok()
+    }
+
+    /// Spend the cost of a verification when there is no stored password to verify against,
+    /// so unknown accounts take as long to reject as known ones.
+    pub fn verify_dummy<T>(password: T)
+        where T: AsRef<[u8]>
+    {
+        static DUMMY: OnceLock<Option<Password>> = OnceLock::new();
+
+        if let Some(dummy) = DUMMY.get_or_init(|| Self::hash("dummy-password").ok()) {
+            dummy.verify(password);
+        }
+    }
+
+    /// Check if the stored hash was produced with different parameters than the configured ones
+    pub fn needs_rehash(&self) -> bool {
+        let current = PasswordParams::default();
+
+        if self.algorithm != ALGORITHM || self.params != Some(current) {
+            return true;
+        }
+
+        // Trust the hash itself over the recorded parameters
+        let Ok(hash) = PasswordHash::new(&self.hash) else {
+            return true;
+        };
+
+        match Params::try_from(&hash) {
+            Ok(params) => {
+                hash.algorithm != Algorithm::Argon2id.ident() ||
+                params.m_cost() != current.memory_cost ||
+                params.t_cost() != current.time_cost ||
+                params.p_cost() != current.parallelism
+            },
+            Err(_) => true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheapest parameters argon2 accepts, keeps the tests fast
+    const CHEAP: PasswordParams = PasswordParams { memory_cost: 8, time_cost: 1, parallelism: 1 };
+
+    #[test]
+    fn hash_verifies_the_same_password_only() {
+        let password = Password::hash_with("correct horse", CHEAP).unwrap();
+
+        assert_eq!(password.algorithm, "argon2id");
+        assert!(password.hash.starts_with("$argon2id$v=19$m=8,t=1,p=1$"));
+        assert!(password.verify("correct horse"));
+        assert!(!password.verify("correct horse "));
+        assert!(!password.verify(""));
+    }
+
+    #[test]
+    fn hash_is_salted() {
+        let first = Password::hash_with("correct horse", CHEAP).unwrap();
+        let second = Password::hash_with("correct horse", CHEAP).unwrap();
+
+        assert_ne!(first.hash, second.hash);
+        assert!(second.verify("correct horse"));
+    }
+
+    #[test]
+    fn verify_rejects_unknown_algorithms_and_malformed_hashes() {
+        let mut password = Password::hash_with("correct horse", CHEAP).unwrap();
+        password.algorithm = String::from("bcrypt");
+
+        assert!(!password.verify("correct horse"));
+        assert!(!Password { algorithm: String::from("argon2id"), params: None, hash: String::from("not a hash") }.verify("correct horse"));
+    }
+
+    #[test]
+    fn needs_rehash_follows_the_configured_params() {
+        let current = Password::hash("correct horse").unwrap();
+        let outdated = Password::hash_with("correct horse", CHEAP).unwrap();
+
+        assert!(!current.needs_rehash());
+        assert!(outdated.needs_rehash());
+        assert!(Password { params: None, ..current.clone() }.needs_rehash());
+        assert!(Password { algorithm: String::from("bcrypt"), ..current.clone() }.needs_rehash());
+
+        // The hash wins over recorded parameters that claim to be current
+        assert!(Password { params: Some(PasswordParams::default()), ..outdated }.needs_rehash());
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+/// Password policy enforced on new passwords
+#[derive(Debug, Clone, Default)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub breached: HashSet<String>,
+}
+
+impl PasswordPolicy {
+    /// Create policy from config, loading the breached password list from `config::PASSWORD_BREACHED_PATH`.
+    /// A missing list is treated as empty.
+    pub fn init() -> Self {
+        let breached = std::fs::read_to_string(config::PASSWORD_BREACHED_PATH)
+            .map(|list| Self::parse_breached(&list))
+            .unwrap_or_default();
+
+        Self {
+            min_length: config::PASSWORD_MIN_LENGTH,
+            max_length: config::PASSWORD_MAX_LENGTH,
+            require_lowercase: config::PASSWORD_REQUIRE_LOWERCASE,
+            require_uppercase: config::PASSWORD_REQUIRE_UPPERCASE,
+            require_digit: config::PASSWORD_REQUIRE_DIGIT,
+            require_symbol: config::PASSWORD_REQUIRE_SYMBOL,
+            breached,
+        }
+    }
+
+    /// Parse one password per line, ignoring blank lines and `#` comments
+    pub fn parse_breached(list: &str) -> HashSet<String> {
+        list.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_lowercase())
+            .collect()
+    }
+
+    pub fn is_breached(&self, password: &str) -> bool {
+        self.breached.contains(&password.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_breached_skips_comments_and_blank_lines() {
+        let breached = PasswordPolicy::parse_breached("# top passwords\n\nPassword1\n  qwerty123  \n#hunter2\n");
+
+        assert_eq!(breached, HashSet::from([String::from("password1"), String::from("qwerty123")]));
+    }
+
+    #[test]
+    fn is_breached_ignores_case() {
+        let policy = PasswordPolicy {
+            breached: PasswordPolicy::parse_breached("Password1"),
+            ..Default::default()
+        };
+
+        assert!(policy.is_breached("PASSWORD1"));
+        assert!(policy.is_breached("password1"));
+        assert!(!policy.is_breached("password12"));
+    }
+
+    #[test]
+    fn init_reads_the_configured_bounds() {
+        let policy = PasswordPolicy::init();
+
+        assert_eq!(policy.min_length, config::PASSWORD_MIN_LENGTH);
+        assert_eq!(policy.max_length, config::PASSWORD_MAX_LENGTH);
+        assert_eq!(policy.require_symbol, config::PASSWORD_REQUIRE_SYMBOL);
+    }
+}
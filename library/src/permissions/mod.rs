@@ -0,0 +1,164 @@
+use anyhow::Result;
+use parking_lot::RwLock;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use crate::DBManager;
+
+/// Grants every permission
+pub const WILDCARD: &str = "*";
+
+#[derive(Debug, Default)]
+struct PermissionGrants {
+    roles: HashMap<String, HashSet<String>>,
+    loaded_at: Option<Instant>,
+}
+
+/// In-process cache of the permissions granted to each role.
+/// Loaded at `Core::init`, reloaded right after grants change in this process
+/// and once the ttl runs out so changes made by other processes are picked up.
+pub struct PermissionResolver {
+    grants: RwLock<PermissionGrants>,
+    ttl: Duration,
+}
+
+impl Default for PermissionResolver {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(config::PERMISSION_CACHE_TTL))
+    }
+}
+
+impl PermissionResolver {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            grants: RwLock::new(PermissionGrants::default()),
+            ttl,
+        }
+    }
+
+    pub async fn init(manager: &DBManager) -> Result<Self> {
+        let resolver = Self::default();
+        resolver.reload(manager).await?;
+
+        Ok(resolver)
+    }
+
+    /// Replace the cached grants with the ones stored in the database
+    pub async fn reload(&self, manager: &DBManager) -> Result<()> {
+        let rows = sqlx::query_as::<_, (String, String)>(r#"
+            SELECT role.name, permission.name FROM role_permission
+            INNER JOIN role ON role.id = role_permission.role_id
+            INNER JOIN permission ON permission.id = role_permission.permission_id
+        "#).fetch_all(manager.reader())
+            .await?;
+
+        let mut roles: HashMap<String, HashSet<String>> = HashMap::new();
+
+        for (role, permission) in rows {
+            roles.entry(role).or_default().insert(permission);
+        }
+
+        *self.grants.write() = PermissionGrants {
+            roles,
+            loaded_at: Some(Instant::now()),
+        };
+
+        Ok(())
+    }
+
+    /// Check if the role was granted the permission, reloading stale grants first
+    pub async fn has_permission(&self, manager: &DBManager, role: &str, permission: &str) -> Result<bool> {
+        let is_fresh = self.grants
+            .read()
+            .loaded_at
+            .is_some_and(|loaded_at| loaded_at.elapsed() < self.ttl);
+
+        if !is_fresh {
+            self.reload(manager).await?;
+        }
+
+        Ok(self.is_granted(role, permission))
+    }
+
+    /// Check the cached grants only, `resource:*` & `*` grants match every action of the resource or every permission
+    pub fn is_granted(&self, role: &str, permission: &str) -> bool {
+        self.grants
+            .read()
+            .roles
+            .get(role)
+            .is_some_and(|grants| grants.iter().any(|granted| Self::matches(granted, permission)))
+    }
+
+    /// Cached permissions of the role, sorted by name
+    pub fn permissions(&self, role: &str) -> Vec<String> {
+        let mut permissions: Vec<String> = self.grants
+            .read()
+            .roles
+            .get(role)
+            .map(|grants| grants.iter().cloned().collect())
+            .unwrap_or_default();
+
+        permissions.sort();
+        permissions
+    }
+
+    fn matches(granted: &str, permission: &str) -> bool {
+        if granted == WILDCARD || granted == permission {
+            return true;
+        }
+
+        match (granted.strip_suffix(":*"), permission.split_once(':')) {
+            (Some(granted), Some((resource, _))) => granted == resource,
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolver(role: &str, grants: &[&str]) -> PermissionResolver {
+        let resolver = PermissionResolver::default();
+
+        *resolver.grants.write() = PermissionGrants {
+            roles: HashMap::from([(role.to_string(), grants.iter().map(|grant| grant.to_string()).collect())]),
+            loaded_at: Some(Instant::now()),
+        };
+
+        resolver
+    }
+
+    #[test]
+    fn matches_exact_permissions() {
+        assert!(PermissionResolver::matches("company:update", "company:update"));
+        assert!(!PermissionResolver::matches("company:update", "company:delete"));
+        assert!(!PermissionResolver::matches("company", "company:update"));
+    }
+
+    #[test]
+    fn matches_every_action_of_a_resource_wildcard() {
+        assert!(PermissionResolver::matches("company:*", "company:update"));
+        assert!(PermissionResolver::matches("company:*", "company:delete"));
+        assert!(!PermissionResolver::matches("company:*", "role:read"));
+        assert!(!PermissionResolver::matches("company:*", "companies:read"));
+        assert!(!PermissionResolver::matches("company:*", "company"));
+    }
+
+    #[test]
+    fn matches_every_permission_with_the_wildcard() {
+        assert!(PermissionResolver::matches(WILDCARD, "company:update"));
+        assert!(PermissionResolver::matches(WILDCARD, "role:read"));
+    }
+
+    #[test]
+    fn is_granted_only_checks_the_grants_of_the_role() {
+        let resolver = resolver("ADMIN", &["company:*", "role:read"]);
+
+        assert!(resolver.is_granted("ADMIN", "company:update"));
+        assert!(resolver.is_granted("ADMIN", "role:read"));
+        assert!(!resolver.is_granted("ADMIN", "role:update"));
+        assert!(!resolver.is_granted("GUEST", "role:read"));
+        assert_eq!(resolver.permissions("ADMIN"), vec!["company:*", "role:read"]);
+    }
+}
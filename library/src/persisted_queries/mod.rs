@@ -0,0 +1,390 @@
+use anyhow::Result;
+use lru::LruCache;
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::{Core, DBManager};
+use crate::hashes::sha256_hex;
+
+/// Request extension carrying the hash, e.g. `{ "persistedQuery": { "version": 1, "sha256Hash": "…" } }`
+pub const EXTENSION: &str = "persistedQuery";
+
+/// Only version 1 of the automatic persisted query protocol exists
+pub const VERSION: i32 = 1;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedQuery {
+    pub version: i32,
+    pub sha256_hash: String,
+}
+
+/// Marks requests received over GET, hashed queries may be sent over GET so they can be cached
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ReadOnlyRequest;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PersistedQueryMode {
+    /// Clients register queries by sending them along with their hash once
+    #[default]
+    Automatic,
+    /// Only queries registered from the manifest file are executed
+    Strict,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistedQueryError {
+    /// Hash is unknown, clients retry with the full query
+    NotFound,
+    HashMismatch,
+    UnsupportedVersion,
+    NotAllowed,
+}
+
+/// Persisted query manifest, either the apollo operations manifest or a plain `{ hash: query }` map
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Manifest {
+    Operations { operations: Vec<ManifestOperation> },
+    Queries(HashMap<String, String>),
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestOperation {
+    id: String,
+    body: String,
+}
+
+/// Resolves persisted query hashes to query text.
+/// Queries are kept in an in-memory lru cache, optionally backed by the `persisted_query` table
+/// so every server process resolves hashes registered on any of them.
+pub struct PersistedQueries {
+    mode: PersistedQueryMode,
+    cache: Mutex<LruCache<String, String>>,
+    manifest: HashMap<String, String>,
+    is_stored: bool,
+}
+
+impl PersistedQueries {
+    pub fn new(mode: PersistedQueryMode, capacity: usize, manifest: HashMap<String, String>, is_stored: bool) -> Self {
+        Self {
+            mode,
+            cache: Mutex::new(LruCache::new(capacity)),
+            manifest,
+            is_stored,
+        }
+    }
+
+    /// Pick the mode named by `PERSISTED_QUERY_MODE` & the store named by `PERSISTED_QUERY_STORE`.
+    /// Strict mode requires the manifest, automatic mode never reads it.
+    pub fn init() -> Result<Self> {
+        let mode = std::env::var("PERSISTED_QUERY_MODE")
+            .unwrap_or_default();
+
+        let mode = match mode.to_lowercase().as_str() {
+            "" | "automatic" => PersistedQueryMode::Automatic,
+            "strict" => PersistedQueryMode::Strict,
+            _ => return Err(anyhow::anyhow!("Unknown persisted query mode: {mode}"))
+        };
+
+        let store = std::env::var("PERSISTED_QUERY_STORE")
+            .unwrap_or_default();
+
+        let is_stored = match store.to_lowercase().as_str() {
+            "" | "memory" => false,
+            "postgres" => true,
+            _ => return Err(anyhow::anyhow!("Unknown persisted query store: {store}"))
+        };
+
+        let manifest = match mode {
+            PersistedQueryMode::Strict => Self::load_manifest(config::PERSISTED_QUERY_MANIFEST_PATH)?,
+            PersistedQueryMode::Automatic => HashMap::new()
+        };
+
+        Ok(Self::new(mode, config::PERSISTED_QUERY_CACHE_CAPACITY, manifest, is_stored))
+    }
+
+    /// Read the manifest, rejecting entries whose hash does not match their query
+    pub fn load_manifest(path: &str) -> Result<HashMap<String, String>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read persisted query manifest {path}: {e}"))?;
+
+        let queries = match serde_json::from_str::<Manifest>(&content)? {
+            Manifest::Operations { operations } => operations
+                .into_iter()
+                .map(|operation| (operation.id, operation.body))
+                .collect(),
+            Manifest::Queries(queries) => queries
+        };
+
+        for (hash, query) in &queries {
+            if sha256_hex(query) != *hash {
+                return Err(anyhow::anyhow!("Persisted query manifest hash does not match its query: {hash}"));
+            }
+        }
+
+        Ok(queries)
+    }
+
+    /// Resolve the query to execute, returns none when the request query runs as sent
+    pub async fn resolve(&self, manager: &DBManager, query: &str, persisted: Option<PersistedQuery>) -> Result<Option<String>, PersistedQueryError> {
+        if persisted.as_ref().is_some_and(|persisted| persisted.version != VERSION) {
+            return Err(PersistedQueryError::UnsupportedVersion);
+        }
+
+        let hash = match (query.is_empty(), persisted) {
+            (true, Some(persisted)) => return self.lookup(manager, &persisted.sha256_hash).await.map(Some),
+            (true, None) => return Ok(None),
+            (false, Some(persisted)) => match sha256_hex(query) == persisted.sha256_hash {
+                true => persisted.sha256_hash,
+                false => return Err(PersistedQueryError::HashMismatch)
+            },
+            (false, None) => match self.mode {
+                PersistedQueryMode::Strict => sha256_hex(query),
+                PersistedQueryMode::Automatic => return Ok(None)
+            }
+        };
+
+        match self.mode {
+            PersistedQueryMode::Strict => match self.manifest.contains_key(&hash) {
+                true => Ok(None),
+                false => Err(PersistedQueryError::NotAllowed)
+            },
+            PersistedQueryMode::Automatic => {
+                self.register(manager, hash, query).await;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn lookup(&self, manager: &DBManager, hash: &str) -> Result<String, PersistedQueryError> {
+        if self.mode == PersistedQueryMode::Strict {
+            return self.manifest
+                .get(hash)
+                .cloned()
+                .ok_or(PersistedQueryError::NotAllowed);
+        }
+
+        if let Some(query) = self.cache.lock().get(hash) {
+            return Ok(query.clone());
+        }
+
+        if !self.is_stored {
+            return Err(PersistedQueryError::NotFound);
+        }
+
+        // A failed lookup is treated as a miss, the client simply resends the query
+        let query = Self::select(manager, hash)
+            .await
+            .ok()
+            .flatten()
+            .ok_or(PersistedQueryError::NotFound)?;
+
+        self.cache.lock().put(hash.to_string(), query.clone());
+
+        Ok(query)
+    }
+
+    async fn register(&self, manager: &DBManager, hash: String, query: &str) {
+        // Anyone may register, oversized queries & queries that cannot run are not worth keeping
+        if query.len() > config::PERSISTED_QUERY_MAX_LENGTH || async_graphql::parser::parse_query(query).is_err() {
+            return;
+        }
+
+        let is_cached = self.cache.lock()
+            .put(hash.clone(), query.to_string())
+            .is_some();
+
+        // The query is already valid for this request, failing to store it only costs a later miss
+        if self.is_stored && !is_cached {
+            let _ = Self::insert(manager, &hash, query).await;
+        }
+    }
+
+    /// Delete stored queries past their ttl & the oldest ones beyond the store capacity.
+    /// Clients whose query was deleted get a miss & register it again.
+    pub async fn cleanup(&self, manager: &DBManager) -> Result<u64> {
+        if !self.is_stored {
+            return Ok(0);
+        }
+
+        let result = sqlx::query(r#"
+            DELETE FROM persisted_query
+            WHERE created_at < NOW() - MAKE_INTERVAL(days => $1::INT)
+                OR id IN (SELECT id FROM persisted_query ORDER BY cursor DESC OFFSET $2)
+        "#).bind(config::PERSISTED_QUERY_STORE_TTL)
+            .bind(config::PERSISTED_QUERY_STORE_CAPACITY)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Scheduler task running `cleanup` in the background
+    pub fn cleanup_task(core: Arc<Core>) {
+        actix_rt::spawn(async move {
+            if let Err(error) = core.persisted_queries.cleanup(&core.database).await {
+                tracing::error!("Persisted query cleanup failed: {error}");
+            }
+        });
+    }
+
+    async fn select(manager: &DBManager, hash: &str) -> Result<Option<String>> {
+        let result = sqlx::query_scalar::<_, String>("SELECT query FROM persisted_query WHERE id = $1")
+            .bind(hash)
+            .fetch_optional(manager.reader())
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn insert(manager: &DBManager, hash: &str, query: &str) -> Result<()> {
+        sqlx::query("INSERT INTO persisted_query (id, query) VALUES ($1, $2) ON CONFLICT (id) DO NOTHING")
+            .bind(hash)
+            .bind(query)
+            .execute(manager.writer())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::postgres::PgPoolOptions;
+
+    use super::*;
+    use crate::Tenant;
+
+    const QUERY: &str = "{ me { id } }";
+    const OTHER: &str = "{ mySessions { id } }";
+
+    /// Memory stores never reach the database, the pools connect lazily & are never used
+    fn manager() -> DBManager {
+        let pool = PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/persisted_queries")
+            .unwrap();
+
+        DBManager {
+            reader: pool.clone(),
+            writer: pool,
+            tenant: Tenant::default(),
+        }
+    }
+
+    fn persisted<T>(query: T) -> Option<PersistedQuery>
+        where T: AsRef<[u8]>
+    {
+        Some(PersistedQuery {
+            version: VERSION,
+            sha256_hash: sha256_hex(query),
+        })
+    }
+
+    fn automatic() -> PersistedQueries {
+        PersistedQueries::new(PersistedQueryMode::Automatic, 10, HashMap::new(), false)
+    }
+
+    fn strict() -> PersistedQueries {
+        let manifest = HashMap::from([(sha256_hex(QUERY), QUERY.to_string())]);
+
+        PersistedQueries::new(PersistedQueryMode::Strict, 10, manifest, false)
+    }
+
+    /// Write a manifest to a file of its own so tests running in parallel do not share it
+    fn manifest(name: &str, content: &str) -> String {
+        let path = std::env::temp_dir().join(format!("persisted_queries_{name}_{}.json", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+
+        path.to_string_lossy().to_string()
+    }
+
+    #[tokio::test]
+    async fn resolve_rejects_a_query_that_does_not_match_its_hash() {
+        let result = automatic().resolve(&manager(), QUERY, persisted(OTHER)).await;
+
+        assert_eq!(result, Err(PersistedQueryError::HashMismatch));
+    }
+
+    #[tokio::test]
+    async fn resolve_rejects_unsupported_versions() {
+        let persisted = Some(PersistedQuery {
+            version: VERSION + 1,
+            sha256_hash: sha256_hex(QUERY),
+        });
+
+        let result = automatic().resolve(&manager(), QUERY, persisted).await;
+
+        assert_eq!(result, Err(PersistedQueryError::UnsupportedVersion));
+    }
+
+    #[tokio::test]
+    async fn resolve_registers_queries_then_looks_them_up_by_hash() {
+        let queries = automatic();
+        let manager = manager();
+
+        // Unknown hashes are a miss, the client retries with the full query
+        assert_eq!(queries.resolve(&manager, "", persisted(QUERY)).await, Err(PersistedQueryError::NotFound));
+        assert_eq!(queries.resolve(&manager, QUERY, persisted(QUERY)).await, Ok(None));
+        assert_eq!(queries.resolve(&manager, "", persisted(QUERY)).await, Ok(Some(QUERY.to_string())));
+
+        // Requests without a hash run as sent
+        assert_eq!(queries.resolve(&manager, OTHER, None).await, Ok(None));
+        assert_eq!(queries.resolve(&manager, "", persisted(OTHER)).await, Err(PersistedQueryError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn resolve_never_registers_queries_that_cannot_be_parsed() {
+        let queries = automatic();
+        let manager = manager();
+        let broken = "{ me { id }";
+
+        assert_eq!(queries.resolve(&manager, broken, persisted(broken)).await, Ok(None));
+        assert_eq!(queries.resolve(&manager, "", persisted(broken)).await, Err(PersistedQueryError::NotFound));
+    }
+
+    #[tokio::test]
+    async fn strict_mode_only_runs_manifest_queries() {
+        let queries = strict();
+        let manager = manager();
+
+        assert_eq!(queries.resolve(&manager, QUERY, None).await, Ok(None));
+        assert_eq!(queries.resolve(&manager, "", persisted(QUERY)).await, Ok(Some(QUERY.to_string())));
+
+        assert_eq!(queries.resolve(&manager, OTHER, None).await, Err(PersistedQueryError::NotAllowed));
+        assert_eq!(queries.resolve(&manager, OTHER, persisted(OTHER)).await, Err(PersistedQueryError::NotAllowed));
+        // Sending the query along with its hash never registers it
+        assert_eq!(queries.resolve(&manager, "", persisted(OTHER)).await, Err(PersistedQueryError::NotAllowed));
+    }
+
+    #[test]
+    fn load_manifest_reads_both_formats() {
+        let hash = sha256_hex(QUERY);
+
+        let queries = serde_json::json!({ hash.clone(): QUERY }).to_string();
+        let operations = serde_json::json!({ "operations": [{ "id": hash.clone(), "body": QUERY }] }).to_string();
+
+        for (name, content) in [("queries", queries), ("operations", operations)] {
+            let manifest = PersistedQueries::load_manifest(&manifest(name, &content)).unwrap();
+
+            assert_eq!(manifest.len(), 1);
+            assert_eq!(manifest.get(&hash).map(String::as_str), Some(QUERY));
+        }
+    }
+
+    #[test]
+    fn load_manifest_rejects_entries_whose_hash_does_not_match() {
+        let queries = serde_json::json!({
+            sha256_hex(QUERY): QUERY,
+            sha256_hex(OTHER): QUERY,
+        }).to_string();
+
+        let operations = serde_json::json!({ "operations": [{ "id": sha256_hex(OTHER), "body": QUERY }] }).to_string();
+
+        assert!(PersistedQueries::load_manifest(&manifest("mismatched_queries", &queries)).is_err());
+        assert!(PersistedQueries::load_manifest(&manifest("mismatched_operations", &operations)).is_err());
+        assert!(PersistedQueries::load_manifest("./missing/persisted_queries.json").is_err());
+    }
+}
@@ -6,6 +6,8 @@ pub enum Response {
     PaymentRequired,
     Forbidden,
     NotFound,
+    TooManyRequests,
+    QueryLimitExceeded,
     InternalServerError,
     ErrorWithoutExtensions,
 }
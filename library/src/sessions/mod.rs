@@ -0,0 +1,126 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::DBManager;
+use crate::cores::database::scoped::ScopedPool;
+use crate::hashes::sha256;
+
+#[derive(Debug, Clone)]
+struct SessionCacheEntry {
+    actor_id: String,
+    expires_at: Option<DateTime<Utc>>,
+    checked_at: Instant,
+}
+
+/// Short lived in-process cache of session lookups.
+/// Keeps token parsing from hitting the database on every request while
+/// revocations made by this process take effect immediately.
+pub struct SessionCache {
+    entries: Mutex<HashMap<String, SessionCacheEntry>>,
+    ttl: Duration,
+    capacity: usize,
+}
+
+impl Default for SessionCache {
+    fn default() -> Self {
+        Self::new(
+            Duration::from_secs(config::SESSION_CACHE_TTL),
+            config::SESSION_CACHE_CAPACITY
+        )
+    }
+}
+
+impl SessionCache {
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            capacity,
+        }
+    }
+
+    /// Check if session exists, belongs to the actor and has not expired
+    pub async fn is_active(&self, manager: &DBManager, sid: &str, aid: &str) -> Result<bool> {
+        // Check cached entry
+        if let Some(entry) = self.entries.lock().get(sid) {
+            if entry.checked_at.elapsed() < self.ttl {
+                return Ok(Self::is_entry_active(entry, aid));
+            }
+        }
+
+        // Retrieve session from reader, falling back to writer since replicas may lag behind a fresh sign in
+        let mut expires_at = Self::select_expires_at(manager.reader(), sid, aid).await?;
+
+        if expires_at.is_none() {
+            expires_at = Self::select_expires_at(manager.writer(), sid, aid).await?;
+        }
+
+        // Missing sessions are not cached
+        let Some(expires_at) = expires_at else {
+            return Ok(false);
+        };
+
+        let entry = SessionCacheEntry {
+            actor_id: aid.to_string(),
+            expires_at,
+            checked_at: Instant::now(),
+        };
+
+        let is_active = Self::is_entry_active(&entry, aid);
+
+        // Store entry and prune stale entries once capacity is reached
+        let mut entries = self.entries.lock();
+
+        if entries.len() >= self.capacity {
+            let ttl = self.ttl;
+            entries.retain(|_, entry| entry.checked_at.elapsed() < ttl);
+        }
+
+        if entries.len() < self.capacity {
+            entries.insert(sid.to_string(), entry);
+        }
+
+        Ok(is_active)
+    }
+
+    /// Check if the refresh token is the latest one issued to the session, rotated tokens are not.
+    /// Reads the writer so a rotation made moments ago is seen.
+    pub async fn is_current_refresh_token(&self, manager: &DBManager, sid: &str, token: &str) -> Result<bool> {
+        let result = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM session WHERE id = $1 AND refresh_token_hash = $2)"
+        ).bind(sid)
+            .bind(sha256(token))
+            .fetch_one(manager.writer())
+            .await?;
+
+        Ok(result)
+    }
+
+    /// Remove a single session from the cache
+    pub fn invalidate(&self, sid: &str) {
+        self.entries.lock().remove(sid);
+    }
+
+    /// Remove every session of an actor from the cache
+    pub fn invalidate_actor(&self, aid: &str) {
+        self.entries.lock().retain(|_, entry| entry.actor_id != aid);
+    }
+
+    async fn select_expires_at(pool: ScopedPool<'_>, sid: &str, aid: &str) -> Result<Option<Option<DateTime<Utc>>>> {
+        let result = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT expires_at FROM session WHERE id = $1 AND actor_id = $2"
+        ).bind(sid)
+            .bind(aid)
+            .fetch_optional(pool)
+            .await?;
+
+        Ok(result)
+    }
+
+    fn is_entry_active(entry: &SessionCacheEntry, aid: &str) -> bool {
+        entry.actor_id == aid && entry.expires_at.is_some_and(|expires_at| expires_at > Utc::now())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use async_graphql::SimpleObject;
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, stream};
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use sqlx::postgres::PgListener;
+use std::time::Duration;
+use tokio::sync::broadcast::{self, Sender};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::DBManager;
+
+/// Raised by `__gl_notify_settings_changed` whenever a settings module is written
+pub const SETTINGS_CHANGED: &str = "settings_changed";
+
+/// Raised by `__gl_notify_message_created` whenever a chat room message is inserted
+pub const MESSAGE_CREATED: &str = "message_created";
+
+const CHANNELS: [&str; 2] = [SETTINGS_CHANGED, MESSAGE_CREATED];
+
+/// Notifications buffered per subscriber before the slowest ones start skipping
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub channel: String,
+    pub payload: Value,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsChanged {
+    pub module: String,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageCreated {
+    pub id: String,
+    pub chat_room_id: String,
+}
+
+/// Fans database notifications out to graphql subscriptions.
+/// A single listener connection per process receives every `pg_notify`,
+/// so changes written by any server process reach every subscriber.
+pub struct Subscriptions {
+    sender: Sender<Notification>,
+}
+
+impl Subscriptions {
+    pub async fn init(manager: &DBManager) -> Result<Self> {
+        let (sender, _) = broadcast::channel(CAPACITY);
+
+        let mut listener = PgListener::connect_with(&manager.writer)
+            .await?;
+
+        listener.listen_all(CHANNELS)
+            .await?;
+
+        Self::spawn_listener(listener, sender.clone());
+
+        Ok(Self { sender })
+    }
+
+    /// Forwards notifications until the process stops, the listener reconnects on its own
+    fn spawn_listener(mut listener: PgListener, sender: Sender<Notification>) {
+        tokio::spawn(async move {
+            loop {
+                match listener.recv().await {
+                    Ok(notification) => {
+                        let Ok(payload) = serde_json::from_str(notification.payload()) else {
+                            continue;
+                        };
+
+                        // Sending only fails when nobody is subscribed
+                        let _ = sender.send(Notification {
+                            channel: notification.channel().to_string(),
+                            payload,
+                        });
+                    },
+                    Err(error) => {
+                        tracing::error!("Subscription listener failed: {error}");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Stream the payloads of a channel, subscribers that fall behind skip the missed ones
+    pub fn subscribe<T>(&self, channel: &'static str) -> impl Stream<Item = T> + Send + 'static
+        where T: DeserializeOwned + Send + 'static
+    {
+        stream::unfold(self.sender.subscribe(), move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(notification) if notification.channel == channel => {
+                        if let Ok(payload) = serde_json::from_value(notification.payload) {
+                            return Some((payload, receiver));
+                        }
+                    },
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use crate::Claims;
+use crate::prelude::{CustomRole, CustomStatus};
+
+/// Setting read by the row level security policies, the company rows are scoped to
+pub const COMPANY_SETTING: &str = "app.company_id";
+
+/// Setting read by the row level security policies, the actor whose own row stays visible
+pub const ACTOR_SETTING: &str = "app.actor_id";
+
+/// Setting letting system work through row level security, which denies every row without it
+pub const BYPASS_SETTING: &str = "app.tenant_bypass";
+
+/// Scope of the rows a database manager may see.
+/// Anonymous tenants see no scoped rows, actors their company & their own row,
+/// the controller & system work see every company.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Tenant {
+    pub company_id: Option<String>,
+    pub actor_id: Option<String>,
+    pub is_system: bool,
+}
+
+impl Tenant {
+    pub fn system() -> Self {
+        Self {
+            is_system: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn company<T>(company_id: T) -> Self
+        where T: ToString
+    {
+        Self {
+            company_id: Some(company_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Scope of the signed in actor, impersonation tokens act as the impersonated actor.
+    /// Controllers are unscoped.
+    pub fn from_claims<R, S>(claims: &Claims<R, S>) -> Self
+        where R: CustomRole,
+              S: CustomStatus
+    {
+        if claims.role.as_ref().is_some_and(|role| role.is_controller()) {
+            return Self::system();
+        }
+
+        Self {
+            company_id: claims.cid.clone(),
+            actor_id: claims.aid.clone(),
+            is_system: false,
+        }
+    }
+
+    pub fn is_system(&self) -> bool {
+        self.is_system
+    }
+}
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{ Serialize, Deserialize };
+
+use crate::Core;
+
+/// Introspection statuses, only active tokens are reported as `active: true`
+pub const ACTIVE: &str = "active";
+pub const EXPIRED: &str = "expired";
+pub const REVOKED: &str = "revoked";
+pub const INVALID: &str = "invalid";
+
+/// Token type reported for refresh tokens
+const REFRESH_TOKEN: &str = "refresh_token";
+
+/// RFC 7662 introspection request, sent as a url encoded form
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntrospectionRequest {
+    pub token: String,
+    pub token_type_hint: Option<String>,
+}
+
+/// RFC 7662 introspection response with the status & claims of the token.
+/// Claims are only reported for tokens whose signature checked out.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Introspection {
+    pub active: bool,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub claims: Option<serde_json::Value>,
+}
+
+impl Introspection {
+    pub fn invalid() -> Self {
+        Self {
+            status: INVALID.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn active(token_type: &str, payload: &serde_json::Value) -> Self {
+        Self::from_payload(ACTIVE, token_type, payload)
+    }
+
+    pub fn expired(token_type: &str, payload: &serde_json::Value) -> Self {
+        Self::from_payload(EXPIRED, token_type, payload)
+    }
+
+    /// Introspect a token issued by this app, active tokens are also checked against their session
+    pub async fn inspect(core: &Core, request: &IntrospectionRequest) -> Result<Self> {
+        // Clone paseto settings so the lock is not held across awaits
+        let paseto = core.paseto
+            .read()
+            .map_err(|_| anyhow::anyhow!("Unable to read paseto settings"))?
+            .clone();
+
+        let mut introspection = paseto.introspect(&request.token, request.token_type_hint.as_deref());
+
+        if !introspection.active {
+            return Ok(introspection);
+        }
+
+        let claim = |key: &str| introspection.claims
+            .as_ref()
+            .and_then(|claims| claims.get(key))
+            .and_then(|value| value.as_str())
+            .map(|value| value.to_string());
+
+        // Impersonation tokens live on the impersonator's session
+        let mut is_session_active = match (claim("sid"), claim("iid").or_else(|| claim("aid"))) {
+            (Some(sid), Some(aid)) => core.sessions
+                .is_active(&core.database, &sid, &aid)
+                .await?,
+            _ => false
+        };
+
+        // Refresh tokens are rotated on use, only the latest one of the session is still valid
+        if is_session_active && introspection.token_type.as_deref() == Some(REFRESH_TOKEN) {
+            is_session_active = match claim("sid") {
+                Some(sid) => core.sessions
+                    .is_current_refresh_token(&core.database, &sid, &request.token)
+                    .await?,
+                None => false
+            };
+        }
+
+        if !is_session_active {
+            introspection.active = false;
+            introspection.status = REVOKED.to_string();
+        }
+
+        Ok(introspection)
+    }
+
+    fn from_payload(status: &str, token_type: &str, payload: &serde_json::Value) -> Self {
+        let string = |key: &str| payload.get(key).and_then(|value| value.as_str()).map(|value| value.to_string());
+        let timestamp = |key: &str| payload.get(key)
+            .and_then(|value| value.as_str())
+            .and_then(|value| value.parse::<DateTime<Utc>>().ok())
+            .map(|date| date.timestamp());
+
+        Self {
+            active: status == ACTIVE,
+            status: status.to_string(),
+            token_type: Some(format!("{token_type}_token")),
+            iss: string("iss"),
+            aud: string("aud"),
+            sub: string("sub"),
+            jti: string("jti"),
+            exp: timestamp("exp"),
+            iat: timestamp("iat"),
+            nbf: timestamp("nbf"),
+            claims: payload.get("data").cloned(),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod bearer;
 pub mod expired;
+pub mod introspection;
 pub mod invalid;
 pub mod token;
\ No newline at end of file
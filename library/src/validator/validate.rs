@@ -1,4 +1,5 @@
 use crate::Validator;
+use crate::passwords::PasswordPolicy;
 
 impl Validator {
     pub fn validate_list_string(&self) -> Option<String> {
@@ -188,4 +189,145 @@ impl Validator {
             _ => None
         }
     }
-}
\ No newline at end of file
+
+    pub fn validate_email(&self) -> Option<String> {
+        // Check if string is empty
+        if self.string_value.is_empty() {
+            return Some(self.locales.lookup(format!("{}-empty", self.field)));
+        }
+
+        // Check if max has value
+        if let Some(max) = self.max {
+            if self.string_value.len() > max {
+                return Some(self.locales.lookup_with_args(
+                    format!("{}-max", self.field),
+                    &[("max", max.to_string().as_str())]
+                ));
+            }
+        }
+
+        // Check if email has a local part and a dotted domain
+        let is_valid = match self.string_value.split_once('@') {
+            Some((local, domain)) => !local.is_empty()
+                && !domain.contains('@')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && domain.contains('.')
+                && !self.string_value.contains(char::is_whitespace),
+            None => false
+        };
+
+        if !is_valid {
+            return Some(self.locales.lookup(format!("{}-invalid", self.field)));
+        }
+
+        None
+    }
+
+    pub fn validate_password(&self, policy: &PasswordPolicy) -> Option<String> {
+        // Check if password is empty
+        if self.string_value.is_empty() {
+            return Some(self.locales.lookup(format!("{}-empty", self.field)));
+        }
+
+        let password = &self.string_value;
+        let len = password.chars().count();
+
+        // Check length
+        if len < policy.min_length || len > policy.max_length {
+            return Some(self.locales.lookup_with_args(
+                format!("{}-min-max", self.field),
+                &[
+                    ("min", policy.min_length.to_string().as_str()),
+                    ("max", policy.max_length.to_string().as_str())
+                ]
+            ));
+        }
+
+        // Check character classes
+        if policy.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            return Some(self.locales.lookup(format!("{}-lowercase", self.field)));
+        }
+
+        if policy.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            return Some(self.locales.lookup(format!("{}-uppercase", self.field)));
+        }
+
+        if policy.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Some(self.locales.lookup(format!("{}-digit", self.field)));
+        }
+
+        if policy.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+            return Some(self.locales.lookup(format!("{}-symbol", self.field)));
+        }
+
+        // Check breached password list
+        if policy.is_breached(password) {
+            return Some(self.locales.lookup(format!("{}-breached", self.field)));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::MaybeUndefined;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::Locale;
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 8,
+            max_length: 20,
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: true,
+            breached: PasswordPolicy::parse_breached("Password1!"),
+        }
+    }
+
+    fn validate(password: &str) -> Option<String> {
+        std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/..")).unwrap();
+        let locale = Arc::new(Locale::default());
+
+        Validator::new(&locale, "actor-password")
+            .set_string_value(&MaybeUndefined::Value(password.to_string()))
+            .validate_password(&policy())
+    }
+
+    #[test]
+    fn validate_password_accepts_passwords_matching_the_policy() {
+        assert_eq!(validate("Tr0ub4dor&3"), None);
+    }
+
+    #[test]
+    fn validate_password_checks_length_bounds_in_characters() {
+        let bounds = Some(String::from("Your password must be between ❛8❜ and ❛20❜ characters long."));
+
+        assert_eq!(validate("Ab1!"), bounds);
+        assert_eq!(validate("Ab1!Ab1!Ab1!Ab1!Ab1!x"), bounds);
+        // Multi-byte characters count once
+        assert_eq!(validate("Ab1!éééééééééééééééé"), None);
+    }
+
+    #[test]
+    fn validate_password_requires_each_character_class() {
+        assert_eq!(validate(""), Some(String::from("Please set your password.")));
+        assert_eq!(validate("AB1!AB1!AB"), Some(String::from("Your password must contain at least one lowercase letter.")));
+        assert_eq!(validate("ab1!ab1!ab"), Some(String::from("Your password must contain at least one uppercase letter.")));
+        assert_eq!(validate("Abc!Abc!Ab"), Some(String::from("Your password must contain at least one number.")));
+        assert_eq!(validate("Abc1Abc1Ab"), Some(String::from("Your password must contain at least one symbol.")));
+    }
+
+    #[test]
+    fn validate_password_rejects_breached_passwords() {
+        assert_eq!(
+            validate("pASSWORD1!"),
+            Some(String::from("This password has appeared in a data breach. Please choose a different one."))
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Authorization code flow against a local mock OpenID Connect issuer
+
+use actix_web::{web, App, HttpResponse, HttpServer};
+use anyhow::Result;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use library::hashes::sha256;
+use library::{Cipher, KeyProvider, OAuth, OAuthAuthorization, OAuthBinding, OAuthProvider, OAuthRequest, OidcProvider};
+
+const CLIENT_ID: &str = "mock-client";
+const CODE: &str = "mock-code";
+const KID: &str = "mock-key";
+
+struct TestKeyProvider;
+
+impl KeyProvider for TestKeyProvider {
+    fn key(&self) -> Result<Vec<u8>> {
+        Ok(vec![7; 32])
+    }
+
+    fn previous_key(&self) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// What the mock issuer publishes & expects, shared with its handlers
+#[derive(Default)]
+struct Issuer {
+    url: String,
+    discovered_issuer: Option<String>,
+    signing_key: Vec<u8>,
+    public_key: Vec<u8>,
+    nonce: String,
+    code_challenge: String,
+}
+
+type State = web::Data<Arc<Mutex<Issuer>>>;
+
+async fn discovery(state: State) -> HttpResponse {
+    let issuer = state.lock().unwrap();
+    let url = &issuer.url;
+
+    HttpResponse::Ok().json(json!({
+        "issuer": issuer.discovered_issuer.clone().unwrap_or_else(|| url.clone()),
+        "authorization_endpoint": format!("{url}/authorize"),
+        "token_endpoint": format!("{url}/token"),
+        "jwks_uri": format!("{url}/jwks"),
+    }))
+}
+
+async fn jwks(state: State) -> HttpResponse {
+    let issuer = state.lock().unwrap();
+
+    HttpResponse::Ok().json(json!({
+        "keys": [{
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "use": "sig",
+            "alg": "EdDSA",
+            "kid": KID,
+            "x": base64_url::encode(&issuer.public_key),
+        }]
+    }))
+}
+
+/// Issue an id token for the expected code once the pkce verifier matches the challenge
+async fn token(state: State, form: web::Form<HashMap<String, String>>) -> HttpResponse {
+    let issuer = state.lock().unwrap();
+    let verifier = form.get("code_verifier").cloned().unwrap_or_default();
+
+    if form.get("code").map(String::as_str) != Some(CODE) || sha256(verifier) != issuer.code_challenge {
+        return HttpResponse::BadRequest().json(json!({ "error": "invalid_grant" }));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let claims = json!({
+        "iss": issuer.url,
+        "aud": CLIENT_ID,
+        "sub": "subject-1",
+        "iat": now,
+        "exp": now + 300,
+        "nonce": issuer.nonce,
+        "email": "Jane@Example.com",
+        "email_verified": "true",
+        "given_name": "Jane",
+        "family_name": "Doe",
+    });
+
+    let mut header = Header::new(Algorithm::EdDSA);
+    header.kid = Some(KID.to_string());
+
+    let id_token = jsonwebtoken::encode(&header, &claims, &EncodingKey::from_ed_der(&issuer.signing_key)).unwrap();
+
+    HttpResponse::Ok().json(json!({ "access_token": "mock", "token_type": "Bearer", "id_token": id_token }))
+}
+
+/// Start the mock issuer on a random local port
+fn start_issuer() -> (Arc<Mutex<Issuer>>, OAuth) {
+    Cipher::init(&TestKeyProvider).unwrap();
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+
+    let state = Arc::new(Mutex::new(Issuer {
+        signing_key: pkcs8.as_ref().to_vec(),
+        public_key: pair.public_key().as_ref().to_vec(),
+        ..Default::default()
+    }));
+
+    let data = web::Data::new(Arc::clone(&state));
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .route("/.well-known/openid-configuration", web::get().to(discovery))
+            .route("/jwks", web::get().to(jwks))
+            .route("/token", web::post().to(token))
+    })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+
+    let url = format!("http://{}", server.addrs()[0]);
+    state.lock().unwrap().url = url.clone();
+    actix_rt::spawn(server.run());
+
+    let settings = OAuth {
+        provider: "mock".to_string(),
+        issuer: url,
+        client_id: CLIENT_ID.to_string(),
+        redirect_uri: "http://localhost/oauth/callback".to_string(),
+        ..Default::default()
+    };
+
+    (state, settings)
+}
+
+/// Start an authorization and hand its nonce & pkce challenge to the issuer, as the browser redirect would
+fn authorize(provider: &OidcProvider, issuer: &Mutex<Issuer>, binding: &OAuthBinding) -> OAuthAuthorization {
+    let authorization = OAuthAuthorization::start(provider, binding).unwrap();
+    let query: HashMap<String, String> = authorization.url
+        .split_once('?')
+        .map(|(_, query)| query.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(key, value)| (key.to_string(), urlencoding::decode(value).unwrap().to_string()))
+            .collect())
+        .unwrap_or_default();
+
+    let mut issuer = issuer.lock().unwrap();
+    issuer.nonce = query["nonce"].clone();
+    issuer.code_challenge = query["code_challenge"].clone();
+
+    assert_eq!(query["state"], authorization.state);
+    assert_eq!(query["client_id"], CLIENT_ID);
+    assert_eq!(query["code_challenge_method"], "S256");
+
+    authorization
+}
+
+#[actix_rt::test]
+async fn callback_returns_verified_identity() {
+    let (issuer, settings) = start_issuer();
+    let provider = OidcProvider::discover_with(&settings, reqwest::Client::new()).await.unwrap();
+
+    let binding = OAuthBinding::generate();
+    let authorization = authorize(&provider, &issuer, &binding);
+
+    let request = OAuthRequest::unseal(&authorization.state, Some(&binding)).unwrap();
+    let identity = provider.exchange(CODE, &request).await.unwrap();
+
+    assert_eq!(identity.provider, "mock");
+    assert_eq!(identity.issuer, settings.issuer);
+    assert_eq!(identity.subject, "subject-1");
+    assert_eq!(identity.email.as_deref(), Some("jane@example.com"));
+    assert!(identity.email_verified);
+    assert_eq!(identity.first_name.as_deref(), Some("Jane"));
+}
+
+#[actix_rt::test]
+async fn callback_rejects_state_from_another_browser() {
+    let (issuer, settings) = start_issuer();
+    let provider = OidcProvider::discover_with(&settings, reqwest::Client::new()).await.unwrap();
+    let authorization = authorize(&provider, &issuer, &OAuthBinding::generate());
+
+    assert!(OAuthRequest::unseal(&authorization.state, None).is_err());
+    assert!(OAuthRequest::unseal(&authorization.state, Some(&OAuthBinding::generate())).is_err());
+    assert!(OAuthRequest::unseal("tampered", None).is_err());
+}
+
+#[actix_rt::test]
+async fn callback_rejects_replayed_nonce_and_wrong_code() {
+    let (issuer, settings) = start_issuer();
+    let provider = OidcProvider::discover_with(&settings, reqwest::Client::new()).await.unwrap();
+
+    let binding = OAuthBinding::generate();
+    let authorization = authorize(&provider, &issuer, &binding);
+    let request = OAuthRequest::unseal(&authorization.state, Some(&binding)).unwrap();
+
+    assert!(provider.exchange("another-code", &request).await.is_err());
+
+    // Id token issued for another authorization
+    issuer.lock().unwrap().nonce = "another-nonce".to_string();
+    assert!(provider.exchange(CODE, &request).await.is_err());
+}
+
+#[actix_rt::test]
+async fn callback_rejects_tokens_signed_with_unknown_key() {
+    let (issuer, settings) = start_issuer();
+    let provider = OidcProvider::discover_with(&settings, reqwest::Client::new()).await.unwrap();
+
+    let binding = OAuthBinding::generate();
+    let authorization = authorize(&provider, &issuer, &binding);
+    let request = OAuthRequest::unseal(&authorization.state, Some(&binding)).unwrap();
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).unwrap();
+    issuer.lock().unwrap().signing_key = pkcs8.as_ref().to_vec();
+
+    assert!(provider.exchange(CODE, &request).await.is_err());
+}
+
+#[actix_rt::test]
+async fn discovery_rejects_issuer_mismatch() {
+    let (issuer, settings) = start_issuer();
+    issuer.lock().unwrap().discovered_issuer = Some("https://evil.example.com".to_string());
+
+    assert!(OidcProvider::discover_with(&settings, reqwest::Client::new()).await.is_err());
+    assert!(OidcProvider::discover_with(&OAuth::default(), reqwest::Client::new()).await.is_err());
+}
@@ -0,0 +1,47 @@
+use proc_macro::TokenStream;
+use syn::{DeriveInput, Ident};
+
+fn impl_set_blind_index_trait(ast: DeriveInput) -> TokenStream {
+    // Get struct identifier
+    let ident = ast.ident;
+
+    // Get source & index field identifiers from `#[blind_index(index_field)]`
+    let fields = match ast.data {
+        syn::Data::Struct(data) => data.fields,
+        syn::Data::Enum(_) => panic!("Enums are not supported by set_blind_index."),
+        syn::Data::Union(_) => panic!("Unions are not supported by set_blind_index.")
+    };
+
+    let (source_idents, index_idents): (Vec<Ident>, Vec<Ident>) = fields.into_iter()
+        .filter_map(|field| {
+            let attr = field.attrs.iter().find(|attr| attr.path().is_ident("blind_index"))?;
+            let index = attr.parse_args::<Ident>()
+                .expect("Expected #[blind_index(index_field)]");
+
+            Some((field.ident?, index))
+        })
+        .unzip();
+
+    // Generate impl
+    quote::quote! {
+        impl #ident {
+            pub fn set_blind_indexes(&mut self) -> anyhow::Result<&mut Self> {
+                #(
+                    self.#index_idents = library::BlindIndexField::blind_index(&self.#source_idents)?;
+                )*
+
+                Ok(self)
+            }
+        }
+    }
+    .into()
+}
+
+#[proc_macro_derive(SetBlindIndex, attributes(blind_index))]
+pub fn set_blind_index_derive_macro(item: TokenStream) -> TokenStream {
+    // Parse
+    let ast: DeriveInput = syn::parse(item).unwrap();
+
+    // Generate
+    impl_set_blind_index_trait(ast)
+}
\ No newline at end of file